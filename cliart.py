@@ -10,35 +10,114 @@ import argparse
 import json
 from collections import defaultdict
 from parse_dotnet import parse_dotnet_project_file
-from project_parsers import parse_project_file
+from project_parsers import parse_project_file, parse_cargo_manifest
+from rust_analysis import (
+    find_rust_uses, find_rust_mod_declarations, resolve_rust_imports, rust_imported_names,
+    resolve_cargo_imports, build_crate_graph, build_lockfile_graph, build_trait_matrix, rust_base_name,
+    find_rust_types, find_rust_traits, find_rust_impls, find_rust_functions, rust_has_a_edges,
+    find_rust_macros, find_rust_macro_invocations, find_rust_item_attributes, attribute_macros, STD_MACROS,
+    rust_cfg_conditions, import_cfg_conditions, cfg_enabled, format_cfg, cargo_enabled_features,
+    cargo_dependency_conditions, find_package_manifest, build_api_surface, find_rust_unsafe, resolve_rust_calls, find_rust_symbols,
+    rust_function_key
+)
+from python_analysis import (
+    parse_python, find_python_definitions, find_python_calls, find_python_imports, python_import_strings,
+    find_python_all, resolve_python_imports, find_python_call_graph, find_python_fields, python_data_model,
+    python_has_a_edges
+)
+from js_analysis import (
+    find_js_imports, find_js_exports, js_import_strings, resolve_js_imports, find_js_import_kinds, build_component_tree
+)
+from java_analysis import (find_java_imports, java_import_string, resolve_java_imports, parse_java_declarations,
+                           find_java_beans, find_java_injection_points, java_injected_type, build_spring_graph)
+from diagram_formats import (
+    render_relation_mermaid, render_relation_dot, render_code_dot, render_directory_dot,
+    render_relation_json, render_code_json, render_directory_json, render_code_plantuml,
+    render_relation_html, render_crates_mermaid, render_crates_dot, render_crates_json,
+    render_traits_json, render_api_json, render_components_mermaid, render_components_json,
+    IMPORT_KIND_LABELS
+)
+
+# Extension of the default output file for each --format
+FORMAT_EXTENSIONS = {'ascii': '.txt', 'json': '.json', 'dot': '.dot', 'mermaid': '.mmd', 'html': '.html',
+                     'plantuml': '.puml'}
 
 def parse_arguments():
-    """Parse command line arguments."""
+    """Parse command line arguments.
+
+    Without --output a command writes to its default file name, e.g. relation_diagram, with
+    the extension of its --format.
+    """
     parser = argparse.ArgumentParser(description='CLIArt - Generate diagrams from code and directories')
     subparsers = parser.add_subparsers(dest='command', help='Command to run')
     
     # Directory command
     dir_parser = subparsers.add_parser('directory', help='Generate a directory structure diagram')
     dir_parser.add_argument('--path', required=True, help='Path to the directory to visualize')
-    dir_parser.add_argument('--output', default='directory_diagram.txt', help='Output file path')
-    dir_parser.add_argument('--format', default='ascii', choices=['ascii'], help='Output format')
+    dir_parser.add_argument('--output', help='Output file path (default: directory_diagram with the --format extension)')
+    dir_parser.set_defaults(default_output='directory_diagram')
+    dir_parser.add_argument('--format', default='ascii', choices=['ascii', 'dot', 'json'], help='Output format')
     dir_parser.add_argument('--max-depth', type=int, help='Maximum directory depth to visualize')
     
     # Code command
     code_parser = subparsers.add_parser('code', help='Generate a source code relationship diagram')
     code_parser.add_argument('--path', required=True, help='Path to the source code to visualize')
-    code_parser.add_argument('--output', default='code_diagram.txt', help='Output file path')
-    code_parser.add_argument('--format', default='ascii', choices=['ascii'], help='Output format')
+    code_parser.add_argument('--output', help='Output file path (default: code_diagram with the --format extension)')
+    code_parser.set_defaults(default_output='code_diagram')
+    code_parser.add_argument('--format', default='ascii', choices=['ascii', 'dot', 'json', 'plantuml'], help='Output format')
     code_parser.add_argument('--language', help='Programming language (auto-detect if not specified)')
     
     # Relation command
     relation_parser = subparsers.add_parser('relation', help='Generate a code relation diagram showing dependencies')
     relation_parser.add_argument('--path', required=True, help='Path to the source code or directory to analyze')
-    relation_parser.add_argument('--output', default='relation_diagram.txt', help='Output file path')
-    relation_parser.add_argument('--format', default='ascii', choices=['ascii'], help='Output format')
+    relation_parser.add_argument('--output', help='Output file path (default: relation_diagram with the --format extension)')
+    relation_parser.set_defaults(default_output='relation_diagram')
+    relation_parser.add_argument('--format', default='ascii', choices=['ascii', 'mermaid', 'dot', 'json', 'html'], help='Output format')
     relation_parser.add_argument('--depth', type=int, default=1, help='Depth of relation analysis (1-3, higher values analyze deeper relationships)')
+    relation_parser.add_argument('--features', help='Rust: only include code compiled with these Cargo features (comma or space separated)')
+    relation_parser.add_argument('--no-default-features', action='store_true', help='Rust: do not enable the default Cargo feature')
+    relation_parser.add_argument('--annotate-features', action='store_true', help='Rust: label each import edge with the cfg that enables it')
+    
+    # Crates command
+    crates_parser = subparsers.add_parser('crates', help='Generate a Cargo workspace and crate dependency graph')
+    crates_parser.add_argument('--path', required=True, help='Path to the Cargo workspace or crate')
+    crates_parser.add_argument('--output', help='Output file path (default: crates_diagram with the --format extension)')
+    crates_parser.set_defaults(default_output='crates_diagram')
+    crates_parser.add_argument('--format', default='ascii', choices=['ascii', 'mermaid', 'dot', 'json'], help='Output format')
+    
+    # Lockfile command
+    lockfile_parser = subparsers.add_parser('lockfile', help='Generate a transitive dependency tree from Cargo.lock')
+    lockfile_parser.add_argument('--path', required=True, help='Path to Cargo.lock or the directory containing it')
+    lockfile_parser.add_argument('--output', help='Output file path (default: lockfile_tree.txt)')
+    lockfile_parser.set_defaults(default_output='lockfile_tree')
+    lockfile_parser.add_argument('--invert', metavar='CRATE', help='Show the crates that depend on CRATE instead')
+    lockfile_parser.add_argument('--max-depth', type=int, help='Maximum tree depth to display')
+    
+    # Traits command
+    traits_parser = subparsers.add_parser('traits', help='Generate a Rust trait implementation matrix')
+    traits_parser.add_argument('--path', required=True, help='Path to the Rust crate or source file')
+    traits_parser.add_argument('--output', help='Output file path (default: traits_matrix with the --format extension)')
+    traits_parser.set_defaults(default_output='traits_matrix')
+    traits_parser.add_argument('--format', default='ascii', choices=['ascii', 'json'], help='Output format')
+    
+    # API surface command
+    api_parser = subparsers.add_parser('api', help='List the public API surface of Rust library crates')
+    api_parser.add_argument('--path', required=True, help='Path to the Rust crate, workspace or lib.rs')
+    api_parser.add_argument('--output', help='Output file path (default: api_surface with the --format extension)')
+    api_parser.set_defaults(default_output='api_surface')
+    api_parser.add_argument('--format', default='ascii', choices=['ascii', 'json'], help='Output format')
+    
+    # Components command
+    components_parser = subparsers.add_parser('components', help='Generate a React, Vue or Svelte component tree')
+    components_parser.add_argument('--path', required=True, help='Path to the frontend project or source file')
+    components_parser.add_argument('--output', help='Output file path (default: component_tree with the --format extension)')
+    components_parser.set_defaults(default_output='component_tree')
+    components_parser.add_argument('--format', default='ascii', choices=['ascii', 'mermaid', 'json'], help='Output format')
     
-    return parser.parse_args()
+    args = parser.parse_args()
+    if getattr(args, 'default_output', None) and args.output is None:
+        args.output = args.default_output + FORMAT_EXTENSIONS[getattr(args, 'format', 'ascii')]
+    return args
 
 def directory_command(args):
     """Generate a directory structure diagram."""
@@ -49,7 +128,12 @@ def directory_command(args):
         sys.exit(1)
     
     try:
-        diagram = create_directory_diagram(args.path, args.max_depth)
+        if args.format == 'dot':
+            diagram = render_directory_dot(build_directory_model(args.path, args.max_depth))
+        elif args.format == 'json':
+            diagram = render_directory_json(build_directory_model(args.path, args.max_depth))
+        else:
+            diagram = create_directory_diagram(args.path, args.max_depth)
         with open(args.output, 'w') as f:
             f.write(diagram)
         print(f"Success: Diagram saved to {args.output}")
@@ -66,7 +150,14 @@ def code_command(args):
         sys.exit(1)
     
     try:
-        diagram = create_code_diagram(args.path, args.language)
+        if args.format == 'dot':
+            diagram = render_code_dot(build_code_model(args.path, args.language))
+        elif args.format == 'json':
+            diagram = render_code_json(build_code_model(args.path, args.language))
+        elif args.format == 'plantuml':
+            diagram = render_code_plantuml(build_code_model(args.path, args.language))
+        else:
+            diagram = create_code_diagram(args.path, args.language)
         with open(args.output, 'w') as f:
             f.write(diagram)
         print(f"Success: Diagram saved to {args.output}")
@@ -84,6 +175,19 @@ def create_directory_diagram(path, max_depth=None):
     
     return "\n".join(result)
 
+def build_directory_model(path, max_depth=None, current_depth=0):
+    """Build a nested model of the directory structure used by structured output formats."""
+    node = {
+        'name': os.path.basename(os.path.abspath(path)),
+        'type': 'directory' if os.path.isdir(path) else 'file',
+    }
+    if node['type'] == 'directory':
+        node['children'] = []
+        if max_depth is None or current_depth < max_depth:
+            for item in sorted(os.listdir(path)):
+                node['children'].append(build_directory_model(os.path.join(path, item), max_depth, current_depth + 1))
+    return node
+
 def _process_directory_for_ascii(result, path, prefix, current_depth, max_depth):
     """Process a directory and add its contents to the ASCII diagram."""
     if max_depth is not None and current_depth >= max_depth:
@@ -106,6 +210,8 @@ def _process_directory_for_ascii(result, path, prefix, current_depth, max_depth)
 def create_code_diagram(path, language=None):
     """Create an ASCII diagram representing code relationships."""
     result = []
+    rust_types = []
+    python_classes = []
     
     if os.path.isdir(path):
         # Process directory of code files
@@ -113,25 +219,23 @@ def create_code_diagram(path, language=None):
         result.append("=" * 50)
         
         # Process all code files in the directory
-        for root, _, files in os.walk(path):
-            for file in files:
-                file_path = os.path.join(root, file)
-                file_ext = os.path.splitext(file)[1].lower()
-                
-                # Skip non-code files
-                if file_ext not in ['.py', '.js', '.java', '.cpp', '.c', '.rb', '.go']:
-                    continue
-                    
-                # Detect language from extension if not specified
-                file_language = language or detect_language_from_extension(file_ext)
-                
-                # Process the file
-                rel_path = os.path.relpath(file_path, path)
-                result.append(f"\nFile: {rel_path} ({file_language})")
-                result.append("-" * 50)
-                
-                file_structure = extract_code_structure(file_path, file_language)
-                result.extend(file_structure)
+        for file_path in collect_code_files(path):
+            file_ext = os.path.splitext(file_path)[1].lower()
+            
+            # Detect language from extension if not specified
+            file_language = language or detect_language_from_extension(file_ext)
+            
+            # Process the file
+            rel_path = os.path.relpath(file_path, path)
+            result.append(f"\nFile: {rel_path} ({file_language})")
+            result.append("-" * 50)
+            
+            file_model = extract_code_model(file_path, file_language)
+            result.extend(format_code_structure(file_model))
+            if file_language in ['rust', 'rs']:
+                rust_types.extend(file_model['classes'])
+            elif file_language == 'python':
+                python_classes.extend(file_model['classes'])
     else:
         # Process single code file
         file_ext = os.path.splitext(path)[1].lower()
@@ -140,11 +244,82 @@ def create_code_diagram(path, language=None):
         result.append(f"Code Diagram for File: {os.path.basename(path)}")
         result.append("=" * 50)
         
-        file_structure = extract_code_structure(path, file_language)
-        result.extend(file_structure)
+        file_model = extract_code_model(path, file_language)
+        result.extend(format_code_structure(file_model))
+        if file_language in ['rust', 'rs']:
+            rust_types.extend(file_model['classes'])
+        elif file_language == 'python':
+            python_classes.extend(file_model['classes'])
+    
+    # Rust structs and enums, and Python classes, that hold other project types in their fields
+    project_types = {cls['name'] for cls in rust_types if cls['kind'] in ['struct', 'enum']}
+    has_a_edges = rust_has_a_edges(rust_types, project_types)
+    has_a_edges += python_has_a_edges(python_classes, {cls['name'] for cls in python_classes})
+    if has_a_edges:
+        result.append("\nHas-a Relationships:")
+        for owner, field, target in has_a_edges:
+            result.append(f"  └── {owner} has a {target} (field {field})")
     
     return "\n".join(result)
 
+def collect_code_files(path):
+    """Collect the code files in a directory that the code command analyzes."""
+    code_files = []
+    for root, _, files in os.walk(path):
+        for file in files:
+            file_ext = os.path.splitext(file)[1].lower()
+            
+            # Skip non-code files
+            if file_ext not in ['.py', '.js', '.jsx', '.ts', '.tsx', '.java', '.cpp', '.c', '.rs', '.rb', '.go']:
+                continue
+            
+            code_files.append(os.path.join(root, file))
+    return code_files
+
+def build_code_model(path, language=None):
+    """Analyze code files and return the symbol model used by structured output formats."""
+    model = {
+        'root': os.path.basename(os.path.abspath(path)),
+        'files': []
+    }
+    
+    code_files = collect_code_files(path) if os.path.isdir(path) else [path]
+    for file_path in code_files:
+        file_ext = os.path.splitext(file_path)[1].lower()
+        file_language = language or detect_language_from_extension(file_ext)
+        rel_path = os.path.relpath(file_path, path) if os.path.isdir(path) else os.path.basename(file_path)
+        
+        relations, symbol_types, definitions = analyze_internal_relations(file_path, file_language)
+        model['files'].append({
+            'path': rel_path,
+            'language': file_language,
+            'symbol_types': symbol_types,
+            'symbols': symbol_definitions(file_path, symbol_types, definitions),
+            'relations': dict(relations),
+            'structure': extract_code_model(file_path, file_language)
+        })
+    
+    # Rust structs and enums that hold other project types get "has a" relations
+    rust_files = [file_info for file_info in model['files'] if file_info['language'] in ['rust', 'rs']]
+    project_types = {cls['name'] for file_info in rust_files for cls in file_info['structure']['classes']
+                     if cls['kind'] in ['struct', 'enum']}
+    for file_info in rust_files:
+        for owner, _, target in rust_has_a_edges(file_info['structure']['classes'], project_types):
+            owner_relations = file_info['relations'].setdefault(owner, [])
+            if f"has a {target}" not in owner_relations:
+                owner_relations.append(f"has a {target}")
+    
+    # Python classes with fields annotated with other project classes do too
+    python_files = [file_info for file_info in model['files'] if file_info['language'] == 'python']
+    project_classes = {cls['name'] for file_info in python_files for cls in file_info['structure']['classes']}
+    for file_info in python_files:
+        for owner, _, target in python_has_a_edges(file_info['structure']['classes'], project_classes):
+            owner_relations = file_info['relations'].setdefault(owner, [])
+            if f"has a {target}" not in owner_relations:
+                owner_relations.append(f"has a {target}")
+    
+    return model
+
 def detect_language_from_extension(ext):
     """Detect programming language from file extension."""
     languages = {
@@ -152,8 +327,12 @@ def detect_language_from_extension(ext):
         '.py': 'python',
         '.js': 'javascript',
         '.jsx': 'javascript',
+        '.mjs': 'javascript',
+        '.cjs': 'javascript',
         '.ts': 'typescript',
         '.tsx': 'typescript',
+        '.mts': 'typescript',
+        '.cts': 'typescript',
         '.html': 'html',
         '.css': 'css',
         '.scss': 'scss',
@@ -349,66 +528,97 @@ def detect_language_from_extension(ext):
 
 def extract_code_structure(file_path, language):
     """Extract code structure from a file based on language."""
-    result = []
+    return format_code_structure(extract_code_model(file_path, language))
+
+def find_block_end(content, open_pos):
+    """Return the position of the brace that closes the block opened at open_pos."""
+    depth = 0
+    for i in range(open_pos, len(content)):
+        if content[i] == '{':
+            depth += 1
+        elif content[i] == '}':
+            depth -= 1
+            if depth == 0:
+                return i
+    return len(content)
+
+def _line_of(content, pos):
+    """Return the 1-based line number of a position in the content."""
+    return content.count('\n', 0, pos) + 1
+
+def _class_member_owner(content, class_blocks, pos, name):
+    """Return the class whose body directly declares the member at pos, if any."""
+    if name in ['if', 'for', 'while', 'switch', 'catch', 'function', 'return', 'super']:
+        return None
+    owner = _innermost_owner(class_blocks, pos)
+    if owner:
+        block_start = max(start for start, end, block_name in class_blocks if block_name == owner and start < pos < end)
+        body = content[block_start + 1:pos]
+        # Members sit at the top level of the class body, not inside a method
+        if body.count('{') != body.count('}'):
+            return None
+    return owner
+
+def _innermost_owner(blocks, pos):
+    """Return the owner of the innermost block (start, end, owner) that contains pos."""
+    owner = None
+    owner_start = -1
+    for start, end, name in blocks:
+        if start < pos < end and start > owner_start:
+            owner = name
+            owner_start = start
+    return owner
+
+def extract_code_model(file_path, language):
+    """Extract a structured model of the classes, functions and imports in a file.
+    
+    Classes, interfaces, structs, enums and traits all go in 'classes' with their 'kind'.
+    Functions and methods go in 'functions'; 'owner' names the enclosing type and
+    'listed' is False for members the ASCII diagram leaves out.
+    """
+    model = {
+        'language': language,
+        'package': None,
+        'namespaces': [],
+        'imports': [],
+        'classes': [],
+        'functions': [],
+        'impls': [],
+        'macros': [],
+        'macro_calls': [],
+        'unsafe': [],
+        'variables': [],
+        'line_count': 0,
+        'non_empty': 0,
+        'error': None
+    }
     
     try:
         with open(file_path, 'r', encoding='utf-8', errors='ignore') as f:
             content = f.read()
         
         if language == 'python':
-            # Extract Python code structure
-            classes = {}
-            standalone_functions = []
-            
-            # Find classes
-            class_pattern = r'class\s+(\w+)(?:\(([^)]+)\))?\s*:'
-            class_matches = re.finditer(class_pattern, content)
-            
-            for match in class_matches:
-                class_name = match.group(1)
-                parent_class = match.group(2) if match.group(2) else None
-                classes[class_name] = {'parent': parent_class, 'methods': []}
-            
-            # Find methods and functions
-            func_pattern = r'def\s+(\w+)\s*\(([^)]*)\)\s*:'
-            func_matches = re.finditer(func_pattern, content)
-            
-            for match in func_matches:
-                func_name = match.group(1)
-                func_params = match.group(2)
-                
-                # Check if this is a method (indented inside a class)
-                is_method = False
-                for class_name in classes:
-                    if re.search(rf'class\s+{class_name}.*?\n\s+def\s+{func_name}\s*\(', content, re.DOTALL):
-                        classes[class_name]['methods'].append((func_name, func_params))
-                        is_method = True
-                        break
-                
-                if not is_method:
-                    standalone_functions.append((func_name, func_params))
-            
-            # Format the output
-            for class_name, class_info in classes.items():
-                parent_str = f" extends {class_info['parent']}" if class_info['parent'] else ""
-                result.append(f"\nClass: {class_name}{parent_str}")
-                
-                if class_info['methods']:
-                    for method_name, method_params in class_info['methods']:
-                        result.append(f"  └── Method: {method_name}({method_params})")
-            
-            if standalone_functions:
-                result.append("\nFunctions:")
-                for func_name, func_params in standalone_functions:
-                    result.append(f"  └── {func_name}({func_params})")
+            # Python code structure comes straight from the syntax tree
+            tree = parse_python(content, file_path)
+            python_classes, python_functions = find_python_definitions(tree)
+            model['imports'] = [imp for imported in find_python_imports(tree) for imp in python_import_strings(imported)]
+            
+            for cls in python_classes:
+                model['classes'].append({'name': cls['name'], 'kind': 'class', 'line': cls['line'],
+                                         'extends': ', '.join(cls['bases']) or None, 'implements': None,
+                                         'qualname': cls['qualname'], 'decorators': cls['decorators'],
+                                         'data_model': python_data_model(cls), 'fields': find_python_fields(cls)})
+            
+            for func in python_functions:
+                model['functions'].append({'name': func['name'], 'params': func['params'], 'line': func['line'],
+                                           'owner': func['owner'], 'listed': True, 'scope': func['scope'],
+                                           'parent': func['parent'], 'qualname': func['qualname'],
+                                           'is_async': func['is_async'], 'decorators': func['decorators'],
+                                           'returns': func['returns']})
         
         elif language in ['javascript', 'js', 'typescript', 'ts']:
             # JavaScript/TypeScript parsing
-            classes = []
-            functions = []
-            interfaces = []
-            variables = []
-            imports = []
+            class_blocks = []
             
             # Find imports (ES6 style)
             import_patterns = [
@@ -420,9 +630,9 @@ def extract_code_structure(file_path, language):
             for pattern in import_patterns:
                 for match in re.finditer(pattern, content):
                     if pattern == import_patterns[0]:  # Named imports
-                        imports.append(f"Import: {{{match.group(1)}}} from '{match.group(2)}'")
+                        model['imports'].append(f"{{{match.group(1)}}} from '{match.group(2)}'")
                     else:  # Default or namespace import
-                        imports.append(f"Import: {match.group(1)} from '{match.group(2)}'")
+                        model['imports'].append(f"{match.group(1)} from '{match.group(2)}'")
             
             # Find classes (ES6 style)
             class_pattern = r'(?:export\s+)?class\s+(\w+)(?:\s+extends\s+(\w+))?(?:\s+implements\s+([\w,\s]+))?\s*{'
@@ -430,16 +640,9 @@ def extract_code_structure(file_path, language):
             
             for match in class_matches:
                 class_name = match.group(1)
-                parent_class = match.group(2) if match.group(2) else None
-                implements = match.group(3) if match.group(3) else None
-                
-                class_info = f"Class: {class_name}"
-                if parent_class:
-                    class_info += f" extends {parent_class}"
-                if implements:
-                    class_info += f" implements {implements}"
-                    
-                classes.append(class_info)
+                model['classes'].append({'name': class_name, 'kind': 'class', 'line': _line_of(content, match.start()),
+                                         'extends': match.group(2), 'implements': match.group(3)})
+                class_blocks.append((match.end() - 1, find_block_end(content, match.end() - 1), class_name))
             
             # Find interfaces (TypeScript)
             if language in ['typescript', 'ts']:
@@ -447,14 +650,8 @@ def extract_code_structure(file_path, language):
                 interface_matches = re.finditer(interface_pattern, content)
                 
                 for match in interface_matches:
-                    interface_name = match.group(1)
-                    extends = match.group(2) if match.group(2) else None
-                    
-                    interface_info = f"Interface: {interface_name}"
-                    if extends:
-                        interface_info += f" extends {extends}"
-                        
-                    interfaces.append(interface_info)
+                    model['classes'].append({'name': match.group(1), 'kind': 'interface', 'line': _line_of(content, match.start()),
+                                             'extends': match.group(2), 'implements': None})
             
             # Find functions (various styles)
             func_patterns = [
@@ -470,165 +667,100 @@ def extract_code_structure(file_path, language):
                 for match in re.finditer(pattern, content):
                     func_name = match.group(1)
                     func_params = match.group(2)
+                    listed = True
+                    owner = None
                     
                     # Skip if this is likely a class method (already captured in class)
                     if pattern in [func_patterns[3], func_patterns[4]]:
                         # Check if this is a class method
-                        class_method = False
                         for class_match in re.finditer(r'class\s+(\w+)', content):
                             class_name = class_match.group(1)
                             if re.search(rf'class\s+{class_name}.*?{func_name}\s*\(', content, re.DOTALL):
-                                class_method = True
+                                listed = False
                                 break
-                        if class_method:
-                            continue
                         
-                    functions.append(f"Function: {func_name}({func_params})")
+                        owner = _class_member_owner(content, class_blocks, match.start(), func_name)
+                        if not listed and not owner:
+                            continue
+                    
+                    model['functions'].append({'name': func_name, 'params': func_params, 'line': _line_of(content, match.start()),
+                                               'owner': owner, 'listed': listed})
             
             # Find top-level variables
             var_pattern = r'(?:export\s+)?(?:const|let|var)\s+(\w+)\s*=\s*(?!function|\(.*\)\s*=>)'
             for match in re.finditer(var_pattern, content):
-                var_name = match.group(1)
-                variables.append(f"Variable: {var_name}")
-            
-            # Format the output
-            if imports:
-                result.append("\nImports:")
-                for imp in imports[:5]:  # Limit to first 5 imports to avoid clutter
-                    result.append(f"  └── {imp}")
-                if len(imports) > 5:
-                    result.append(f"  └── ... and {len(imports) - 5} more imports")
-            
-            if interfaces:
-                result.append("\nInterfaces:")
-                for interface in interfaces:
-                    result.append(f"  └── {interface}")
-            
-            if classes:
-                result.append("\nClasses:")
-                for cls in classes:
-                    result.append(f"  └── {cls}")
-            
-            if functions:
-                result.append("\nFunctions:")
-                for func in functions:
-                    result.append(f"  └── {func}")
-            
-            if variables:
-                result.append("\nTop-level Variables:")
-                for var in variables[:10]:  # Limit to first 10 variables
-                    result.append(f"  └── {var}")
-                if len(variables) > 10:
-                    result.append(f"  └── ... and {len(variables) - 10} more variables")
+                model['variables'].append(match.group(1))
         
         elif language in ['rust', 'rs']:
             # Rust parsing
-            structs = []
-            enums = []
-            traits = []
-            impls = []
-            functions = []
-            
-            # Find structs
-            struct_pattern = r'(?:pub\s+)?struct\s+(\w+)(?:<[^>]*>)?\s*(?:\{|;)'
-            struct_matches = re.finditer(struct_pattern, content)
-            for match in struct_matches:
-                struct_name = match.group(1)
-                structs.append(f"Struct: {struct_name}")
-            
-            # Find enums
-            enum_pattern = r'(?:pub\s+)?enum\s+(\w+)(?:<[^>]*>)?\s*\{'
-            enum_matches = re.finditer(enum_pattern, content)
-            for match in enum_matches:
-                enum_name = match.group(1)
-                enums.append(f"Enum: {enum_name}")
+            blocks = []
+            
+            # Find structs and enums with their fields and variants
+            rust_types = find_rust_types(content)
+            for kind in ['struct', 'enum']:
+                for rust_type in rust_types:
+                    if rust_type['kind'] == kind:
+                        model['classes'].append({'name': rust_type['name'], 'kind': kind, 'line': rust_type['line'],
+                                                 'extends': None, 'implements': None, 'visibility': rust_type['visibility'],
+                                                 'shape': rust_type['shape'], 'fields': rust_type['fields'],
+                                                 'variants': rust_type['variants'], 'derives': rust_type['derives'],
+                                                 'attributes': attribute_macros(rust_type['attributes'])})
             
             # Find traits
-            trait_pattern = r'(?:pub\s+)?trait\s+(\w+)(?:<[^>]*>)?(?:\s*:\s*([^{]*))?\s*\{'
-            trait_matches = re.finditer(trait_pattern, content)
-            for match in trait_matches:
-                trait_name = match.group(1)
-                trait_bounds = match.group(2).strip() if match.group(2) else None
-                trait_info = f"Trait: {trait_name}"
-                if trait_bounds:
-                    trait_info += f" : {trait_bounds}"
-                traits.append(trait_info)
+            for trait in find_rust_traits(content):
+                model['classes'].append({'name': trait['name'], 'kind': 'trait', 'line': trait['line'],
+                                         'extends': ' + '.join(trait['supertraits']) or None, 'implements': None,
+                                         'required': trait['required'], 'provided': trait['provided'],
+                                         'attributes': attribute_macros(trait['attributes'])})
             
             # Find implementations
-            impl_pattern = r'impl(?:<[^>]*>)?\s+(?:([^\s{]+)\s+for\s+)?([^\s{<]+)(?:<[^>]*>)?\s*\{'
-            impl_matches = re.finditer(impl_pattern, content)
-            for match in re.finditer(impl_pattern, content):
-                trait_name = match.group(1) if match.group(1) else None
-                type_name = match.group(2)
-                if trait_name:
-                    impls.append(f"Impl: {trait_name} for {type_name}")
-                else:
-                    impls.append(f"Impl: {type_name}")
-            
-            # Find functions
-            func_pattern = r'(?:pub(?:\([^)]*\))?\s+)?(?:async\s+)?fn\s+(\w+)(?:<[^>]*>)?\s*\(([^)]*)\)'
-            func_matches = re.finditer(func_pattern, content)
-            for match in func_matches:
-                func_name = match.group(1)
-                func_params = match.group(2)
-                functions.append(f"Function: {func_name}({func_params})")
-            
-            # Format the output
-            if structs:
-                result.append("\nStructs:")
-                for struct in structs:
-                    result.append(f"  └── {struct}")
-            
-            if enums:
-                result.append("\nEnums:")
-                for enum in enums:
-                    result.append(f"  └── {enum}")
-            
-            if traits:
-                result.append("\nTraits:")
-                for trait in traits:
-                    result.append(f"  └── {trait}")
-            
-            if impls:
-                result.append("\nImplementations:")
-                for impl in impls:
-                    result.append(f"  └── {impl}")
-            
-            if functions:
-                result.append("\nFunctions:")
-                for func in functions:
-                    result.append(f"  └── {func}")
+            for impl in find_rust_impls(content):
+                model['impls'].append({'trait': impl['trait'], 'type': rust_base_name(impl['type']), 'line': impl['line'],
+                                       'attributes': attribute_macros(impl['attributes'])})
+            
+            # Find functions; impl methods and trait methods are owned by their block
+            for func in find_rust_functions(content):
+                model['functions'].append({'name': func['name'], 'params': func['params'], 'line': func['line'],
+                                           'owner': func['owner'], 'listed': True, 'receiver': func['receiver'],
+                                           'trait': func['trait'], 'container': func['container'],
+                                           'block_line': func['block_line'], 'attributes': attribute_macros(func['attributes'])})
+            
+            # Find macro_rules! definitions and count macro invocations
+            for macro in find_rust_macros(content):
+                model['macros'].append({'name': macro['name'], 'exported': macro['exported'], 'line': macro['line']})
+            calls = {}
+            for path, _ in find_rust_macro_invocations(content):
+                calls[path] = calls.get(path, 0) + 1
+            model['macro_calls'] = [{'name': path, 'count': count} for path, count in calls.items()]
+            
+            # Find unsafe code and FFI boundaries
+            model['unsafe'] = [{key: value for key, value in site.items() if key != 'position'}
+                               for site in find_rust_unsafe(content)]
                     
         elif language in ['cpp', 'c++', 'c']:
             # C/C++ parsing
-            classes = []
-            structs = []
-            functions = []
-            namespaces = []
+            blocks = []
             
             # Find namespaces (C++ only)
             if language in ['cpp', 'c++']:
                 namespace_pattern = r'namespace\s+(\w+)\s*\{'
                 for match in re.finditer(namespace_pattern, content):
-                    namespace_name = match.group(1)
-                    namespaces.append(f"Namespace: {namespace_name}")
+                    model['namespaces'].append(match.group(1))
             
             # Find classes (C++ only)
             if language in ['cpp', 'c++']:
                 class_pattern = r'(?:class|struct)\s+(\w+)(?:\s*:\s*(?:public|protected|private)\s+([^{]*))?\s*\{'
                 for match in re.finditer(class_pattern, content):
-                    class_name = match.group(1)
                     inheritance = match.group(2).strip() if match.group(2) else None
-                    class_info = f"Class: {class_name}"
-                    if inheritance:
-                        class_info += f" : {inheritance}"
-                    classes.append(class_info)
+                    model['classes'].append({'name': match.group(1), 'kind': 'class', 'line': _line_of(content, match.start()),
+                                             'extends': inheritance, 'implements': None})
+                    blocks.append((match.end() - 1, find_block_end(content, match.end() - 1), match.group(1)))
             
             # Find structs (C)
             struct_pattern = r'struct\s+(\w+)\s*\{'
             for match in re.finditer(struct_pattern, content):
-                struct_name = match.group(1)
-                structs.append(f"Struct: {struct_name}")
+                model['classes'].append({'name': match.group(1), 'kind': 'struct', 'line': _line_of(content, match.start()),
+                                         'extends': None, 'implements': None})
             
             # Find functions
             func_pattern = r'(?:(?:static|inline|extern)\s+)?(?:[\w:]+\s+)+([\w:]+)\s*\(([^)]*)\)\s*(?:\{|;)'
@@ -637,145 +769,280 @@ def extract_code_structure(file_path, language):
                 func_params = match.group(2)
                 # Skip if this is likely a constructor/destructor or operator overload
                 if re.match(r'^[~]?\w+$', func_name) and not re.match(r'^operator', func_name):
-                    functions.append(f"Function: {func_name}({func_params})")
-            
-            # Format the output
-            if namespaces:
-                result.append("\nNamespaces:")
-                for namespace in namespaces:
-                    result.append(f"  └── {namespace}")
-            
-            if classes:
-                result.append("\nClasses:")
-                for cls in classes:
-                    result.append(f"  └── {cls}")
-            
-            if structs:
-                result.append("\nStructs:")
-                for struct in structs:
-                    result.append(f"  └── {struct}")
-            
-            if functions:
-                result.append("\nFunctions:")
-                for func in functions[:15]:  # Limit to 15 functions to avoid clutter
-                    result.append(f"  └── {func}")
-                if len(functions) > 15:
-                    result.append(f"  └── ... and {len(functions) - 15} more functions")
+                    model['functions'].append({'name': func_name, 'params': func_params, 'line': _line_of(content, match.start()),
+                                               'owner': _innermost_owner(blocks, match.start()), 'listed': True})
                     
         elif language in ['java']:
             # Java parsing
-            classes = []
-            interfaces = []
-            enums = []
-            methods = []
-            imports = []
-            package = None
-            
             # Find package declaration
             package_pattern = r'package\s+([\w.]+)\s*;'
             package_match = re.search(package_pattern, content)
             if package_match:
-                package = package_match.group(1)
+                model['package'] = package_match.group(1)
             
             # Find imports
             import_pattern = r'import\s+(?:static\s+)?([\w.*]+)\s*;'
             for match in re.finditer(import_pattern, content):
-                import_name = match.group(1)
-                imports.append(f"Import: {import_name}")
-            
-            # Find classes
-            class_pattern = r'(?:public|protected|private)?\s*(?:abstract|final)?\s*class\s+(\w+)(?:\s+extends\s+(\w+))?(?:\s+implements\s+([^{]*))?\s*\{'
-            for match in re.finditer(class_pattern, content):
-                class_name = match.group(1)
-                extends = match.group(2) if match.group(2) else None
-                implements = match.group(3).strip() if match.group(3) else None
-                
-                class_info = f"Class: {class_name}"
-                if extends:
-                    class_info += f" extends {extends}"
-                if implements:
-                    class_info += f" implements {implements}"
-                classes.append(class_info)
-            
-            # Find interfaces
-            interface_pattern = r'(?:public|protected|private)?\s*interface\s+(\w+)(?:\s+extends\s+([^{]*))?\s*\{'
-            for match in re.finditer(interface_pattern, content):
-                interface_name = match.group(1)
-                extends = match.group(2).strip() if match.group(2) else None
-                
-                interface_info = f"Interface: {interface_name}"
-                if extends:
-                    interface_info += f" extends {extends}"
-                interfaces.append(interface_info)
-            
-            # Find enums
-            enum_pattern = r'(?:public|protected|private)?\s*enum\s+(\w+)(?:\s+implements\s+([^{]*))?\s*\{'
-            for match in re.finditer(enum_pattern, content):
-                enum_name = match.group(1)
-                implements = match.group(2).strip() if match.group(2) else None
-                
-                enum_info = f"Enum: {enum_name}"
-                if implements:
-                    enum_info += f" implements {implements}"
-                enums.append(enum_info)
-            
-            # Find methods
-            method_pattern = r'(?:public|protected|private|static|final|abstract|synchronized)?\s+(?:static\s+)?[\w<>\[\],\s]+\s+(\w+)\s*\(([^)]*)\)\s*(?:throws\s+[\w,\s]+)?\s*(?:\{|;)'
-            for match in re.finditer(method_pattern, content):
-                method_name = match.group(1)
-                method_params = match.group(2)
-                # Skip if this is likely a constructor
-                if not any(method_name == class_name for class_name in [m.split(":")[1].strip() for m in classes]):
-                    methods.append(f"Method: {method_name}({method_params})")
-            
-            # Format the output
-            if package:
-                result.append(f"\nPackage: {package}")
-            
-            if imports:
-                result.append("\nImports:")
-                for imp in imports[:5]:  # Limit to first 5 imports
-                    result.append(f"  └── {imp}")
-                if len(imports) > 5:
-                    result.append(f"  └── ... and {len(imports) - 5} more imports")
-            
-            if classes:
-                result.append("\nClasses:")
-                for cls in classes:
-                    result.append(f"  └── {cls}")
-            
-            if interfaces:
-                result.append("\nInterfaces:")
-                for interface in interfaces:
-                    result.append(f"  └── {interface}")
-            
-            if enums:
-                result.append("\nEnums:")
-                for enum in enums:
-                    result.append(f"  └── {enum}")
-            
-            if methods:
-                result.append("\nMethods:")
-                for method in methods[:15]:  # Limit to 15 methods
-                    result.append(f"  └── {method}")
-                if len(methods) > 15:
-                    result.append(f"  └── ... and {len(methods) - 15} more methods")
+                model['imports'].append(match.group(1))
+            
+            # Classes, interfaces, enums, records and annotation types, with their annotations
+            java_types, java_members = parse_java_declarations(content)
+            for java_type in java_types:
+                model['classes'].append({'name': java_type['name'], 'kind': java_type['kind'], 'line': java_type['line'],
+                                         'extends': ', '.join(java_type['extends']) or None,
+                                         'implements': ', '.join(java_type['implements']) or None,
+                                         'qualname': java_type['qualname'], 'annotations': java_type['annotations'],
+                                         'components': java_type['components'],
+                                         'fields': [dict(member, visibility=next((modifier for modifier in member['modifiers']
+                                                                                  if modifier in ['public', 'protected', 'private']), 'package'))
+                                                    for member in java_members
+                                                    if member['kind'] == 'field' and member['owner'] == java_type['qualname']]})
+            
+            # Methods and constructors; constructors are left out of the diagram
+            for member in java_members:
+                if member['kind'] != 'field':
+                    model['functions'].append({'name': member['name'], 'params': _format_java_parameters(member['params']),
+                                               'line': member['line'], 'owner': member['owner'].split('.')[-1],
+                                               'listed': member['kind'] == 'method', 'returns': member['type'],
+                                               'annotations': member['annotations']})
                     
         else:
-            # Generic code structure for other languages
-            result.append(f"\nLanguage '{language}' parsing not fully implemented.")
-            result.append("Showing basic file information:")
-            
             # Count lines of code
             lines = content.split('\n')
-            result.append(f"  └── Lines of code: {len(lines)}")
+            model['line_count'] = len(lines)
             
             # Count non-empty, non-comment lines
-            non_empty = sum(1 for line in lines if line.strip() and not line.strip().startswith(('//', '#', '/*', '*', '*/'))) 
-            result.append(f"  └── Non-empty, non-comment lines: {non_empty}")
+            model['non_empty'] = sum(1 for line in lines if line.strip() and not line.strip().startswith(('//', '#', '/*', '*', '*/'))) 
     
     except Exception as e:
-        result.append(f"Error parsing file: {str(e)}")
+        model['error'] = str(e)
+    
+    return model
+
+def _format_parent(cls, separator=' extends '):
+    """Format the extends/implements suffix of a class-like entry."""
+    suffix = f"{separator}{cls['extends']}" if cls['extends'] else ""
+    if cls['implements']:
+        suffix += f" implements {cls['implements']}"
+    return suffix
+
+def _format_python_function(func):
+    """Format a Python function or method as `[async ]name(params) -> returns @decorator`."""
+    signature = f"{'async ' if func['is_async'] else ''}{func['name']}({func['params']})"
+    if func['returns']:
+        signature += f" -> {func['returns']}"
+    return signature + "".join(f" @{decorator}" for decorator in func['decorators'])
+
+def _format_python_field(field):
+    """Format a Python class field or attribute as `Field: name: type = default`."""
+    text = f"{field['kind'].title()}: {field['name']}"
+    if field['type']:
+        text += f": {field['type']}"
+    if field['default'] and field['kind'] != 'attribute':
+        text += f" = {field['default']}"
+    return text
+
+def _format_rust_field(field):
+    """Format a Rust field as `pub name: Type`, or just the type for tuple fields."""
+    visibility = "" if field['visibility'] == 'private' else f"{field['visibility']} "
+    if field['name'].isdigit():
+        return f"{visibility}{field['type']}"
+    return f"{visibility}{field['name']}: {field['type']}"
+
+def _format_rust_attributes(item):
+    """Format the derive and attribute macros on a Rust item as `#[...]` strings."""
+    attributes = [f"#[derive({', '.join(item['derives'])})]"] if item.get('derives') else []
+    return attributes + [f"#[{attribute}]" for attribute in item.get('attributes', [])]
+
+def _attribute_suffix(item):
+    """Return the item's attribute macros as a trailing ` #[...]` suffix, or an empty string."""
+    attributes = _format_rust_attributes(item)
+    return f" {' '.join(attributes)}" if attributes else ""
+
+def _format_rust_variant(variant):
+    """Format an enum variant with its payload, e.g. `Move { x: i32 }` or `Write(String)`."""
+    fields = ", ".join(_format_rust_field(field) for field in variant['fields'])
+    if variant['shape'] == 'struct':
+        return f"{variant['name']} {{ {fields} }}"
+    if variant['shape'] == 'tuple':
+        return f"{variant['name']}({fields})"
+    return variant['name']
+
+def _format_rust_type(cls):
+    """Format a Rust struct or enum with its attributes and fields or variants nested below it."""
+    attributes = [f"      └── {attribute}" for attribute in _format_rust_attributes(cls)]
+    if cls['kind'] == 'enum':
+        lines = [f"Enum: {cls['name']}"] + attributes
+        for variant in cls['variants']:
+            lines.append(f"      └── {_format_rust_variant(variant)}")
+        return "\n".join(lines)
+    
+    if cls['shape'] == 'tuple':
+        return "\n".join([f"Struct: {cls['name']}({', '.join(_format_rust_field(field) for field in cls['fields'])})"] + attributes)
+    if cls['shape'] == 'unit':
+        return "\n".join([f"Struct: {cls['name']} (unit)"] + attributes)
+    lines = [f"Struct: {cls['name']}"] + attributes
+    for field in cls['fields']:
+        lines.append(f"      └── {_format_rust_field(field)}")
+    return "\n".join(lines)
+
+def _format_rust_method(func):
+    """Format a Rust method, tagging associated functions and typed receivers like `self: Box<Self>`."""
+    signature = f"Method: {func['name']}({func['params']})"
+    if func['receiver'] is None:
+        signature += " [associated]"
+    elif func['params'].split(',')[0].strip() != func['receiver']:
+        signature += f" [{func['receiver']}]"
+    return signature + _attribute_suffix(func)
+
+def _format_rust_trait(cls, functions):
+    """Format a Rust trait with its required and provided methods nested below it."""
+    lines = [f"Trait: {cls['name']}{_format_parent(cls, ' : ')}{_attribute_suffix(cls)}"]
+    for func in functions:
+        if func['container'] == 'trait' and func['owner'] == cls['name']:
+            status = 'provided' if func['name'] in cls['provided'] else 'required'
+            lines.append(f"      └── {_format_rust_method(func)} ({status})")
+    return "\n".join(lines)
+
+def _format_rust_impl(impl, functions):
+    """Format a Rust impl block with the methods it defines nested below it."""
+    header = f"Impl: {impl['trait']} for {impl['type']}" if impl['trait'] else f"Impl: {impl['type']}"
+    lines = [header + _attribute_suffix(impl)]
+    for func in functions:
+        if func['container'] == 'impl' and func['block_line'] == impl['line']:
+            lines.append(f"      └── {_format_rust_method(func)}")
+    return "\n".join(lines)
+
+def _format_java_annotation(annotation):
+    """Format a Java annotation as written, e.g. `@GetMapping("/{id}")`."""
+    return f"@{annotation['name']}({annotation['args']})" if annotation['args'] else f"@{annotation['name']}"
+
+def _format_java_parameters(params):
+    """Format Java parameters as `@PathVariable long id, String name`."""
+    return ", ".join(" ".join([_format_java_annotation(annotation) for annotation in param['annotations']]
+                              + [param['type'], param['name']]) for param in params)
+
+def _format_java_type(cls):
+    """Format a Java type with its annotations and fields nested below it."""
+    kind = 'Annotation' if cls['kind'] == 'annotation' else cls['kind'].title()
+    header = f"{kind}: {cls['name']}"
+    if cls['kind'] == 'record':
+        header += f"({_format_java_parameters(cls['components'])})"
+    lines = [header + _format_parent(cls)]
+    lines.extend(f"      └── {_format_java_annotation(annotation)}" for annotation in cls['annotations'])
+    for field in cls['fields']:
+        annotations = "".join(f" {_format_java_annotation(annotation)}" for annotation in field['annotations'])
+        lines.append(f"      └── Field: {field['type']} {field['name']}{annotations}")
+    return "\n".join(lines)
+
+def _format_java_method(func):
+    """Format a Java method as `name(params) @Annotation`."""
+    return f"Method: {func['name']}({func['params']})" + "".join(
+        f" {_format_java_annotation(annotation)}" for annotation in func['annotations'])
+
+def _format_unsafe_site(site):
+    """Format an unsafe or FFI site from find_rust_unsafe, e.g. `unsafe block in read_raw`."""
+    if site['kind'] == 'unsafe block':
+        return f"unsafe block in {site['function']}" if site['function'] else "unsafe block"
+    if site['kind'] == 'extern block':
+        return f"extern {site['abi']} block: {', '.join(site['items'])}" if site['items'] else f"extern {site['abi']} block"
+    if site['kind'] == 'extern fn':
+        return f"extern {site['abi']} fn {site['name']}"
+    if site['kind'] == 'no_mangle':
+        return f"#[no_mangle] {site['name']}"
+    return f"{site['kind']} {site['name']}"
+
+def format_code_structure(model):
+    """Format a code model as the lines of the ASCII code diagram."""
+    result = []
+    language = model['language']
+    classes = model['classes']
+    functions = model['functions']
+    
+    if model['error']:
+        result.append(f"Error parsing file: {model['error']}")
+        return result
+    
+    def add_section(title, entries, limit=None, noun=None):
+        if entries:
+            result.append(f"\n{title}:")
+            for entry in (entries[:limit] if limit else entries):
+                result.append(f"  └── {entry}")
+            if limit and len(entries) > limit:
+                result.append(f"  └── ... and {len(entries) - limit} more {noun}")
+    
+    def of_kind(kind):
+        return [cls for cls in classes if cls['kind'] == kind]
+    
+    if language == 'python':
+        def add_nested(parent, indent):
+            # Functions defined inside a function sit one level below it
+            for func in functions:
+                if func['scope'] == 'function' and func['parent'] == parent:
+                    result.append(f"{indent}└── Function: {_format_python_function(func)}")
+                    add_nested(func['qualname'], indent + "    ")
+        
+        for cls in classes:
+            result.append(f"\nClass: {cls['qualname']}{_format_parent(cls)}")
+            for decorator in cls['decorators']:
+                result.append(f"  └── @{decorator}")
+            for field in cls['fields']:
+                result.append(f"  └── {_format_python_field(field)}")
+            for func in functions:
+                if func['owner'] and func['parent'] == cls['qualname']:
+                    result.append(f"  └── Method: {_format_python_function(func)}")
+                    add_nested(func['qualname'], "      ")
+        
+        standalone_functions = [func for func in functions if func['scope'] == 'module']
+        if standalone_functions:
+            result.append("\nFunctions:")
+            for func in standalone_functions:
+                result.append(f"  └── {_format_python_function(func)}")
+                add_nested(func['qualname'], "      ")
+    
+    elif language in ['javascript', 'js', 'typescript', 'ts']:
+        add_section("Imports", [f"Import: {imp}" for imp in model['imports']], 5, "imports")  # Limit to avoid clutter
+        add_section("Interfaces", [f"Interface: {cls['name']}{_format_parent(cls)}" for cls in of_kind('interface')])
+        add_section("Classes", [f"Class: {cls['name']}{_format_parent(cls)}" for cls in of_kind('class')])
+        add_section("Functions", [f"Function: {func['name']}({func['params']})" for func in functions if func['listed']])
+        add_section("Top-level Variables", [f"Variable: {var}" for var in model['variables']], 10, "variables")
+    
+    elif language in ['rust', 'rs']:
+        add_section("Structs", [_format_rust_type(cls) for cls in of_kind('struct')])
+        add_section("Enums", [_format_rust_type(cls) for cls in of_kind('enum')])
+        add_section("Traits", [_format_rust_trait(cls, functions) for cls in of_kind('trait')])
+        add_section("Implementations", [_format_rust_impl(impl, functions) for impl in model['impls']])
+        add_section("Functions", [f"Function: {func['name']}({func['params']}){_attribute_suffix(func)}"
+                                  for func in functions if func['container'] is None])
+        add_section("Macros", [f"Macro: {macro['name']}!" + (" (exported)" if macro['exported'] else "")
+                               for macro in model['macros']])
+        add_section("Macro Invocations", [f"{call['name']}! ({call['count']} call{'s' if call['count'] != 1 else ''})"
+                                          for call in model['macro_calls']], 10, "macros")
+        add_section("Unsafe and FFI", [f"{_format_unsafe_site(site)} (line {site['line']})" for site in model['unsafe']])
+    
+    elif language in ['cpp', 'c++', 'c']:
+        add_section("Namespaces", [f"Namespace: {namespace}" for namespace in model['namespaces']])
+        add_section("Classes", [f"Class: {cls['name']}{_format_parent(cls, ' : ')}" for cls in of_kind('class')])
+        add_section("Structs", [f"Struct: {cls['name']}" for cls in of_kind('struct')])
+        add_section("Functions", [f"Function: {func['name']}({func['params']})" for func in functions], 15, "functions")
+    
+    elif language in ['java']:
+        if model['package']:
+            result.append(f"\nPackage: {model['package']}")
+        add_section("Imports", [f"Import: {imp}" for imp in model['imports']], 5, "imports")
+        add_section("Classes", [_format_java_type(cls) for cls in of_kind('class')])
+        add_section("Interfaces", [_format_java_type(cls) for cls in of_kind('interface')])
+        add_section("Enums", [_format_java_type(cls) for cls in of_kind('enum')])
+        add_section("Records", [_format_java_type(cls) for cls in of_kind('record')])
+        add_section("Annotation Types", [_format_java_type(cls) for cls in of_kind('annotation')])
+        add_section("Methods", [_format_java_method(func) for func in functions if func['listed']], 15, "methods")
+    
+    else:
+        # Generic code structure for other languages
+        result.append(f"\nLanguage '{language}' parsing not fully implemented.")
+        result.append("Showing basic file information:")
+        result.append(f"  └── Lines of code: {model['line_count']}")
+        result.append(f"  └── Non-empty, non-comment lines: {model['non_empty']}")
     
     return result
 
@@ -787,8 +1054,24 @@ def relation_command(args):
         print(f"Error: Path {args.path} does not exist")
         sys.exit(1)
     
+    # Cargo feature selection for Rust cfg attributes
+    feature_options = {
+        'features': re.split(r'[,\s]+', args.features.strip()) if args.features else None,
+        'no_default_features': args.no_default_features,
+        'annotate_features': args.annotate_features
+    }
+    
     try:
-        diagram = create_relation_diagram(args.path, args.depth)
+        if args.format == 'mermaid':
+            diagram = render_relation_mermaid(build_relation_model(args.path, args.depth, **feature_options))
+        elif args.format == 'dot':
+            diagram = render_relation_dot(build_relation_model(args.path, args.depth, **feature_options))
+        elif args.format == 'json':
+            diagram = render_relation_json(build_relation_model(args.path, args.depth, detailed=True, **feature_options))
+        elif args.format == 'html':
+            diagram = render_relation_html(build_relation_model(args.path, args.depth, **feature_options))
+        else:
+            diagram = create_relation_diagram(args.path, args.depth, **feature_options)
         with open(args.output, 'w') as f:
             f.write(diagram)
         print(f"Success: Relation diagram saved to {args.output}")
@@ -796,14 +1079,73 @@ def relation_command(args):
         print(f"Error: {str(e)}")
         sys.exit(1)
 
-def create_relation_diagram(path, depth=1, max_files=500):
+def rust_feature_conditions(rel_paths, file_languages, features=None, no_default_features=False):
+    """Collect the cfg conditions of Rust files and Cargo manifests for the relation model.
+    
+    Returns {rel_path: {'file': [conditions], 'imports': {import: [conditions]}, 'symbols': {name: [conditions]},
+    'exports': {name: [conditions]}, 'features': set}} where 'features' are the Cargo features
+    enabled for the file's package. Manifests only have 'imports', for their dependencies.
+    """
+    enabled_by_manifest = {}
+    
+    def enabled_features(manifest_path):
+        if manifest_path not in enabled_by_manifest:
+            manifest = parse_cargo_manifest(manifest_path) if manifest_path else None
+            if manifest and manifest['package']:
+                enabled_by_manifest[manifest_path] = cargo_enabled_features(manifest, features, no_default_features)
+            else:
+                enabled_by_manifest[manifest_path] = {name for name in features or [] if '/' not in name}
+        return enabled_by_manifest[manifest_path]
+    
+    result = {}
+    rust_files = {f: rel for f, rel in rel_paths.items() if file_languages[rel] == 'rust'}
+    for rel_path, file_conditions in rust_cfg_conditions(rust_files).items():
+        file_path = next(f for f, rel in rust_files.items() if rel == rel_path)
+        result[rel_path] = {
+            'file': file_conditions['file'],
+            'imports': {imported: import_cfg_conditions([], occurrences)
+                        for imported, occurrences in file_conditions['imports'].items()},
+            'symbols': {name: import_cfg_conditions([], occurrences)
+                        for name, occurrences in file_conditions['symbols'].items()},
+            'exports': {name: import_cfg_conditions([], occurrences)
+                        for name, occurrences in file_conditions['exports'].items()},
+            'features': enabled_features(find_package_manifest(file_path))
+        }
+    
+    for file_path, rel_path in rel_paths.items():
+        if os.path.basename(file_path) != 'Cargo.toml':
+            continue
+        manifest = parse_cargo_manifest(file_path)
+        if manifest:
+            result[rel_path] = {
+                'file': [],
+                'imports': cargo_dependency_conditions(manifest),
+                'features': enabled_features(file_path)
+            }
+    return result
+
+def create_relation_diagram(path, depth=1, max_files=500, **feature_options):
     """Create a diagram showing code relationships and dependencies."""
-    result = []
+    model = build_relation_model(path, depth, max_files, **feature_options)
+    if model is None:
+        return ""
+    return render_relation_ascii(model)
+
+def build_relation_model(path, depth=1, max_files=500, detailed=False, features=None,
+                         no_default_features=False, annotate_features=False):
+    """Analyze a file or directory and return the relation model used by all output formats.
+    
+    With detailed=True the model also records symbol kinds, line numbers and internal
+    relations for every file, which structured formats like JSON need.
     
+    features and no_default_features select the Cargo features to build Rust code with:
+    files and imports behind a `#[cfg]` that is off are left out. With annotate_features,
+    each import edge gets a 'cfg' label with the condition that enables it.
+    """
     # Check if path exists
     if not os.path.exists(path):
         print(f"Error: Path {path} does not exist")
-        return "\n".join(result)
+        return None
         
     # Set a timeout for regex operations to prevent hanging
     # This is a workaround for the re.search timeout parameter not being available in all Python versions
@@ -841,7 +1183,7 @@ def create_relation_diagram(path, depth=1, max_files=500):
                 # Code files
                 code_extensions = [
                     # Web Development
-                    '.py', '.js', '.jsx', '.ts', '.tsx', '.html', '.css', '.scss', '.sass', '.less', '.php', '.vue', '.svelte', '.astro',
+                    '.py', '.js', '.jsx', '.mjs', '.cjs', '.ts', '.tsx', '.mts', '.cts', '.html', '.css', '.scss', '.sass', '.less', '.php', '.vue', '.svelte', '.astro',
                     # Mobile & Desktop
                     '.java', '.kt', '.kts', '.swift', '.m', '.h', '.dart', '.cs', '.fs', '.fsx', '.vb', '.xaml', '.cshtml',
                     # Systems Programming
@@ -891,6 +1233,7 @@ def create_relation_diagram(path, depth=1, max_files=500):
     file_dependencies = {}
     file_exports = {}
     file_symbols = {}
+    file_languages = {}
     
     for file_path in code_files:
         rel_path = os.path.relpath(file_path, path) if os.path.isdir(path) else os.path.basename(file_path)
@@ -902,18 +1245,316 @@ def create_relation_diagram(path, depth=1, max_files=500):
         file_dependencies[rel_path] = imports
         file_exports[rel_path] = exports
         file_symbols[rel_path] = symbols
+        file_languages[rel_path] = language
     
-    # Generate the relation diagram
-    if len(code_files) == 1 and not os.path.isdir(path):
-        # Single file analysis - show internal relationships
-        result.append("Internal Symbol Relationships:")
+    # Rust cfg conditions, and the files and imports they switch off for the selected features
+    filter_features = features is not None or no_default_features
+    import_conditions = {}
+    disabled_symbols = {}
+    if filter_features or annotate_features:
+        rel_of = {file_path: os.path.relpath(file_path, path) if os.path.isdir(path) else os.path.basename(file_path)
+                  for file_path in code_files}
+        conditions = rust_feature_conditions(rel_of, file_languages, features, no_default_features)
+        for file_path in list(code_files):
+            rel_path = rel_of[file_path]
+            if rel_path not in conditions:
+                continue
+            file_conditions = conditions[rel_path]
+            if filter_features and not cfg_enabled(file_conditions['file'], file_conditions['features']):
+                code_files.remove(file_path)
+                for file_map in [file_dependencies, file_exports, file_symbols, file_languages]:
+                    del file_map[rel_path]
+                continue
+            for imported in file_dependencies[rel_path]:
+                gated = file_conditions['imports'].get(imported, [])
+                if filter_features and not cfg_enabled(gated, file_conditions['features']):
+                    file_dependencies[rel_path] = [imp for imp in file_dependencies[rel_path] if imp != imported]
+                elif file_conditions['file'] or gated:
+                    import_conditions[(rel_path, imported)] = format_cfg(file_conditions['file'] + gated)
+            # Items under a disabled cfg of their own, such as `#[cfg(test)] mod tests`
+            if filter_features:
+                for file_map, key in [(file_symbols, 'symbols'), (file_exports, 'exports')]:
+                    disabled = {name for name, gated in file_conditions.get(key, {}).items()
+                                if not cfg_enabled(gated, file_conditions['features'])}
+                    file_map[rel_path] = [name for name in file_map[rel_path] if name not in disabled]
+                    if key == 'symbols' and disabled:
+                        disabled_symbols[rel_path] = disabled
+    
+    model = {
+        'root': os.path.basename(os.path.abspath(path)),
+        'depth': depth,
+        'single_file': len(code_files) == 1 and not os.path.isdir(path),
+        'files': list(file_dependencies.keys()),
+        'languages': file_languages,
+        'imports': file_dependencies,
+        'exports': file_exports,
+        'symbols': file_symbols,
+        'import_edges': [],
+        'symbol_to_file': {},
+        'symbol_usage': {},
+        'function_calls': {},
+        'symbol_types': {},
+        'relations': {},
+        'symbol_details': {},
+        'file_relations': {},
+        'export_collisions': []
+    }
+    
+    if detailed:
+        # Record symbol kinds, line numbers and internal relations for each file
+        for file_path in code_files:
+            rel_path = os.path.relpath(file_path, path) if os.path.isdir(path) else os.path.basename(file_path)
+            relations, symbol_types, definitions = analyze_internal_relations(file_path, file_languages[rel_path])
+            model['file_relations'][rel_path] = dict(relations)
+            
+            details = [definition for definition in symbol_definitions(file_path, symbol_types, definitions)
+                       if definition['name'] not in disabled_symbols.get(rel_path, ())]
+            defined = {definition['name'] for definition in details} | set(symbol_types)
+            others = sorted(s for s in file_symbols[rel_path] if s not in defined)
+            lines = find_symbol_lines(file_path, others)
+            model['symbol_details'][rel_path] = details + [
+                {'name': name, 'kind': 'symbol', 'owner': None, 'qualname': name, 'line': lines.get(name)}
+                for name in others
+            ]
+    
+    # Map file exports to their respective files
+    export_to_file = {}
+    for file, exports in file_exports.items():
+        for export in exports:
+            if export not in export_to_file:
+                export_to_file[export] = []
+            export_to_file[export].append(file)
+    
+    # Resolve language-specific import paths to the files that define them
+    rel_paths = {}
+    for file_path in code_files:
+        rel_paths[file_path] = os.path.relpath(file_path, path) if os.path.isdir(path) else os.path.basename(file_path)
+    resolved_imports = resolve_rust_imports({f: rel for f, rel in rel_paths.items() if file_languages[rel] == 'rust'})
+    resolved_imports.update(resolve_cargo_imports({f: rel for f, rel in rel_paths.items() if os.path.basename(f) == 'Cargo.toml'}))
+    # Python and JS/TS imports also name the symbol they bind in the target file
+    symbol_imports = resolve_python_imports({f: rel for f, rel in rel_paths.items() if file_languages[rel] == 'python'})
+    js_files = {f: rel for f, rel in rel_paths.items() if file_languages[rel] in ['javascript', 'typescript']}
+    js_imports, js_reexports, model['export_collisions'] = resolve_js_imports(js_files)
+    symbol_imports.update(js_imports)
+    # JS/TS imports that are dynamic (code-split) or only used as types
+    import_kinds = find_js_import_kinds(js_files)
+    # Java types resolve by fully qualified name; same-package references need no import statement
+    java_files = {f: rel for f, rel in rel_paths.items() if file_languages[rel] == 'java'}
+    java_imports = resolve_java_imports(java_files)
+    for file, imports in java_imports.items():
+        file_dependencies[file].extend(imported for imported in imports if imported not in file_dependencies[file])
+    resolved_imports.update(java_imports)
+    for file, imports in symbol_imports.items():
+        resolved_imports[file] = {imported: [target] for imported, (target, _) in imports.items()}
+    for file, imports in js_reexports.items():
+        for imported, chain in imports.items():
+            if len(chain['candidates']) > 1:
+                # An ambiguous `export *` name may come from any of the modules
+                resolved_imports[file][imported] = list(dict.fromkeys(target for target, _ in chain['candidates']))
+    
+    # Find connections between files
+    for file, imports in file_dependencies.items():
+        for imported in imports:
+            # Find which files export this symbol
+            targets = resolved_imports.get(file, {}).get(imported) or export_to_file.get(imported, [])
+            if targets and not any(target in file_dependencies for target in targets):
+                # Every target was left out by the feature selection
+                continue
+            edge = {
+                'source': file,
+                'import': imported,
+                'targets': [target for target in targets if target in file_dependencies]
+            }
+            if annotate_features:
+                edge['cfg'] = import_conditions.get((file, imported))
+            if imported in symbol_imports.get(file, {}):
+                # The name the import binds is defined as this symbol in the target file
+                edge['symbol'] = symbol_imports[file][imported][1]
+            if import_kinds.get(file, {}).get(imported):
+                edge['kind'] = import_kinds[file][imported]
+            if imported in js_reexports.get(file, {}):
+                # Imported through barrel files
+                edge['via'] = js_reexports[file][imported]['via']
+                if len(js_reexports[file][imported]['candidates']) > 1:
+                    edge['ambiguous'] = True
+            model['import_edges'].append(edge)
+    
+    if model['single_file']:
+        # Single file analysis - internal relationships
         file_path = code_files[0]
-        file_name = os.path.basename(file_path)
         file_ext = os.path.splitext(file_path)[1].lower()
         language = detect_language_from_extension(file_ext)
         
-        # Get internal relationships and symbol types
-        internal_relations, symbol_types = analyze_internal_relations(file_path, language)
+        internal_relations, symbol_types, _ = analyze_internal_relations(file_path, language)
+        model['relations'] = dict(internal_relations)
+        model['symbol_types'] = symbol_types
+        return model
+    
+    if depth >= 2:
+        # Create a map of symbols to their defining files
+        symbol_to_file = model['symbol_to_file']
+        for file, symbols in file_symbols.items():
+            for symbol in symbols:
+                if symbol not in symbol_to_file:
+                    symbol_to_file[symbol] = []
+                symbol_to_file[symbol].append(file)
+        
+        # Create a map of which symbols are used in which files
+        symbol_usage = model['symbol_usage']
+        for file, imports in file_dependencies.items():
+            for imported in imports:
+                if file_languages[file] == 'rust':
+                    base_symbols = rust_imported_names(imported)
+                elif symbol_imports.get(file, {}).get(imported, (None, None))[1] not in [None, 'default']:
+                    base_symbols = [symbol_imports[file][imported][1]]
+                else:
+                    base_symbols = [imported.split('.')[-1]]  # Get the base symbol name
+                for base_symbol in base_symbols:
+                    if base_symbol not in symbol_usage:
+                        symbol_usage[base_symbol] = []
+                    symbol_usage[base_symbol].append(file)
+        
+        # Spring and Jakarta beans with what is injected into them, and the HTTP endpoints
+        if java_files:
+            spring = build_spring_graph(java_files)
+            if spring['beans'] or spring['endpoints']:
+                model['spring'] = spring
+    
+    if depth >= 3:
+        # Analyze each file for function calls
+        function_calls = model['function_calls']
+        rust_sources = {}
+        python_files = {}
+        for file_path in code_files:
+            rel_path = os.path.relpath(file_path, path) if os.path.isdir(path) else os.path.basename(file_path)
+            file_ext = os.path.splitext(file_path)[1].lower()
+            language = detect_language_from_extension(file_ext)
+            
+            if language == 'rust':
+                # Rust calls are resolved by receiver type and module path, see resolve_rust_calls
+                try:
+                    with open(file_path, 'r', encoding='utf-8', errors='ignore') as f:
+                        rust_sources[rel_path] = f.read()
+                except (OSError, IOError):
+                    pass
+                continue
+            
+            if language == 'python':
+                # Python calls are resolved through imports, see add_python_call_graph
+                python_files[file_path] = rel_path
+                continue
+            
+            # Get internal relationships
+            file_relations = model['file_relations'].get(rel_path)
+            if file_relations is None:
+                file_relations, _, _ = analyze_internal_relations(file_path, language)
+            
+            # Add to global function call map
+            for caller, callees in file_relations.items():
+                if caller not in function_calls:
+                    function_calls[caller] = []
+                
+                for callee in callees:
+                    if not callee.startswith(("inherits from", "has method", "has a ", "derives ", "uses ", "injects ",
+                                              "provides ")):
+                        function_calls[caller].append((callee, rel_path))
+        
+        if rust_sources:
+            add_rust_call_graph(model, rust_sources)
+        if python_files:
+            add_python_call_graph(model, python_files)
+    
+    return model
+
+def add_python_call_graph(model, python_files):
+    """Add Python calls, resolved to their definitions across files, to a depth-3 relation model.
+    
+    python_files maps absolute paths to relative paths. Callers and callees are recorded by
+    qualified name (`PostService.publish`) with the file that defines the callee, and
+    'symbol_to_file' learns where each of them is defined.
+    """
+    function_calls = model['function_calls']
+    symbol_to_file = model['symbol_to_file']
+    for call in find_python_call_graph(python_files):
+        calls = function_calls.setdefault(call['caller'], [])
+        if (call['callee'], call['callee_file']) not in calls:
+            calls.append((call['callee'], call['callee_file']))
+        for name, file in [(call['caller'], call['caller_file']), (call['callee'], call['callee_file'])]:
+            defining_files = symbol_to_file.setdefault(name, [])
+            if file not in defining_files:
+                defining_files.append(file)
+
+def add_rust_call_graph(model, rust_sources):
+    """Add Rust calls and the unsafe code they reach to a depth-3 relation model.
+    
+    rust_sources maps relative paths to file contents. Calls go into 'function_calls' when
+    they resolve to a function defined in one of the files (see resolve_rust_calls), keyed by
+    the functions' qualified names (`PostService::create_post`). 'unsafe' lists each file's
+    unsafe and FFI sites, and 'unsafe_reach' the safe `pub` functions that contain unsafe
+    code or reach it through calls, with the shortest chain.
+    """
+    function_calls = model['function_calls']
+    symbol_to_file = model['symbol_to_file']
+    functions, calls = resolve_rust_calls(rust_sources)
+    
+    for rel_path, file_functions in functions.items():
+        for func in file_functions:
+            defining_files = symbol_to_file.setdefault(func['key'], [])
+            if rel_path not in defining_files:
+                defining_files.append(rel_path)
+    for call in calls:
+        callees = function_calls.setdefault(call['caller']['key'], [])
+        if (call['callee']['key'], call['callee_file']) not in callees:
+            callees.append((call['callee']['key'], call['callee_file']))
+    
+    # Where unsafe code is: functions with unsafe blocks, unsafe fns and foreign functions
+    model['unsafe'] = {}
+    sinks = {}
+    for rel_path, content in rust_sources.items():
+        sites = find_rust_unsafe(content)
+        if sites:
+            model['unsafe'][rel_path] = [{key: value for key, value in site.items() if key != 'position'} for site in sites]
+        by_position = {func['position']: func for func in functions[rel_path]}
+        for site in sites:
+            if site['kind'] == 'unsafe block' and site['function']:
+                enclosing = [func for func in functions[rel_path]
+                             if func['body'] and func['body'][0] < site['position'] < func['body'][1]]
+                func = max(enclosing, key=lambda func: func['body'][0])
+                sinks.setdefault(func['key'], f"unsafe block at {rel_path}:{site['line']}")
+            elif site['kind'] == 'unsafe fn':
+                sinks.setdefault(by_position[site['position']]['key'], f"unsafe fn at {rel_path}:{site['line']}")
+            elif site['kind'] == 'extern block':
+                for func in functions[rel_path]:
+                    if func['name'] in site['items'] and func['owner'] is None:
+                        sinks.setdefault(func['key'], f"extern {site['abi']} fn at {rel_path}:{site['line']}")
+    
+    # Breadth-first search from each safe public function to the nearest unsafe code
+    model['unsafe_reach'] = []
+    for rel_path, file_functions in functions.items():
+        for func in file_functions:
+            if func['visibility'] != 'pub' or 'unsafe' in func['qualifiers'].split() or not func['body']:
+                continue
+            chains = [[func['key']]]
+            visited = {func['key']}
+            while chains:
+                chain = chains.pop(0)
+                if chain[-1] in sinks:
+                    model['unsafe_reach'].append({'function': func['name'], 'owner': func['owner'], 'file': rel_path,
+                                                  'line': func['line'], 'path': chain, 'reason': sinks[chain[-1]]})
+                    break
+                for callee, _ in function_calls.get(chain[-1], []):
+                    if callee not in visited:
+                        visited.add(callee)
+                        chains.append(chain + [callee])
+
+def render_relation_ascii(model):
+    """Render a relation model as an ASCII diagram."""
+    result = []
+    
+    if model['single_file']:
+        # Single file analysis - show internal relationships
+        result.append("Internal Symbol Relationships:")
+        symbol_types = model['symbol_types']
         
         # Display symbol types first
         result.append("Symbol Types:")
@@ -922,7 +1563,7 @@ def create_relation_diagram(path, depth=1, max_files=500):
         
         # Display internal relationships
         result.append("\nRelationships:")
-        for source, targets in internal_relations.items():
+        for source, targets in model['relations'].items():
             # Skip if no relationships
             if not targets:
                 continue
@@ -930,109 +1571,167 @@ def create_relation_diagram(path, depth=1, max_files=500):
             source_type = symbol_types.get(source, '')
             result.append(f"\n{source} [{source_type}]")
             for target in targets:
-                if target.startswith("inherits from") or target.startswith("has method"):
+                if target.startswith(("inherits from", "has method", "derives", "uses ", "injects ", "provides ")):
                     result.append(f"  └── {target}")
                 else:
                     target_type = symbol_types.get(target, '')
                     result.append(f"  └── uses {target} [{target_type}]")
-    else:
-        # Multiple files - show file dependencies
-        result.append("File Dependencies:")
-        
-        # Map file exports to their respective files
-        export_to_file = {}
-        for file, exports in file_exports.items():
-            for export in exports:
-                if export not in export_to_file:
-                    export_to_file[export] = []
-                export_to_file[export].append(file)
-        
-        # Find connections between files
-        for file, imports in file_dependencies.items():
-            if imports:
-                result.append(f"\n{file}")
-                result.append("  └── imports from:")
-                
-                for imported in imports:
-                    # Find which files export this symbol
-                    source_files = export_to_file.get(imported, [])
-                    
-                    if source_files:
-                        for source in source_files:
-                            result.append(f"      └── {imported} (from {source})")
-                    else:
-                        # External dependency
-                        result.append(f"      └── {imported} (external)")
         
-        # If depth > 1, show more detailed symbol usage across files
-        if depth >= 2:
-            result.append("\n\nSymbol Usage Across Files:")
-            
-            # Create a map of symbols to their defining files
-            symbol_to_file = {}
-            for file, symbols in file_symbols.items():
+        return "\n".join(result)
+    
+    # Multiple files - show file dependencies
+    result.append("File Dependencies:")
+    
+    current_file = None
+    for edge in model['import_edges']:
+        if edge['source'] != current_file:
+            current_file = edge['source']
+            result.append(f"\n{current_file}")
+            result.append("  └── imports from:")
+        
+        condition = f" [{edge['cfg']}]" if edge.get('cfg') else ""
+        if edge.get('kind'):
+            condition += f" [{IMPORT_KIND_LABELS[edge['kind']]}]"
+        if edge['targets']:
+            symbol = f": {edge['symbol']}" if edge.get('symbol') else ""
+            symbol += _format_reexport_chain(edge)
+            for source in edge['targets']:
+                if source == edge['source']:
+                    # `use super::*` in an inline module of the same file
+                    result.append(f"      └── {edge['import']} (local){condition}")
+                else:
+                    result.append(f"      └── {edge['import']} (from {source}{symbol}){condition}")
+        else:
+            # External dependency
+            result.append(f"      └── {edge['import']} (external){condition}")
+    
+    if model.get('export_collisions'):
+        result.append("\n\nAmbiguous Re-exports:")
+        current_file = None
+        for collision in model['export_collisions']:
+            if collision['file'] != current_file:
+                current_file = collision['file']
+                result.append(f"\n{current_file}")
+            result.append(f"  └── {collision['name']} (export * from {', '.join(collision['sources'])})")
+    
+    # If depth > 1, show more detailed symbol usage across files
+    if model['depth'] >= 2:
+        result.append("\n\nSymbol Usage Across Files:")
+        
+        # Show symbol definitions and usage
+        for file, symbols in model['symbols'].items():
+            if symbols:
+                result.append(f"\n{file} defines:")
                 for symbol in symbols:
-                    if symbol not in symbol_to_file:
-                        symbol_to_file[symbol] = []
-                    symbol_to_file[symbol].append(file)
-            
-            # Create a map of which symbols are used in which files
-            symbol_usage = {}
-            for file, imports in file_dependencies.items():
-                for imported in imports:
-                    base_symbol = imported.split('.')[-1]  # Get the base symbol name
-                    if base_symbol not in symbol_usage:
-                        symbol_usage[base_symbol] = []
-                    symbol_usage[base_symbol].append(file)
-            
-            # Show symbol definitions and usage
-            for file, symbols in file_symbols.items():
-                if symbols:
-                    result.append(f"\n{file} defines:")
-                    for symbol in symbols:
-                        result.append(f"  └── {symbol}")
-                        
-                        # Find which files import/use this symbol
-                        using_files = symbol_usage.get(symbol, [])
-                        if using_files:
-                            result.append("      └── used by:")
-                            for using_file in using_files:
-                                if using_file != file:  # Don't show self-usage
-                                    result.append(f"          └── {using_file}")
-            
-            # If depth >= 3, show function call graph
-            if depth >= 3:
-                result.append("\n\nFunction Call Graph:")
-                
-                # Analyze each file for function calls
-                function_calls = {}
-                for file_path in code_files:
-                    rel_path = os.path.relpath(file_path, path) if os.path.isdir(path) else os.path.basename(file_path)
-                    file_ext = os.path.splitext(file_path)[1].lower()
-                    language = detect_language_from_extension(file_ext)
-                    
-                    # Get internal relationships
-                    file_relations, _ = analyze_internal_relations(file_path, language)
+                    result.append(f"  └── {symbol}")
                     
-                    # Add to global function call map
-                    for caller, callees in file_relations.items():
-                        if caller not in function_calls:
-                            function_calls[caller] = []
-                        
-                        for callee in callees:
-                            if not callee.startswith("inherits from") and not callee.startswith("has method"):
-                                function_calls[caller].append((callee, rel_path))
-                
-                # Display function call graph
-                for caller, callees in function_calls.items():
-                    if callees:  # Only show functions that call others
-                        defining_files = symbol_to_file.get(caller, ['unknown'])
-                        result.append(f"\n{caller} (in {', '.join(defining_files)})")
-                        for callee, file in callees:
-                            result.append(f"  └── calls {callee} (in {file})")
+                    # Find which other files import/use this symbol
+                    using_files = [using_file for using_file in model['symbol_usage'].get(symbol, []) if using_file != file]
+                    if using_files:
+                        result.append("      └── used by:")
+                        for using_file in using_files:
+                            result.append(f"          └── {using_file}")
+        
+        if model.get('spring'):
+            result.extend(_format_spring_graph(model['spring']))
+        
+        # If depth >= 3, show function call graph
+        if model['depth'] >= 3:
+            result.append("\n\nFunction Call Graph:")
+            
+            for caller, callees in model['function_calls'].items():
+                if callees:  # Only show functions that call others
+                    defining_files = model['symbol_to_file'].get(caller, ['unknown'])
+                    result.append(f"\n{caller} (in {', '.join(defining_files)})")
+                    for callee, file in callees:
+                        result.append(f"  └── calls {callee} (in {file})")
+            
+            if model.get('unsafe'):
+                result.append("\n\nUnsafe and FFI:")
+                for file, sites in model['unsafe'].items():
+                    result.append(f"\n{file}")
+                    for site in sites:
+                        result.append(f"  └── {_format_unsafe_site(site)} (line {site['line']})")
+            
+            if model.get('unsafe_reach'):
+                result.append("\n\nSafe Public Functions Reaching Unsafe Code:")
+                for reach in model['unsafe_reach']:
+                    name = f"{reach['owner']}::{reach['function']}" if reach['owner'] else reach['function']
+                    result.append(f"\n{name} ({reach['file']}:{reach['line']})")
+                    result.append(f"  └── {' → '.join(reach['path'])} ({reach['reason']})")
     
     return "\n".join(result)
 
+def _format_spring_graph(spring):
+    """Format the Spring Beans and HTTP Endpoints sections of the ASCII relation diagram."""
+    result = []
+    beans = {}
+    for bean in spring['beans']:
+        beans.setdefault(bean['name'], bean)
+    injections = defaultdict(list)
+    for injection in spring['injections']:
+        injections[injection['consumer']].append(injection)
+    
+    if spring['beans']:
+        result.append("\n\nSpring Beans:")
+        for bean in spring['beans']:
+            declared = f"{bean['owner']}.{bean['method']}" if bean['method'] else bean['owner']
+            source = f" from {declared}" if bean['method'] else ""
+            primary = " [primary]" if bean['primary'] else ""
+            result.append(f"\n{bean['name']}: {bean['type']} (@{bean['stereotype']}{source}, {bean['file']}:{bean['line']}){primary}")
+            for injection in injections.pop(bean['name'], []):
+                if not injection['targets']:
+                    provider = "no bean in project"
+                else:
+                    provider = ", ".join(f"{target} ({beans[target]['file']})" for target in injection['targets'])
+                    if injection.get('ambiguous'):
+                        provider = f"ambiguous: {provider}"
+                result.append(f"  └── injects {injection['type']} via {injection['via']} {injection['name']} → {provider}")
+    
+    if spring['endpoints']:
+        result.append("\n\nHTTP Endpoints:")
+        for endpoint in spring['endpoints']:
+            result.append(f"  └── {endpoint['method']} {endpoint['path']} → {endpoint['handler']} ({endpoint['file']}:{endpoint['line']})")
+    return result
+
+def _format_reexport_chain(edge):
+    """Describe the barrel files an import went through, and whether its name was ambiguous."""
+    text = ""
+    if edge.get('via'):
+        text += f", via {' → '.join(edge['via'])}"
+    if edge.get('ambiguous'):
+        text += ", ambiguous export *"
+    return text
+
+def symbol_definitions(file_path, symbol_types, definitions):
+    """Return the definitions from analyze_internal_relations, or for languages without a
+    parser one entry per name in symbol_types, located by find_symbol_lines."""
+    if definitions is not None:
+        return definitions
+    lines = find_symbol_lines(file_path, list(symbol_types.keys()))
+    return [{'name': name, 'kind': kind, 'owner': None, 'qualname': name, 'line': lines.get(name)}
+            for name, kind in symbol_types.items()]
+
+def find_symbol_lines(file_path, names):
+    """Find the line number where each symbol is defined in a file."""
+    lines = {}
+    try:
+        with open(file_path, 'r', encoding='utf-8', errors='ignore') as f:
+            content = f.read()
+    except (OSError, IOError):
+        return lines
+    
+    definition_keywords = r'(?:class|interface|struct|enum|trait|record|def|fn|function|func|type|mod|namespace|const|let|var)'
+    for name in names:
+        escaped = re.escape(name)
+        # Prefer a declaration, then a call-like occurrence, then any occurrence
+        match = (re.search(rf'\b{definition_keywords}\s+{escaped}\b', content)
+                 or re.search(rf'\b{escaped}\s*\(', content)
+                 or re.search(rf'\b{escaped}\b', content))
+        if match:
+            lines[name] = content.count('\n', 0, match.start()) + 1
+    return lines
+
 def extract_dependencies(file_path, language):
     """Extract imports, exports, and symbols from a file."""
     imports = []
@@ -1063,45 +1762,26 @@ def extract_dependencies(file_path, language):
             content = f.read()
         
         if language in ['python']:
-            # Python imports
-            import_patterns = [
-                r'import\s+([\w.]+)',  # import module
-                r'from\s+([\w.]+)\s+import\s+([^\n]+)'  # from module import symbol
-            ]
-            
-            for pattern in import_patterns:
-                for match in re.finditer(pattern, content):
-                    if pattern == import_patterns[0]:
-                        imports.append(match.group(1))
-                    else:  # from ... import ...
-                        module = match.group(1)
-                        symbols_str = match.group(2)
-                        for symbol in re.findall(r'\b(\w+)\b', symbols_str):
-                            imports.append(f"{module}.{symbol}")
-            
-            # Python classes and functions (potential exports)
-            class_pattern = r'class\s+(\w+)'
-            func_pattern = r'def\s+(\w+)'
+            # Python imports, classes and functions from the syntax tree
+            tree = parse_python(content, file_path)
+            for imported in find_python_imports(tree):
+                imports.extend(python_import_strings(imported))
             
             # Check for __all__ declaration
-            all_exports = []
-            all_pattern = r'__all__\s*=\s*\[([^\]]+)\]'
-            all_match = re.search(all_pattern, content)
-            if all_match:
-                all_content = all_match.group(1)
-                # Extract symbols from __all__ list
-                for symbol in re.finditer(r'[\'\"]([\w_]+)[\'\",\s]', all_content):
-                    all_exports.append(symbol.group(1))
+            all_exports = find_python_all(tree) or []
+            python_classes, python_functions = find_python_definitions(tree)
             
-            for match in re.finditer(class_pattern, content):
-                class_name = match.group(1)
-                symbols.append(class_name)
+            for cls in python_classes:
+                symbols.append(cls['name'])
                 # Add to exports if it's in __all__ or looks like a public class
-                if class_name in all_exports or not class_name.startswith('_'):
-                    exports.append(class_name)
+                if cls['name'] in all_exports or not cls['name'].startswith('_'):
+                    exports.append(cls['name'])
             
-            for match in re.finditer(func_pattern, content):
-                func_name = match.group(1)
+            for func in python_functions:
+                func_name = func['name']
+                # Nested functions are local to their enclosing function
+                if func['scope'] == 'function':
+                    continue
                 # Skip private functions (starting with single underscore)
                 if not func_name.startswith('_') or (func_name.startswith('__') and func_name.endswith('__')):
                     symbols.append(func_name)
@@ -1110,39 +1790,17 @@ def extract_dependencies(file_path, language):
                         exports.append(func_name)
         
         elif language in ['javascript', 'typescript', 'js', 'ts']:
-            # JS/TS imports
-            import_patterns = [
-                r'import\s+{([^}]*)}\s+from\s+[\'"]([^\'"]*)[\'"](;)?',  # import {symbols} from 'module'
-                r'import\s+(\w+)\s+from\s+[\'"]([^\'"]*)[\'"](;)?',  # import symbol from 'module'
-                r'import\s+\*\s+as\s+(\w+)\s+from\s+[\'"]([^\'"]*)[\'"](;)?',  # import * as symbol from 'module'
-                r'require\([\'"]([^\'"]*)[\'"]\)'  # require('module')
-            ]
-            
-            for pattern in import_patterns:
-                for match in re.finditer(pattern, content):
-                    if pattern == import_patterns[0]:  # Named imports
-                        module = match.group(2)
-                        for symbol in re.findall(r'\b(\w+)\b', match.group(1)):
-                            imports.append(f"{module}.{symbol}")
-                    elif pattern == import_patterns[3]:  # require
-                        imports.append(match.group(1))
-                    else:  # Default or namespace import
-                        imports.append(f"{match.group(2)}.{match.group(1)}")
-            
-            # JS/TS exports
-            export_patterns = [
-                r'export\s+(?:default\s+)?(?:class|function|const|let|var)\s+(\w+)',  # export class/function/variable
-                r'export\s+{([^}]*)}(;)?',  # export {symbols}
-                r'export\s+default\s+(\w+)(;)?'  # export default symbol
-            ]
-            
-            for pattern in export_patterns:
-                for match in re.finditer(pattern, content):
-                    if pattern == export_patterns[1]:  # Named exports
-                        for symbol in re.findall(r'\b(\w+)\b', match.group(1)):
-                            exports.append(symbol)
-                    else:  # Default or direct export
-                        exports.append(match.group(1))
+            # JS/TS imports, static and require()
+            for imported in find_js_imports(content):
+                imports.extend(imp for imp, _ in js_import_strings(imported))
+            
+            # JS/TS exports, including `export ... from` re-exports; a default export is listed by its local name
+            for export in find_js_exports(content):
+                if export['name'] == 'default':
+                    if export['local']:
+                        exports.append(export['local'])
+                elif export['name']:
+                    exports.append(export['name'])
             
             # JS/TS classes, functions, and variables
             symbol_patterns = [
@@ -1156,10 +1814,9 @@ def extract_dependencies(file_path, language):
                     symbols.append(match.group(1))
         
         elif language in ['java']:
-            # Java imports
-            import_pattern = r'import\s+(?:static\s+)?([\w.]+)\s*;'
-            for match in re.finditer(import_pattern, content):
-                imports.append(match.group(1))
+            # Java imports, single-type, on-demand and static
+            for imported in find_java_imports(content):
+                imports.append(java_import_string(imported))
             
             # Java classes, interfaces, and enums (exports)
             class_patterns = [
@@ -1178,39 +1835,23 @@ def extract_dependencies(file_path, language):
                 symbols.append(match.group(1))
         
         elif language in ['rust', 'rs']:
-            # Rust imports (use statements)
-            import_pattern = r'use\s+([^;]+);'
-            for match in re.finditer(import_pattern, content):
-                imports.append(match.group(1))
-            
-            # Rust exports (pub items)
-            export_patterns = [
-                r'pub\s+(?:struct|enum|trait|fn|type|mod)\s+(\w+)',  # pub struct/enum/trait/fn/type/mod
-                r'pub\s+use\s+([^;]+);'  # pub use
-            ]
-            
-            for pattern in export_patterns:
-                for match in re.finditer(pattern, content):
-                    if pattern == export_patterns[1]:  # pub use
-                        for symbol in re.findall(r'\b(\w+)\b', match.group(1)):
-                            exports.append(symbol)
-                    else:  # Direct pub item
-                        exports.append(match.group(1))
-            
-            # Rust symbols
-            symbol_patterns = [
-                r'(?:pub\s+)?(?:struct|enum|trait|fn|type|mod)\s+(\w+)',  # struct/enum/trait/fn/type/mod
-                r'impl(?:<[^>]*>)?\s+(?:([^\s{]+)\s+for\s+)?([^\s{<]+)'  # impl or impl Trait for Type
-            ]
-            
-            for pattern in symbol_patterns:
-                for match in re.finditer(pattern, content):
-                    if pattern == symbol_patterns[1]:  # impl
-                        if match.group(1):  # impl Trait for Type
-                            symbols.append(match.group(1))
-                        symbols.append(match.group(2))
-                    else:  # Direct symbol
-                        symbols.append(match.group(1))
+            # Rust imports (use statements, ignoring comments and strings)
+            for use_tree, _ in find_rust_uses(content):
+                imports.append(use_tree)
+            
+            # Out-of-line module declarations pull in another file
+            for module_name, _ in find_rust_mod_declarations(content):
+                imports.append(f"mod:{module_name}")
+            
+            # Macro invocations other than the std ones
+            for path, _ in find_rust_macro_invocations(content):
+                if path.split('::')[-1] not in STD_MACROS and f"macro:{path}" not in imports:
+                    imports.append(f"macro:{path}")
+
+            # Rust items, macro_rules! definitions and impls; pub items and exported macros are exports
+            rust_symbols, rust_exports = find_rust_symbols(content)
+            symbols.extend(name for name, _ in rust_symbols)
+            exports.extend(name for name, _ in rust_exports)
         
         elif language in ['csharp', 'cs']:
             # C# using statements (imports)
@@ -1315,9 +1956,15 @@ def extract_dependencies(file_path, language):
     return imports, exports, symbols
 
 def analyze_internal_relations(file_path, language):
-    """Analyze internal relationships between symbols in a file."""
+    """Analyze internal relationships between symbols in a file.
+    
+    Returns (relations, symbol_types, definitions). symbol_types maps each name to its kind;
+    definitions has one {'name', 'kind', 'owner', 'qualname', 'line'} entry per definition,
+    with the parser's line, for Python, Rust and Java, and is None for other languages.
+    """
     relations = defaultdict(list)
     symbol_types = {}  # Track symbol types (class, function, method, etc.)
+    definitions = None  # One entry per definition, for the languages with a parser
     class_methods = {}  # Track which methods belong to which classes
     inheritance = {}  # Track inheritance relationships
     
@@ -1329,46 +1976,63 @@ def analyze_internal_relations(file_path, language):
         symbols = []
     except Exception as e:
         print(f"Warning: Error reading file {file_path}: {str(e)}")
-        return relations, symbol_types
+        return relations, symbol_types, definitions
         
     try:
         # First pass: identify all symbols and their types
         if language in ['python']:
+            tree = parse_python(content, file_path)
+            python_classes, python_functions = find_python_definitions(tree)
+            definitions = []
+            
             # Python classes
-            class_pattern = r'class\s+(\w+)(?:\s*\(([^)]+)\))?\s*:'
-            for match in re.finditer(class_pattern, content):
-                class_name = match.group(1)
-                parent_classes = match.group(2)
+            for cls in python_classes:
+                class_name = cls['name']
                 symbols.append(class_name)
                 symbol_types[class_name] = 'class'
                 class_methods[class_name] = []
+                definitions.append({'name': class_name, 'kind': 'class', 'owner': None,
+                                    'qualname': cls['qualname'], 'line': cls['line']})
                 
-                # Track inheritance
-                if parent_classes:
-                    for parent in re.findall(r'\b(\w+)\b', parent_classes):
-                        if parent not in ['object']:
-                            inheritance[class_name] = inheritance.get(class_name, []) + [parent]
+                # Track inheritance (keyword bases such as metaclass=... are not parents)
+                for parent in cls['bases']:
+                    parent = parent.split('[')[0].split('.')[-1]
+                    if parent not in ['object']:
+                        inheritance[class_name] = inheritance.get(class_name, []) + [parent]
             
-            # Python functions and methods
-            func_pattern = r'def\s+(\w+)\s*\(([^)]*)\)\s*:'
-            for match in re.finditer(func_pattern, content):
-                func_name = match.group(1)
-                params = match.group(2)
-                
-                # Check if this is a method (indented inside a class)
-                is_method = False
-                for class_name in symbol_types:
-                    if symbol_types[class_name] == 'class':
-                        # Look for method definition inside class
-                        if re.search(rf'class\s+{class_name}[^\n]*:[^\n]*(?:\n+[^\n]*)*\n+\s+def\s+{func_name}\s*\(', content, re.DOTALL):
-                            is_method = True
-                            class_methods[class_name].append(func_name)
-                            symbol_types[func_name] = f'method of {class_name}'
-                            break
-                
-                if not is_method:
+            # Python functions, methods and nested functions
+            for func in python_functions:
+                func_name = func['name']
+                prefix = 'async ' if func['is_async'] else ''
+                if func['owner']:
+                    class_methods[func['owner']].append(func_name)
+                    kind = f"{prefix}method of {func['owner']}"
+                    symbol_types[func_name] = kind
+                elif func['scope'] == 'function':
+                    kind = f"{prefix}function in {func['parent'].split('.')[-1]}"
+                    symbol_types.setdefault(func_name, kind)
+                else:
                     symbols.append(func_name)
-                    symbol_types[func_name] = 'function'
+                    kind = f"{prefix}function"
+                    symbol_types[func_name] = kind
+                definitions.append({'name': func_name, 'kind': kind, 'owner': func['owner'],
+                                    'qualname': func['qualname'], 'line': func['line']})
+            definitions.sort(key=lambda definition: definition['line'])
+            
+            # Calls to functions and classes defined in this file
+            module_names = {cls['name'] for cls in python_classes if cls['scope'] == 'module'}
+            module_names.update(func['name'] for func in python_functions if func['scope'] == 'module')
+            for func in python_functions:
+                local_names = module_names | {nested['name'] for nested in python_functions
+                                              if nested['parent'] == func['qualname'] and nested['scope'] == 'function'}
+                for call in find_python_calls(func['node']):
+                    if call['receiver'] is None:
+                        called = call['name'] in local_names
+                    else:
+                        called = (call['receiver'] in ['self', 'cls'] and func['owner'] is not None
+                                  and call['name'] in class_methods.get(func['owner'], []))
+                    if called and call['name'] != func['name'] and call['name'] not in relations[func['name']]:
+                        relations[func['name']].append(call['name'])
         
         elif language in ['javascript', 'typescript', 'js', 'ts']:
             # JS/TS classes
@@ -1461,6 +2125,35 @@ def analyze_internal_relations(file_path, language):
                             class_methods[class_name] = class_methods.get(class_name, []) + [method_name]
                             relations[method_name].append(class_name)
                             break
+            
+            # Spring and Jakarta beans: the dependencies injected into each, and the beans its @Bean methods provide
+            java_types, java_members = parse_java_declarations(content)
+            beans = find_java_beans(java_types, java_members)
+            bean_owners = {bean['owner'] for bean in beans}
+            stereotypes = {}
+            for bean in beans:
+                owner = bean['owner'].split('.')[-1]
+                if bean['method']:
+                    relations[owner].append(f"provides {bean['type']}")
+                else:
+                    stereotypes[bean['owner']] = f" (@{bean['stereotype']})"
+                    if owner in symbol_types:
+                        symbol_types[owner] += stereotypes[bean['owner']]
+            for point in find_java_injection_points(java_types, java_members):
+                injected = f"injects {java_injected_type(point['type'])[0]}"
+                if point['owner'] in bean_owners and injected not in relations[point['owner'].split('.')[-1]]:
+                    relations[point['owner'].split('.')[-1]].append(injected)
+            
+            # Types, methods and constructors, with the declaring type for members
+            definitions = [{'name': java_type['name'], 'kind': java_type['kind'] + stereotypes.get(java_type['qualname'], ''),
+                            'owner': java_type['qualname'].rpartition('.')[0] or None,
+                            'qualname': java_type['qualname'], 'line': java_type['line']}
+                           for java_type in java_types]
+            definitions += [{'name': member['name'], 'kind': f"{member['kind']} of {member['owner'].split('.')[-1]}",
+                             'owner': member['owner'], 'qualname': f"{member['owner']}.{member['name']}",
+                             'line': member['line']}
+                            for member in java_members if member['kind'] != 'field']
+            definitions.sort(key=lambda definition: definition['line'])
                             
         elif language in ['csharp', 'cs']:
             # Extract namespace
@@ -1533,55 +2226,112 @@ def analyze_internal_relations(file_path, language):
                     symbol_types[method_name] = 'function'
         
         elif language in ['rust', 'rs']:
-            # Rust structs and enums
-            struct_pattern = r'(?:pub\s+)?struct\s+(\w+)'
-            enum_pattern = r'(?:pub\s+)?enum\s+(\w+)'
+            definitions = []
             
-            for match in re.finditer(struct_pattern, content):
-                struct_name = match.group(1)
-                symbols.append(struct_name)
-                symbol_types[struct_name] = 'struct'
-            
-            for match in re.finditer(enum_pattern, content):
-                enum_name = match.group(1)
-                symbols.append(enum_name)
-                symbol_types[enum_name] = 'enum'
-            
-            # Rust implementations
-            impl_pattern = r'impl(?:<[^>]*>)?\s+(?:([^\s{]+)\s+for\s+)?([^\s{<]+)'
-            for match in re.finditer(impl_pattern, content):
-                trait_name = match.group(1)
-                type_name = match.group(2)
-                
-                if trait_name:
-                    # This is a trait implementation
+            # Rust structs and enums
+            for rust_type in find_rust_types(content):
+                if rust_type['kind'] in ['struct', 'enum']:
+                    symbols.append(rust_type['name'])
+                    symbol_types[rust_type['name']] = rust_type['kind']
+                    definitions.append({'name': rust_type['name'], 'kind': rust_type['kind'], 'owner': None,
+                                        'qualname': rust_type['name'], 'line': rust_type['line']})
+            
+            # Rust traits
+            for trait in find_rust_traits(content):
+                symbols.append(trait['name'])
+                symbol_types[trait['name']] = 'trait'
+                definitions.append({'name': trait['name'], 'kind': 'trait', 'owner': None,
+                                    'qualname': trait['name'], 'line': trait['line']})
+            
+            # Rust trait implementations
+            for impl in find_rust_impls(content):
+                if impl['trait'] and not impl['negative']:
+                    type_name = rust_base_name(impl['type'])
                     if type_name not in inheritance:
                         inheritance[type_name] = []
-                    inheritance[type_name].append(f"trait:{trait_name}")
-            
-            # Rust functions
-            func_pattern = r'(?:pub\s+)?fn\s+(\w+)\s*\(([^)]*)\)'
-            for match in re.finditer(func_pattern, content):
-                func_name = match.group(1)
-                # Check if this is a method in an impl block
-                is_method = False
-                for impl_match in re.finditer(impl_pattern, content):
-                    type_name = impl_match.group(2) if impl_match.group(2) else impl_match.group(1)
-                    impl_block_match = re.search(rf'impl[^{{]*{type_name}[^{{]*{{([^{{}}]*(?:{{[^{{}}]*}}[^{{}}]*)*)}}', content, re.DOTALL)
-                    if impl_block_match and re.search(rf'fn\s+{func_name}\s*\(', impl_block_match.group(1)):
-                        is_method = True
-                        if type_name not in class_methods:
-                            class_methods[type_name] = []
-                        class_methods[type_name].append(func_name)
-                        symbol_types[func_name] = f'method of {type_name}'
-                        break
-                
-                if not is_method:
+                    if f"trait:{impl['trait']}" not in inheritance[type_name]:
+                        inheritance[type_name].append(f"trait:{impl['trait']}")
+            
+            # Rust functions, attributed to the impl or trait block that contains them
+            functions = find_rust_functions(content)
+            unsafe_sites = find_rust_unsafe(content)
+            foreign = {item: site['abi'] for site in unsafe_sites if site['kind'] == 'extern block' for item in site['items']}
+            for func in functions:
+                func_name = func['name']
+                if func['container'] is None:
                     symbols.append(func_name)
-                    symbol_types[func_name] = 'function'
+                    if func_name in foreign:
+                        symbol_types[func_name] = f"foreign function (extern {foreign[func_name]})"
+                    elif 'unsafe' in func['qualifiers'].split():
+                        symbol_types[func_name] = 'unsafe function'
+                    else:
+                        symbol_types[func_name] = 'function'
+                    definitions.append({'name': func_name, 'kind': symbol_types[func_name], 'owner': None,
+                                        'qualname': func_name, 'line': func['line']})
+                    continue
+                
+                type_name = func['owner']
+                if type_name not in class_methods:
+                    class_methods[type_name] = []
+                if func_name not in class_methods[type_name]:
+                    class_methods[type_name].append(func_name)
+                
+                receiver = func['receiver'] or 'associated'
+                if func['container'] == 'impl' and func['trait']:
+                    kind = f"method of {type_name} ({receiver}, impl {func['trait']})"
+                else:
+                    kind = f"method of {type_name} ({receiver})"
+                symbol_types[func_name] = kind
+                definitions.append({'name': func_name, 'kind': kind, 'owner': type_name,
+                                    'qualname': rust_function_key(func), 'line': func['line']})
+            
+            # Rust macro_rules! definitions
+            for macro in find_rust_macros(content):
+                symbols.append(f"{macro['name']}!")
+                symbol_types[f"{macro['name']}!"] = 'macro (exported)' if macro['exported'] else 'macro'
+                definitions.append({'name': f"{macro['name']}!", 'kind': symbol_types[f"{macro['name']}!"], 'owner': None,
+                                    'qualname': f"{macro['name']}!", 'line': macro['line']})
+            
+            # Modules, inline or declared with `mod name;`, in their own namespace
+            for name, position in find_rust_symbols(content)[0]:
+                if name.startswith('mod:'):
+                    definitions.append({'name': name, 'kind': 'module', 'owner': None, 'qualname': name,
+                                        'line': content.count('\n', 0, position) + 1})
+            definitions.sort(key=lambda definition: definition['line'])
+            
+            # Derive and attribute macros on each item
+            for item_name, attributes in find_rust_item_attributes(content).items():
+                for derive in attributes['derives']:
+                    relations[item_name].append(f"derives {derive}")
+                for attribute in attributes['macros']:
+                    relations[item_name].append(f"uses #[{attribute}]")
+            
+            # Macro invocations, attributed to the innermost function containing them
+            for path, position in find_rust_macro_invocations(content):
+                if path.split('::')[-1] in STD_MACROS:
+                    continue
+                enclosing = [func for func in functions if func['body'] and func['body'][0] < position < func['body'][1]]
+                if enclosing:
+                    caller = max(enclosing, key=lambda func: func['body'][0])['name']
+                    if f"uses {path}!" not in relations[caller]:
+                        relations[caller].append(f"uses {path}!")
+            
+            # Calls between the functions of this file, resolved as in the depth-3 call graph
+            for call in resolve_rust_calls({file_path: content})[1]:
+                caller, callee = call['caller']['name'], call['callee']['name']
+                if callee != caller and callee not in relations[caller]:
+                    relations[caller].append(callee)
+            
+            # Unsafe blocks and symbols exported to C
+            for site in unsafe_sites:
+                if site['kind'] == 'unsafe block' and site['function']:
+                    if "uses unsafe block" not in relations[site['function']]:
+                        relations[site['function']].append("uses unsafe block")
+                elif site['kind'] == 'no_mangle':
+                    relations[site['name'].split('::')[-1]].append("uses #[no_mangle]")
         
         # Second pass: analyze relationships between symbols
-        for symbol in symbols + list(symbol_types.keys()):
+        for symbol in dict.fromkeys(symbols + list(symbol_types.keys())):
             # Skip if this is a method (will be analyzed with its class)
             if symbol in symbol_types and 'method of' in symbol_types.get(symbol, ''):
                 continue
@@ -1589,34 +2339,7 @@ def analyze_internal_relations(file_path, language):
             # Get the definition of the symbol - with improved error handling
             symbol_def = ""
             try:
-                if language in ['python']:
-                    if symbol_types.get(symbol) == 'class':
-                        try:
-                            # Use re.escape to handle special characters in symbol names
-                            pattern = rf'class\s+{re.escape(symbol)}[^:]*:'
-                            class_match = re.search(pattern, content)
-                            if class_match:
-                                # Get a limited portion of the class definition to avoid memory issues
-                                start_pos = class_match.end()
-                                end_pos = min(start_pos + 2000, len(content))  # Limit to 2000 chars
-                                symbol_def = content[start_pos:end_pos]
-                        except re.error:
-                            # Skip problematic regex patterns
-                            pass
-                    elif symbol_types.get(symbol) == 'function':
-                        try:
-                            pattern = rf'def\s+{re.escape(symbol)}[^:]*:'
-                            func_match = re.search(pattern, content)
-                            if func_match:
-                                # Get a limited portion of the function definition
-                                start_pos = func_match.end()
-                                end_pos = min(start_pos + 1000, len(content))  # Limit to 1000 chars
-                                symbol_def = content[start_pos:end_pos]
-                        except re.error:
-                            # Skip problematic regex patterns
-                            pass
-                            
-                elif language in ['javascript', 'typescript', 'js', 'ts']:
+                if language in ['javascript', 'typescript', 'js', 'ts']:
                     if symbol_types.get(symbol) == 'class':
                         try:
                             pattern = rf'class\s+{re.escape(symbol)}[^{{]*{{'
@@ -1662,7 +2385,449 @@ def analyze_internal_relations(file_path, language):
     except Exception as e:
         print(f"Warning: Error analyzing internal relations in {file_path}: {str(e)}")
     
-    return relations, symbol_types
+    return relations, symbol_types, definitions
+
+def crates_command(args):
+    """Generate a Cargo workspace and crate dependency graph."""
+    print(f"Generating crate dependency graph for {args.path}")
+    
+    if not os.path.exists(args.path):
+        print(f"Error: Path {args.path} does not exist")
+        sys.exit(1)
+    
+    try:
+        graph = build_crate_graph(args.path)
+        if not graph['crates']:
+            print(f"Error: No Cargo packages found in {args.path}")
+            sys.exit(1)
+        if args.format == 'mermaid':
+            diagram = render_crates_mermaid(graph)
+        elif args.format == 'dot':
+            diagram = render_crates_dot(graph)
+        elif args.format == 'json':
+            diagram = render_crates_json(graph)
+        else:
+            diagram = render_crates_ascii(graph)
+        with open(args.output, 'w') as f:
+            f.write(diagram)
+        print(f"Success: Crate graph saved to {args.output}")
+    except Exception as e:
+        print(f"Error: {str(e)}")
+        sys.exit(1)
+
+def render_crates_ascii(graph):
+    """Render a Cargo crate graph as ASCII text."""
+    result = []
+    
+    if graph['workspaces']:
+        result.append("Cargo Workspaces:\n")
+        for workspace in graph['workspaces']:
+            result.append(workspace['manifest'])
+            result.append("  └── members:")
+            for member in workspace['members']:
+                result.append(f"      └── {member}")
+            result.append("")
+    
+    result.append("Crates:\n")
+    for crate in graph['crates']:
+        version = f" {crate['version']}" if crate['version'] else ""
+        result.append(f"{crate['name']}{version} ({crate['manifest']})")
+        
+        sections = {}
+        for edge in graph['edges']:
+            if edge['source'] != crate['name']:
+                continue
+            heading = 'dependencies' if edge['kind'] == 'normal' else f"{edge['kind']}-dependencies"
+            if edge['cfg']:
+                heading += f" [{edge['cfg']}]"
+            sections.setdefault(heading, []).append(edge)
+        
+        for heading, edges in sections.items():
+            result.append(f"  └── {heading}:")
+            for edge in edges:
+                details = edge['requirement']
+                if edge['alias']:
+                    details += f", as {edge['alias']}"
+                if edge['optional']:
+                    details += ", optional"
+                origin = "workspace crate" if edge['internal'] else "external"
+                result.append(f"      └── {edge['target']} {details} ({origin})")
+        result.append("")
+    
+    if graph['external']:
+        result.append("External Crates:\n")
+        for name, external in graph['external'].items():
+            result.append(f"{name} {' | '.join(external['requirements'])}")
+            result.append("  └── used by:")
+            for user in external['used_by']:
+                result.append(f"      └── {user}")
+            result.append("")
+    
+    return "\n".join(result).rstrip() + "\n"
+
+def lockfile_command(args):
+    """Generate a transitive dependency tree from a Cargo.lock."""
+    print(f"Generating lockfile dependency tree for {args.path}")
+    
+    if not os.path.exists(args.path):
+        print(f"Error: Path {args.path} does not exist")
+        sys.exit(1)
+    
+    try:
+        graph = build_lockfile_graph(args.path)
+        if graph is None:
+            print(f"Error: Could not read a Cargo.lock at {args.path}")
+            sys.exit(1)
+        if args.invert and not any(package['name'] == args.invert for package in graph['packages'].values()):
+            print(f"Error: Crate {args.invert} is not in the lockfile")
+            sys.exit(1)
+        diagram = render_lockfile_ascii(graph, args.invert, args.max_depth)
+        with open(args.output, 'w') as f:
+            f.write(diagram)
+        print(f"Success: Dependency tree saved to {args.output}")
+    except Exception as e:
+        print(f"Error: {str(e)}")
+        sys.exit(1)
+
+def render_lockfile_ascii(graph, invert=None, max_depth=None):
+    """Render a Cargo.lock package graph as a cargo tree-style ASCII tree.
+    
+    With invert set, each locked version of that crate is a root and its children are the
+    packages that depend on it, so the tree answers why the crate is in the build.
+    """
+    result = []
+    
+    if invert:
+        roots = [package_id for package_id, package in graph['packages'].items() if package['name'] == invert]
+        edges = graph['dependents']
+    else:
+        roots = graph['roots']
+        edges = graph['dependencies']
+    
+    shown = set()
+    for root in roots:
+        result.append(_lockfile_label(graph, root))
+        shown.add(root)
+        _process_lockfile_for_ascii(result, graph, edges, root, "", [root], shown, 1, max_depth)
+        result.append("")
+    
+    if graph['duplicates'] and not invert:
+        result.append("Duplicate Crates:\n")
+        for name, package_ids in graph['duplicates'].items():
+            result.append(name)
+            for i, package_id in enumerate(package_ids):
+                is_last = i == len(package_ids) - 1
+                result.append(f"{'└── ' if is_last else '├── '}{_lockfile_name(graph, package_id)[len(name) + 1:]}")
+                dependents = graph['dependents'][package_id]
+                for j, dependent in enumerate(dependents):
+                    connector = "└── " if j == len(dependents) - 1 else "├── "
+                    result.append(f"{'    ' if is_last else '│   '}{connector}required by {_lockfile_name(graph, dependent)}")
+            result.append("")
+    
+    return "\n".join(result).rstrip() + "\n"
+
+def _lockfile_name(graph, package_id):
+    """Name a locked package as `name vX.Y.Z`, adding its source only when another package
+    has the same name and version."""
+    package = graph['packages'][package_id]
+    same_version = [other for other in graph['duplicates'].get(package['name'], [])
+                    if graph['packages'][other]['version'] == package['version']]
+    return package_id if len(same_version) > 1 else f"{package['name']} v{package['version']}"
+
+def _lockfile_label(graph, package_id):
+    """Label a locked package, marking local crates and crates locked more than once."""
+    package = graph['packages'][package_id]
+    label = _lockfile_name(graph, package_id)
+    if package['local']:
+        label += " (local)"
+    if package['name'] in graph['duplicates']:
+        others = [_lockfile_name(graph, other)[len(package['name']) + 1:]
+                  for other in graph['duplicates'][package['name']] if other != package_id]
+        label += f" [duplicate: also {', '.join(others)}]"
+    return label
+
+def _process_lockfile_for_ascii(result, graph, edges, package_id, prefix, ancestors, shown, current_depth, max_depth):
+    """Add a package's dependencies (or dependents) to the ASCII tree."""
+    if max_depth is not None and current_depth > max_depth:
+        return
+    
+    children = edges[package_id]
+    for i, child in enumerate(children):
+        is_last = i == len(children) - 1
+        
+        # Choose the appropriate connector
+        connector = "└── " if is_last else "├── "
+        label = _lockfile_label(graph, child)
+        
+        if child in ancestors:
+            result.append(f"{prefix}{connector}{label} (cycle)")
+            continue
+        if child in shown and edges[child]:
+            # Already expanded elsewhere, like `cargo tree`'s (*)
+            result.append(f"{prefix}{connector}{label} (*)")
+            continue
+        
+        result.append(f"{prefix}{connector}{label}")
+        if max_depth is None or current_depth < max_depth:
+            shown.add(child)
+        new_prefix = prefix + ("    " if is_last else "│   ")
+        _process_lockfile_for_ascii(result, graph, edges, child, new_prefix, ancestors + [child], shown, current_depth + 1, max_depth)
+
+def traits_command(args):
+    """Generate a Rust trait implementation matrix."""
+    print(f"Generating trait implementation matrix for {args.path}")
+    
+    if not os.path.exists(args.path):
+        print(f"Error: Path {args.path} does not exist")
+        sys.exit(1)
+    
+    try:
+        matrix = build_trait_matrix(args.path)
+        if args.format == 'json':
+            diagram = render_traits_json(matrix)
+        else:
+            diagram = render_traits_ascii(matrix)
+        with open(args.output, 'w') as f:
+            f.write(diagram)
+        print(f"Success: Trait matrix saved to {args.output}")
+    except Exception as e:
+        print(f"Error: {str(e)}")
+        sys.exit(1)
+
+def render_traits_ascii(matrix):
+    """Render a Rust trait matrix as an ASCII types x traits table followed by per-trait details."""
+    result = []
+    
+    if not matrix['traits']:
+        result.append("No traits declared, derived or implemented.")
+        return "\n".join(result) + "\n"
+    
+    shape_marks = {'derive': 'D', 'concrete': 'I', 'generic': 'G'}
+    local_types = [rust_type['name'] for rust_type in matrix['types']]
+    
+    # Local types are grouped by name; foreign self types (Vec<T>, String) keep their written form
+    def row_of(impl):
+        base = rust_base_name(impl['type'])
+        return base if base in local_types else impl['type']
+    
+    rows = list(dict.fromkeys(local_types))
+    for impl in matrix['impls']:
+        if impl['shape'] != 'blanket' and row_of(impl) not in rows:
+            rows.append(row_of(impl))
+    
+    traits = [name for name, trait in matrix['traits'].items() if trait['local']]
+    traits += sorted(name for name, trait in matrix['traits'].items() if not trait['local'])
+    blanket = {impl['trait'] for impl in matrix['impls'] if impl['shape'] == 'blanket'}
+    
+    cells = {}
+    for impl in matrix['impls']:
+        if impl['shape'] != 'blanket':
+            cells.setdefault((row_of(impl), impl['trait']), shape_marks[impl['shape']])
+    
+    # A blanket impl covers the rows that already implement every trait it requires
+    for impl in matrix['impls']:
+        if impl['shape'] == 'blanket' and impl['requires']:
+            for row in rows:
+                if all((row, required) in cells for required in impl['requires']):
+                    cells.setdefault((row, impl['trait']), 'B')
+    
+    headers = [f"{name}*" if name in blanket else name for name in traits]
+    row_width = max([len(row) for row in rows] + [4])
+    
+    result.append("Trait Implementation Matrix:\n")
+    result.append(" " * row_width + "  " + "  ".join(headers))
+    for row in rows:
+        line = row.ljust(row_width) + "  "
+        line += "  ".join(cells.get((row, name), '.').ljust(len(header)) for name, header in zip(traits, headers))
+        result.append(line.rstrip())
+    result.append("")
+    result.append("D = derived, I = impl, G = generic impl, B = covered by a blanket impl, * = trait has a blanket impl")
+    result.append("")
+    
+    result.append("Traits:\n")
+    for name in traits:
+        trait = matrix['traits'][name]
+        location = f" ({trait['file']}:{trait['line']})" if trait['local'] else " (external)"
+        result.append(f"{name}{location}")
+        if trait['supertraits']:
+            result.append(f"  └── supertraits: {', '.join(trait['supertraits'])}")
+        if trait['required']:
+            result.append("  └── required methods:")
+            for method in trait['required']:
+                result.append(f"      └── {method}")
+        if trait['provided']:
+            result.append("  └── provided methods:")
+            for method in trait['provided']:
+                result.append(f"      └── {method}")
+        
+        implementors = [impl for impl in matrix['impls'] if impl['trait'] == name and impl['shape'] != 'blanket']
+        if implementors:
+            result.append("  └── implementors:")
+            for impl in implementors:
+                detail = 'derive' if impl['shape'] == 'derive' else 'impl' if impl['shape'] == 'concrete' else impl['header']
+                result.append(f"      └── {impl['type']} ({detail}, {impl['file']}:{impl['line']})")
+        
+        blanket_impls = [impl for impl in matrix['impls'] if impl['trait'] == name and impl['shape'] == 'blanket']
+        if blanket_impls:
+            result.append("  └── blanket impls:")
+            for impl in blanket_impls:
+                result.append(f"      └── {impl['header']} ({impl['file']}:{impl['line']})")
+        result.append("")
+    
+    return "\n".join(result).rstrip() + "\n"
+
+def api_command(args):
+    """List the public API surface of Rust library crates."""
+    print(f"Generating public API surface for {args.path}")
+    
+    if not os.path.exists(args.path):
+        print(f"Error: Path {args.path} does not exist")
+        sys.exit(1)
+    
+    try:
+        surface = build_api_surface(args.path)
+        if not surface['crates']:
+            print(f"Error: No Rust library crates found in {args.path}")
+            sys.exit(1)
+        if args.format == 'json':
+            diagram = render_api_json(surface)
+        else:
+            diagram = render_api_ascii(surface)
+        with open(args.output, 'w') as f:
+            f.write(diagram)
+        print(f"Success: API surface saved to {args.output}")
+    except Exception as e:
+        print(f"Error: {str(e)}")
+        sys.exit(1)
+
+def render_api_ascii(surface):
+    """Render the public API surface of Rust crates as ASCII text, one public path per entry."""
+    result = []
+    
+    for crate in surface['crates']:
+        result.append(f"Public API of {crate['name']} ({crate['lib']})")
+        result.append("=" * 50)
+        result.append("")
+        
+        if crate['modules']:
+            result.append("Modules:")
+            for module in crate['modules']:
+                result.append(f"  └── {module}")
+            result.append("")
+        
+        result.append("Items:\n")
+        for item in crate['items']:
+            result.append(f"{item['path']} ({item['file']}:{item['line']})")
+            if item['alias_of']:
+                result.append(f"  └── same as {item['alias_of']}")
+                result.append("")
+                continue
+            result.append(f"  └── {item['signature']}")
+            if item['reexports']:
+                result.append(f"  └── re-export of {item['reexports']}")
+            if item['cfg']:
+                result.append(f"  └── {format_cfg(item['cfg'])}")
+            for title, entries in [("fields", [_format_rust_field(field) for field in item['fields']]),
+                                   ("variants", [_format_rust_variant(variant) for variant in item['variants']]),
+                                   ("methods", item['methods'])]:
+                if entries:
+                    result.append(f"  └── {title}:")
+                    for entry in entries:
+                        result.append(f"      └── {entry}")
+            if item['impls']:
+                result.append(f"  └── implements: {', '.join(item['impls'])}")
+            result.append("")
+        
+        if not crate['items']:
+            result.append("No public items.\n")
+    
+    return "\n".join(result).rstrip() + "\n"
+
+def components_command(args):
+    """Generate a React, Vue or Svelte component tree."""
+    print(f"Generating component tree for {args.path}")
+    
+    if not os.path.exists(args.path):
+        print(f"Error: Path {args.path} does not exist")
+        sys.exit(1)
+    
+    try:
+        tree = build_component_tree(args.path)
+        if args.format == 'json':
+            diagram = render_components_json(tree)
+        elif args.format == 'mermaid':
+            diagram = render_components_mermaid(tree)
+        else:
+            diagram = render_components_ascii(tree)
+        with open(args.output, 'w') as f:
+            f.write(diagram)
+        print(f"Success: Component tree saved to {args.output}")
+    except Exception as e:
+        print(f"Error: {str(e)}")
+        sys.exit(1)
+
+def render_components_ascii(tree):
+    """Render a component tree as ASCII, each child with the props passed where it is rendered.
+    
+    Every component no other component renders is a root. A component is expanded the
+    first time it appears; later usages are marked (*) and recursive ones (cycle).
+    """
+    result = []
+    
+    if not tree['components']:
+        result.append("No React, Vue or Svelte components found.")
+        return "\n".join(result) + "\n"
+    
+    components = {component['id']: component for component in tree['components']}
+    children = defaultdict(list)
+    for usage in tree['usages']:
+        children[usage['parent']].append(usage)
+    
+    result.append(f"Component Tree for {tree['root']}")
+    result.append("=" * 50)
+    result.append("")
+    
+    shown = set()
+    for root in tree['roots']:
+        component = components[root]
+        result.append(f"{component['name']} ({component['file']}:{component['line']}) [{component['kind']}]")
+        shown.add(root)
+        _process_components_for_ascii(result, components, children, root, "", [root], shown)
+        result.append("")
+    
+    return "\n".join(result).rstrip() + "\n"
+
+def _process_components_for_ascii(result, components, children, component_id, prefix, ancestors, shown):
+    """Add the components a component renders to the ASCII tree."""
+    usages = children[component_id]
+    for i, usage in enumerate(usages):
+        is_last = i == len(usages) - 1
+        
+        # Choose the appropriate connector
+        connector = "└── " if is_last else "├── "
+        if usage['child']:
+            location = f" ({components[usage['child']]['file']})"
+        elif usage['source']:
+            location = f" (from {usage['source']})"
+        else:
+            location = ""
+        props = f" {', '.join(usage['props'])}" if usage['props'] else ""
+        label = f"{usage['name']}{location}{props}"
+        child = usage['child']
+        
+        if child in ancestors:
+            result.append(f"{prefix}{connector}{label} (cycle)")
+            continue
+        if child in shown and children[child]:
+            # Already expanded elsewhere
+            result.append(f"{prefix}{connector}{label} (*)")
+            continue
+        
+        result.append(f"{prefix}{connector}{label}")
+        if child:
+            shown.add(child)
+            new_prefix = prefix + ("    " if is_last else "│   ")
+            _process_components_for_ascii(result, components, children, child, new_prefix, ancestors + [child], shown)
 
 def main():
     """Main entry point for the application."""
@@ -1674,8 +2839,18 @@ def main():
         code_command(args)
     elif args.command == 'relation':
         relation_command(args)
+    elif args.command == 'crates':
+        crates_command(args)
+    elif args.command == 'lockfile':
+        lockfile_command(args)
+    elif args.command == 'traits':
+        traits_command(args)
+    elif args.command == 'api':
+        api_command(args)
+    elif args.command == 'components':
+        components_command(args)
     else:
-        print("Error: Please specify a command (directory, code, or relation)")
+        print("Error: Please specify a command (directory, code, relation, crates, lockfile, traits, api, or components)")
         sys.exit(1)
 
 if __name__ == '__main__':
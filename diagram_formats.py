@@ -0,0 +1,1210 @@
+"""
+Module for rendering CLIArt analysis models in formats other than ASCII.
+The renderers take the models built by cliart.py and return the diagram as a string.
+"""
+
+import os
+import json
+import re
+from rust_analysis import rust_has_a_edges
+from python_analysis import python_has_a_edges
+
+class NodeIds:
+    """Hand out stable, sanitized node identifiers for diagram languages."""
+
+    def __init__(self, prefix, used=None):
+        # Allocators that share a used set never hand out the same identifier
+        self.prefix = prefix
+        self.ids = {}
+        self.used = used if used is not None else set()
+
+    def get(self, key):
+        """Return the identifier for a key, creating a unique one on first use."""
+        if key not in self.ids:
+            base = f"{self.prefix}_{re.sub(r'[^A-Za-z0-9_]', '_', key)}"
+            node_id = base
+            counter = 1
+            while node_id in self.used:
+                counter += 1
+                node_id = f"{base}_{counter}"
+            self.ids[key] = node_id
+            self.used.add(node_id)
+        return self.ids[key]
+
+def _relation_edges(relations):
+    """Split internal relation strings into (source, kind, target) edges."""
+    edges = []
+    for source, targets in relations.items():
+        for target in targets:
+            if target.startswith("inherits from "):
+                edges.append((source, 'inherits', target[len('inherits from '):]))
+            elif target.startswith("has method "):
+                edges.append((source, 'has_method', target[len('has method '):]))
+            elif target.startswith("has a "):
+                edges.append((source, 'has_a', target[len('has a '):]))
+            elif target.startswith("derives "):
+                # A derive macro implements the trait, like an explicit impl
+                edges.append((source, 'inherits', f"trait:{target[len('derives '):]}"))
+            elif target.startswith("uses "):
+                edges.append((source, 'uses', target[len('uses '):]))
+            elif target.startswith("injects "):
+                edges.append((source, 'injects', target[len('injects '):]))
+            elif target.startswith("provides "):
+                edges.append((source, 'provides', target[len('provides '):]))
+            else:
+                edges.append((source, 'uses', target))
+    return edges
+
+def _mermaid_label(text):
+    """Escape a label for use inside a quoted Mermaid node."""
+    return text.replace('"', '#quot;')
+
+def render_relation_mermaid(model):
+    """Render a relation model as a Mermaid flowchart."""
+    result = ["graph LR"]
+
+    if model['single_file']:
+        # Single file analysis - show internal relationships between symbols
+        symbol_ids = NodeIds('sym')
+        symbol_types = model['symbol_types']
+        edges = _relation_edges(model['relations'])
+
+        symbols = list(symbol_types.keys())
+        for source, _, target in edges:
+            for symbol in (source, target):
+                if symbol not in symbols:
+                    symbols.append(symbol)
+
+        for symbol in symbols:
+            symbol_type = symbol_types.get(symbol)
+            label = f"{symbol} [{symbol_type}]" if symbol_type else symbol
+            result.append(f'    {symbol_ids.get(symbol)}["{_mermaid_label(label)}"]')
+
+        for source, kind, target in edges:
+            result.append(f"    {symbol_ids.get(source)} -->|{kind.replace('_', ' ')}| {symbol_ids.get(target)}")
+
+        return "\n".join(result)
+
+    file_ids = NodeIds('file')
+    external_ids = NodeIds('ext')
+
+    # File nodes
+    for file in model['files']:
+        result.append(f'    {file_ids.get(file)}["{_mermaid_label(file)}"]')
+
+    # External packages get their own subgraph
+    externals = []
+    for edge in model['import_edges']:
+        if not edge['targets'] and edge['import'] not in externals:
+            externals.append(edge['import'])
+
+    if externals:
+        result.append('    subgraph external ["External Packages"]')
+        for imported in externals:
+            result.append(f'        {external_ids.get(imported)}["{_mermaid_label(imported)}"]')
+        result.append("    end")
+
+    # Import edges: solid for local files, dotted for external packages and for dynamic or
+    # type-only imports, labeled with their cfg and kind
+    seen_edges = set()
+    for edge in model['import_edges']:
+        texts = [text for text in [edge.get('cfg'), IMPORT_KIND_LABELS.get(edge.get('kind'))] if text]
+        label = f'|"{_mermaid_label(", ".join(texts))}"|' if texts else ""
+        if edge['targets']:
+            arrow = '-.->' if edge.get('kind') else '-->'
+            for target in edge['targets']:
+                if target == edge['source']:
+                    continue
+                line = f"    {file_ids.get(edge['source'])} {arrow}{label} {file_ids.get(target)}"
+                if line not in seen_edges:
+                    seen_edges.add(line)
+                    result.append(line)
+        else:
+            result.append(f"    {file_ids.get(edge['source'])} -.->{label} {external_ids.get(edge['import'])}")
+
+    # Spring beans and the beans injected into them
+    if model.get('spring') and model['spring']['beans']:
+        bean_ids = NodeIds('bean')
+        result.append('    subgraph beans ["Spring Beans"]')
+        for bean in model['spring']['beans']:
+            label = f"{bean['name']}<br/>@{bean['stereotype']} {bean['type']}"
+            result.append(f'        {bean_ids.get(bean["name"])}["{_mermaid_label(label)}"]')
+        result.append("    end")
+        for injection in model['spring']['injections']:
+            for target in injection['targets']:
+                result.append(f"    {bean_ids.get(injection['consumer'])} -.->|injects| {bean_ids.get(target)}")
+
+    # Function call edges at depth 3
+    if model['depth'] >= 3 and any(model['function_calls'].values()):
+        function_ids = NodeIds('fn')
+        functions = []
+        for caller, callees in model['function_calls'].items():
+            if not callees:
+                continue
+            for name in [caller] + [callee for callee, _ in callees]:
+                if name not in functions:
+                    functions.append(name)
+
+        result.append('    subgraph calls ["Function Call Graph"]')
+        for name in functions:
+            defining_files = model['symbol_to_file'].get(name, ['unknown'])
+            result.append(f'        {function_ids.get(name)}["{_mermaid_label(name)}<br/>{_mermaid_label(", ".join(defining_files))}"]')
+        result.append("    end")
+        for caller, callees in model['function_calls'].items():
+            for callee, _ in callees:
+                result.append(f"    {function_ids.get(caller)} ==>|calls| {function_ids.get(callee)}")
+
+    return "\n".join(result)
+
+# How import edges that are not plain static imports are labelled
+IMPORT_KIND_LABELS = {
+    'dynamic': 'dynamic import',
+    'type': 'type only'
+}
+
+# Graphviz edge styles by relationship kind
+DOT_EDGE_STYLES = {
+    'local': 'style=solid, color="black"',
+    'external': 'style=dashed, color="gray50"',
+    'symbol': 'style=dotted, color="blue"',
+    'dynamic': 'style=dashed, color="darkorange"',
+    'type': 'style=dotted, color="purple"',
+    'call': 'style=bold, color="red"',
+    'inherits': 'arrowhead=empty',
+    'has_method': 'arrowhead=none, arrowtail=diamond, dir=back',
+    'has_a': 'arrowhead=vee, arrowtail=odiamond, dir=both',
+    'uses': 'style=dashed',
+    'injects': 'style=dashed, color="teal", arrowhead=open',
+    'provides': 'style=solid, color="teal", arrowhead=dot',
+    'dev': 'style=dashed, color="gray30"',
+    'build': 'style=dotted, color="darkgreen"'
+}
+
+def _dot_label(text):
+    """Escape a label for use inside a quoted DOT string."""
+    return text.replace('\\', '\\\\').replace('"', '\\"')
+
+def _dot_edge(source_id, target_id, kind, label=None):
+    """Format a DOT edge styled by its relationship kind."""
+    attributes = DOT_EDGE_STYLES[kind]
+    if label:
+        attributes += f', label="{_dot_label(label)}"'
+    return f"{source_id} -> {target_id} [{attributes}];"
+
+def _build_path_tree(paths):
+    """Group relative file paths into a nested directory tree."""
+    tree = {'dirs': {}, 'files': []}
+    for path in paths:
+        parts = path.replace('\\', '/').split('/')
+        node = tree
+        for part in parts[:-1]:
+            node = node['dirs'].setdefault(part, {'dirs': {}, 'files': []})
+        node['files'].append(path)
+    return tree
+
+def _emit_dot_clusters(result, tree, render_file, cluster_ids, prefix='', indent='    '):
+    """Emit files inside nested cluster subgraphs that follow the directory hierarchy."""
+    for file in tree['files']:
+        for line in render_file(file):
+            result.append(f"{indent}{line}")
+    for name, subtree in tree['dirs'].items():
+        dir_path = f"{prefix}{name}"
+        result.append(f"{indent}subgraph {cluster_ids.get(dir_path)} {{")
+        result.append(f'{indent}    label="{_dot_label(name)}/";')
+        _emit_dot_clusters(result, subtree, render_file, cluster_ids, f"{dir_path}/", indent + "    ")
+        result.append(f"{indent}}}")
+
+def _dot_header(name):
+    """Return the opening lines of a DOT digraph."""
+    return [
+        f'digraph "{_dot_label(name)}" {{',
+        "    rankdir=LR;",
+        "    compound=true;",
+        '    node [shape=box, fontname="Helvetica"];',
+        '    edge [fontname="Helvetica", fontsize=10];'
+    ]
+
+def _dot_symbol_graph(result, symbol_types, relations, symbol_ids, indent='    '):
+    """Emit symbol nodes and their inheritance/method edges."""
+    nodes = []
+    edges = _relation_edges(relations)
+
+    for symbol in list(symbol_types.keys()) + [name for edge in edges for name in (edge[0], edge[2])]:
+        if symbol not in nodes:
+            nodes.append(symbol)
+
+    for symbol in nodes:
+        symbol_type = symbol_types.get(symbol)
+        label = _dot_label(symbol)
+        if symbol_type:
+            label += f"\\n[{_dot_label(symbol_type)}]"
+        result.append(f'{indent}{symbol_ids.get(symbol)} [label="{label}"];')
+
+    return edges
+
+def render_relation_dot(model):
+    """Render a relation model as a Graphviz DOT digraph."""
+    result = _dot_header(model['root'])
+
+    if model['single_file']:
+        symbol_ids = NodeIds('sym')
+        edges = _dot_symbol_graph(result, model['symbol_types'], model['relations'], symbol_ids)
+        for source, kind, target in edges:
+            result.append(f"    {_dot_edge(symbol_ids.get(source), symbol_ids.get(target), kind)}")
+        result.append("}")
+        return "\n".join(result)
+
+    file_ids = NodeIds('file')
+    external_ids = NodeIds('ext')
+    function_ids = NodeIds('fn')
+    cluster_ids = NodeIds('cluster')
+
+    # Function nodes live next to the file that defines them
+    functions_by_file = {}
+    call_edges = []
+    if model['depth'] >= 3:
+        for caller, callees in model['function_calls'].items():
+            for callee, _ in callees:
+                call_edges.append((caller, callee))
+        for name in [name for edge in call_edges for name in edge]:
+            defining_file = model['symbol_to_file'].get(name, [None])[0]
+            functions = functions_by_file.setdefault(defining_file, [])
+            if name not in functions:
+                functions.append(name)
+
+    def render_file(file):
+        lines = [f'{file_ids.get(file)} [label="{_dot_label(os.path.basename(file))}", tooltip="{_dot_label(file)}"];']
+        for name in functions_by_file.get(file, []):
+            lines.append(f'{function_ids.get(name)} [label="{_dot_label(name)}()", shape=ellipse];')
+        return lines
+
+    _emit_dot_clusters(result, _build_path_tree(model['files']), render_file, cluster_ids)
+
+    for name in functions_by_file.get(None, []):
+        result.append(f'    {function_ids.get(name)} [label="{_dot_label(name)}()", shape=ellipse, style=dashed];')
+
+    # External packages
+    externals = []
+    for edge in model['import_edges']:
+        if not edge['targets'] and edge['import'] not in externals:
+            externals.append(edge['import'])
+    if externals:
+        result.append(f"    subgraph {cluster_ids.get('external packages')} {{")
+        result.append('        label="External Packages";')
+        result.append('        style=dashed;')
+        for imported in externals:
+            result.append(f'        {external_ids.get(imported)} [label="{_dot_label(imported)}", shape=component];')
+        result.append("    }")
+
+    # Import edges
+    seen_edges = set()
+    for edge in model['import_edges']:
+        texts = [text for text in [edge.get('cfg'), IMPORT_KIND_LABELS.get(edge.get('kind'))] if text]
+        label = ", ".join(texts) or None
+        if edge['targets']:
+            for target in edge['targets']:
+                if target == edge['source']:
+                    continue
+                line = _dot_edge(file_ids.get(edge['source']), file_ids.get(target), edge.get('kind') or 'local', label)
+                if line not in seen_edges:
+                    seen_edges.add(line)
+                    result.append(f"    {line}")
+        else:
+            result.append(f"    {_dot_edge(file_ids.get(edge['source']), external_ids.get(edge['import']), 'external', label)}")
+
+    # Symbol usage edges
+    if model['depth'] >= 2:
+        for file, symbols in model['symbols'].items():
+            for symbol in symbols:
+                for using_file in model['symbol_usage'].get(symbol, []):
+                    if using_file != file:
+                        result.append(f"    {_dot_edge(file_ids.get(using_file), file_ids.get(file), 'symbol', symbol)}")
+
+    # Function call edges
+    for caller, callee in call_edges:
+        result.append(f"    {_dot_edge(function_ids.get(caller), function_ids.get(callee), 'call')}")
+
+    # Spring beans and the beans injected into them
+    if model.get('spring') and model['spring']['beans']:
+        bean_ids = NodeIds('bean')
+        result.append(f"    subgraph {cluster_ids.get('spring beans')} {{")
+        result.append('        label="Spring Beans";')
+        for bean in model['spring']['beans']:
+            label = f"{_dot_label(bean['name'])}\\n@{_dot_label(bean['stereotype'])} {_dot_label(bean['type'])}"
+            result.append(f'        {bean_ids.get(bean["name"])} [label="{label}", shape=component];')
+        result.append("    }")
+        for injection in model['spring']['injections']:
+            for target in injection['targets']:
+                result.append(f"    {_dot_edge(bean_ids.get(injection['consumer']), bean_ids.get(target), 'injects')}")
+
+    result.append("}")
+    return "\n".join(result)
+
+# Relations whose target is a type, which may be declared in another file
+CROSS_FILE_RELATIONS = [('has a ', 'has_a'), ('injects ', 'injects'), ('provides ', 'provides')]
+
+def render_code_dot(model):
+    """Render a code model as a Graphviz DOT digraph with one cluster per file."""
+    result = _dot_header(model['root'])
+    cluster_ids = NodeIds('cluster')
+    file_edges = []
+    # Paths like a/b.py and a_b.py sanitize alike, so file prefixes and symbol ids come from one pool
+    file_prefixes = NodeIds('sym')
+    symbol_ids = {info['path']: NodeIds(file_prefixes.get(info['path']), file_prefixes.used) for info in model['files']}
+
+    # Has-a, injects and provides edges may point at a type declared in another file
+    defined_in = {}
+    for info in model['files']:
+        for name in info['symbol_types']:
+            defined_in.setdefault(name, info['path'])
+
+    def render_file(file):
+        file_info = next(info for info in model['files'] if info['path'] == file)
+        file_ids = symbol_ids[file]
+        lines = [f"subgraph {cluster_ids.get('file:' + file)} {{", f'    label="{_dot_label(os.path.basename(file))} ({_dot_label(file_info["language"])})";']
+
+        relations = {}
+        for source, targets in file_info['relations'].items():
+            for target in targets:
+                kind = next((kind for prefix, kind in CROSS_FILE_RELATIONS if target.startswith(prefix)), None)
+                name = target.split(' ', 2)[-1] if kind else None
+                if name and name not in file_info['symbol_types'] and name in defined_in:
+                    file_edges.append(_dot_edge(file_ids.get(source), symbol_ids[defined_in[name]].get(name), kind))
+                else:
+                    relations.setdefault(source, []).append(target)
+
+        body = []
+        edges = _dot_symbol_graph(body, file_info['symbol_types'], relations, file_ids, indent='    ')
+        lines.extend(body)
+        lines.append("}")
+        for source, kind, target in edges:
+            file_edges.append(_dot_edge(file_ids.get(source), file_ids.get(target), kind))
+        return lines
+
+    _emit_dot_clusters(result, _build_path_tree([info['path'] for info in model['files']]), render_file, cluster_ids)
+
+    for line in file_edges:
+        result.append(f"    {line}")
+
+    result.append("}")
+    return "\n".join(result)
+
+def render_directory_dot(model):
+    """Render a directory model as a Graphviz DOT tree."""
+    result = _dot_header(model['name'])
+    node_ids = NodeIds('node')
+
+    def emit(node, path):
+        node_id = node_ids.get(path)
+        shape = 'folder' if node['type'] == 'directory' else 'note'
+        result.append(f'    {node_id} [label="{_dot_label(node["name"])}", shape={shape}];')
+        for child in node.get('children', []):
+            child_path = f"{path}/{child['name']}"
+            emit(child, child_path)
+            result.append(f"    {node_id} -> {node_ids.get(child_path)} [arrowhead=none];")
+
+    emit(model, model['name'])
+    result.append("}")
+    return "\n".join(result)
+
+# Version of the JSON output schema. Bump it whenever a field is renamed or removed.
+JSON_SCHEMA_VERSION = 1
+
+def _json_document(command, root, **fields):
+    """Wrap command output in the versioned CLIArt JSON envelope."""
+    document = {
+        'schema': 'cliart',
+        'version': JSON_SCHEMA_VERSION,
+        'command': command,
+        'root': root
+    }
+    document.update(fields)
+    return json.dumps(document, indent=2)
+
+def _json_relation_edges(relations, file):
+    """Convert internal relation strings into JSON edge objects."""
+    return [
+        {'kind': kind, 'source': source, 'target': target, 'file': file}
+        for source, kind, target in _relation_edges(relations)
+    ]
+
+def render_relation_json(model):
+    """Render a detailed relation model as versioned JSON."""
+    files = []
+    edges = []
+
+    for file in model['files']:
+        imports = []
+        for edge in sorted(model['import_edges'], key=lambda edge: edge['import']):
+            if edge['source'] == file:
+                imports.append({'raw': edge['import'], 'targets': edge['targets'], 'external': not edge['targets']})
+                if 'cfg' in edge:
+                    imports[-1]['cfg'] = edge['cfg']
+                if 'symbol' in edge:
+                    imports[-1]['symbol'] = edge['symbol']
+                if 'kind' in edge:
+                    imports[-1]['kind'] = edge['kind']
+                if 'via' in edge:
+                    imports[-1]['via'] = edge['via']
+                    imports[-1]['ambiguous'] = edge.get('ambiguous', False)
+
+        files.append({
+            'path': file,
+            'language': model['languages'].get(file),
+            'imports': imports,
+            'exports': sorted(model['exports'].get(file, [])),
+            'symbols': model['symbol_details'].get(file, [])
+        })
+        edges.extend(_json_relation_edges(model['file_relations'].get(file, {}), file))
+
+    for caller, callees in model['function_calls'].items():
+        for callee, file in callees:
+            edges.append({'kind': 'calls', 'source': caller, 'target': callee, 'file': file})
+
+    # Rust unsafe code audit, present at depth 3
+    audit = {key: model[key] for key in ['unsafe', 'unsafe_reach'] if key in model}
+    if model.get('export_collisions'):
+        audit['export_collisions'] = model['export_collisions']
+    if model.get('spring'):
+        audit['spring'] = model['spring']
+
+    languages = sorted(set(language for language in model['languages'].values() if language))
+    return _json_document('relation', model['root'], depth=model['depth'], languages=languages, files=files, edges=edges,
+                          **audit)
+
+def render_code_json(model):
+    """Render a code model as versioned JSON."""
+    files = []
+    edges = []
+
+    for file_info in model['files']:
+        files.append({
+            'path': file_info['path'],
+            'language': file_info['language'],
+            'symbols': file_info['symbols']
+        })
+        edges.extend(_json_relation_edges(file_info['relations'], file_info['path']))
+
+    languages = sorted(set(file_info['language'] for file_info in model['files']))
+    return _json_document('code', model['root'], languages=languages, files=files, edges=edges)
+
+def render_directory_json(model):
+    """Render a directory model as versioned JSON."""
+    return _json_document('directory', model['name'], tree=model)
+
+def _plantuml_name(name):
+    """Reduce a type reference to the bare name PlantUML should link to."""
+    name = re.sub(r'<.*$', '', name.strip())
+    name = re.sub(r'^(?:public|protected|private|virtual)\s+', '', name)
+    return re.split(r'::|\.', name)[-1].strip()
+
+def _plantuml_parents(text, separator=','):
+    """Split a raw parent list into bare type names."""
+    parents = []
+    depth = 0
+    current = ''
+    for char in text or '':
+        if char == '<':
+            depth += 1
+        elif char == '>':
+            depth -= 1
+        if char == separator and depth == 0:
+            parents.append(current)
+            current = ''
+        else:
+            current += char
+    parents.append(current)
+
+    names = []
+    for parent in parents:
+        parent = parent.strip()
+        # Skip keyword arguments (metaclass=...), lifetimes and ?Sized bounds
+        if not parent or '=' in parent or parent.startswith(("'", '?')) or parent == 'object':
+            continue
+        names.append(_plantuml_name(parent))
+    return [name for name in names if name]
+
+def _plantuml_relationships(structure, ref=lambda name: name):
+    """Collect inheritance and realization arrows for one file's code model.
+
+    ref maps a bare type name, as seen from this file, to the name the diagram declares it as.
+    """
+    relationships = []
+    language = structure['language']
+    for cls in structure['classes']:
+        name = ref(_plantuml_name(cls['name']))
+        if cls['kind'] == 'trait':
+            for parent in _plantuml_parents(cls['extends'], '+'):
+                relationships.append(f"{ref(parent)} <|-- {name}")
+            continue
+        if cls['kind'] == 'interface' or language in ['python', 'cpp', 'c++']:
+            parents = _plantuml_parents(cls['extends'])
+        else:
+            parents = _plantuml_parents(cls['extends'])[:1]
+        for parent in parents:
+            relationships.append(f"{ref(parent)} <|-- {name}")
+        for interface in _plantuml_parents(cls['implements']):
+            relationships.append(f"{ref(interface)} <|.. {name}")
+
+    for impl in structure['impls']:
+        if impl['trait']:
+            relationships.append(f"{ref(_plantuml_name(impl['trait']))} <|.. {ref(_plantuml_name(impl['type']))}")
+    return relationships
+
+def render_code_plantuml(model):
+    """Render a code model as a PlantUML class diagram."""
+    result = [
+        "@startuml",
+        "set namespaceSeparator none",
+        f"title Code Diagram for {model['root']}"
+    ]
+
+    # Types are keyed by file and name, so same-named types in different files stay apart.
+    # A name declared in several files gets an alias per file; a reference resolves to the
+    # type in the same file, else to the declaring file that shares the most directories with it.
+    defined_in = {}
+    for file_info in model['files']:
+        for cls in file_info['structure']['classes']:
+            files = defined_in.setdefault(_plantuml_name(cls['name']), [])
+            if file_info['path'] not in files:
+                files.append(file_info['path'])
+    aliases = NodeIds('type')
+
+    def type_key(name, file):
+        files = defined_in.get(name, [])
+        if file in files:
+            return file, name
+        if len(files) <= 1:
+            return (files[0] if files else None), name
+
+        def shared(other):
+            count = 0
+            for a, b in zip(os.path.dirname(file).split(os.sep), os.path.dirname(other).split(os.sep)):
+                if a != b:
+                    break
+                count += 1
+            return count
+        best = max(shared(other) for other in files)
+        closest = [other for other in files if shared(other) == best]
+        return (closest[0] if len(closest) == 1 else None), name
+
+    def type_ref(name, file):
+        key_file, _ = type_key(name, file)
+        return aliases.get(f"{key_file}:{name}") if key_file and len(defined_in[name]) > 1 else name
+
+    # Methods can be declared away from their type (e.g. Rust impl blocks), so collect them first
+    members = {}
+    for file_info in model['files']:
+        for func in file_info['structure']['functions']:
+            if func['owner']:
+                members.setdefault(type_key(_plantuml_name(func['owner']), file_info['path']), []).append(func)
+
+    declared = set()
+    relationships = []
+    for file_info in model['files']:
+        structure = file_info['structure']
+        file = file_info['path']
+        types = [(cls['name'], cls['kind']) for cls in structure['classes']]
+        # Types that only appear in impl blocks, such as `impl Describe for Vec<T>`
+        types += [(impl['type'], 'class') for impl in structure['impls']
+                  if _plantuml_name(impl['type']) not in defined_in]
+
+        body = []
+        for type_name, kind in types:
+            name = _plantuml_name(type_name)
+            key = type_key(name, file)
+            if not name or key in declared:
+                continue
+            declared.add(key)
+
+            ref = type_ref(name, file)
+            label = f'"{name}" as {ref}' if ref != name else name
+            if kind in ['struct', 'record']:
+                declaration = f"class {label} <<{kind}>>"
+            elif kind == 'trait':
+                declaration = f"interface {label} <<trait>>"
+            else:
+                declaration = f"{kind} {label}"
+
+            body.append(f"    {declaration} {{")
+            cls = next((cls for cls in structure['classes'] if cls['name'] == type_name), {})
+            for field in cls.get('fields') or []:
+                visibility = '-' if field['visibility'] == 'private' else '+'
+                static = '{static} ' if field.get('kind') == 'class attribute' or 'static' in field.get('modifiers', []) else ''
+                field_type = f" : {' '.join(field['type'].split())}" if field['type'] else ''
+                body.append(f"        {static}{visibility}{field['name']}{field_type}")
+            for variant in cls.get('variants') or []:
+                payload = ', '.join(' '.join(field['type'].split()) for field in variant['fields'])
+                body.append(f"        {variant['name']}({payload})" if variant['fields'] else f"        {variant['name']}")
+            seen_methods = set()
+            for func in members.get(key, []):
+                params = ' '.join(func['params'].split())
+                signature = f"{func['name']}({params})"
+                if signature in seen_methods:
+                    continue
+                seen_methods.add(signature)
+                visibility = '-' if structure['language'] == 'python' and func['name'].startswith('_') and not func['name'].endswith('__') else '+'
+                body.append(f"        {visibility}{signature}")
+            body.append("    }")
+
+        if body:
+            result.append(f'package "{file_info["path"]}" {{')
+            result.extend(body)
+            result.append("}")
+
+        for relationship in _plantuml_relationships(structure, lambda name, file=file: type_ref(name, file)):
+            if relationship not in relationships:
+                relationships.append(relationship)
+
+    # Rust has-a links from a field's type to another type in the project
+    project_types = {cls['name'] for file_info in model['files'] for cls in file_info['structure']['classes']
+                     if cls['kind'] in ['struct', 'enum']}
+    for file_info in model['files']:
+        if file_info['language'] not in ['rust', 'rs']:
+            continue
+        for owner, field, target in rust_has_a_edges(file_info['structure']['classes'], project_types):
+            relationships.append(f"{type_ref(owner, file_info['path'])} *-- {type_ref(target, file_info['path'])} : {field}")
+
+    # Python has-a links from a field's annotated type to another project class
+    project_classes = {cls['name'] for file_info in model['files'] if file_info['language'] == 'python'
+                       for cls in file_info['structure']['classes']}
+    for file_info in model['files']:
+        if file_info['language'] != 'python':
+            continue
+        for owner, field, target in python_has_a_edges(file_info['structure']['classes'], project_classes):
+            relationships.append(f"{type_ref(owner, file_info['path'])} *-- {type_ref(target, file_info['path'])} : {field}")
+
+    result.extend(relationships)
+    result.append("@enduml")
+    return "\n".join(result)
+
+def _html_report_data(model):
+    """Build the node/link data the HTML report script draws."""
+    nodes = []
+    links = []
+    node_index = {}
+
+    def add_node(key, label, kind, title, sections, search_terms=()):
+        node_index[key] = len(nodes)
+        nodes.append({'label': label, 'kind': kind, 'title': title, 'sections': sections, 'search': list(search_terms)})
+
+    if model['single_file']:
+        symbol_types = model['symbol_types']
+        edges = _relation_edges(model['relations'])
+        for symbol in list(symbol_types.keys()) + [name for edge in edges for name in (edge[0], edge[2])]:
+            if symbol not in node_index:
+                outgoing = [f"{kind.replace('_', ' ')} {target}" for source, kind, target in edges if source == symbol]
+                incoming = [f"{source} ({kind.replace('_', ' ')})" for source, kind, target in edges if target == symbol]
+                add_node(symbol, symbol, 'symbol', f"{symbol} [{symbol_types.get(symbol, 'external')}]",
+                         [{'title': 'Relationships', 'items': outgoing}, {'title': 'Used by', 'items': incoming}])
+        for source, kind, target in edges:
+            links.append({'source': node_index[source], 'target': node_index[target], 'kind': kind})
+        return {'nodes': nodes, 'links': links}
+
+    # Which functions each file defines calls into
+    calls_by_file = {}
+    for caller, callees in model['function_calls'].items():
+        for callee, file in callees:
+            for defining_file in model['symbol_to_file'].get(caller, [file]):
+                calls_by_file.setdefault(defining_file, []).append(f"{caller} → {callee}")
+
+    for file in model['files']:
+        imports = []
+        for edge in model['import_edges']:
+            if edge['source'] == file:
+                condition = f" [{edge['cfg']}]" if edge.get('cfg') else ""
+                if edge.get('kind'):
+                    condition += f" [{IMPORT_KIND_LABELS[edge['kind']]}]"
+                if edge['targets']:
+                    symbol = f": {edge['symbol']}" if edge.get('symbol') else ""
+                    if edge.get('via'):
+                        symbol += f", via {' → '.join(edge['via'])}"
+                    if edge.get('ambiguous'):
+                        symbol += ", ambiguous export *"
+                    imports.extend(f"{edge['import']} (local){condition}" if target == file
+                                   else f"{edge['import']} (from {target}{symbol}){condition}" for target in edge['targets'])
+                else:
+                    imports.append(f"{edge['import']} (external){condition}")
+
+        used_by = []
+        if model['depth'] >= 2:
+            for symbol in model['symbols'].get(file, []):
+                for using_file in model['symbol_usage'].get(symbol, []):
+                    if using_file != file:
+                        used_by.append(f"{symbol} ← {using_file}")
+
+        sections = [
+            {'title': 'Imports', 'items': imports},
+            {'title': 'Exports', 'items': sorted(model['exports'].get(file, []))},
+            {'title': 'Defines', 'items': sorted(model['symbols'].get(file, []))}
+        ]
+        if model['depth'] >= 2:
+            sections.append({'title': 'Used by', 'items': used_by})
+        if model['depth'] >= 3:
+            sections.append({'title': 'Calls', 'items': calls_by_file.get(file, [])})
+
+        add_node(file, os.path.basename(file), 'file', f"{file} ({model['languages'].get(file, 'unknown')})",
+                 sections, model['symbols'].get(file, []))
+
+    for edge in model['import_edges']:
+        if edge['targets']:
+            for target in edge['targets']:
+                if target != edge['source']:
+                    links.append({'source': node_index[edge['source']], 'target': node_index[target],
+                                  'kind': edge.get('kind') or 'local'})
+        else:
+            key = f"external:{edge['import']}"
+            if key not in node_index:
+                importers = sorted(set(e['source'] for e in model['import_edges'] if e['import'] == edge['import']))
+                add_node(key, edge['import'], 'external', f"{edge['import']} (external)",
+                         [{'title': 'Imported by', 'items': importers}])
+            links.append({'source': node_index[edge['source']], 'target': node_index[key], 'kind': 'external'})
+
+    return {'nodes': nodes, 'links': links}
+
+HTML_TEMPLATE = """<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>__TITLE__</title>
+<style>
+  * { box-sizing: border-box; }
+  body { margin: 0; font: 13px/1.4 -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; display: flex; height: 100vh; color: #222; }
+  #sidebar { width: 340px; border-right: 1px solid #ddd; display: flex; flex-direction: column; background: #fafafa; }
+  #sidebar h1 { font-size: 15px; margin: 12px; }
+  #search { margin: 0 12px 8px; padding: 6px 8px; border: 1px solid #ccc; border-radius: 4px; }
+  #list { flex: 1; overflow-y: auto; border-top: 1px solid #ddd; }
+  #list div { padding: 3px 12px; cursor: pointer; white-space: nowrap; overflow: hidden; text-overflow: ellipsis; }
+  #list div:hover, #list div.active { background: #e6f0ff; }
+  #list .match { color: #777; font-size: 11px; }
+  #details { height: 45%; overflow-y: auto; border-top: 1px solid #ddd; padding: 8px 12px; }
+  #details h2 { font-size: 14px; margin: 4px 0 8px; word-break: break-all; }
+  #details h3 { font-size: 12px; margin: 10px 0 4px; text-transform: uppercase; color: #666; }
+  #details ul { margin: 0; padding-left: 18px; }
+  #details li { word-break: break-all; }
+  #graph { flex: 1; position: relative; }
+  svg { width: 100%; height: 100%; cursor: grab; background: #fff; }
+  .link { stroke-opacity: 0.6; }
+  .link.local { stroke: #555; }
+  .link.external { stroke: #aaa; stroke-dasharray: 4 3; }
+  .link.dynamic { stroke: #d80; stroke-dasharray: 6 3; }
+  .link.type { stroke: #84c; stroke-dasharray: 2 3; }
+  .link.inherits { stroke: #2a7; }
+  .link.has_method { stroke: #27a; }
+  .link.has_a { stroke: #72a; }
+  .link.uses { stroke: #a72; stroke-dasharray: 2 2; }
+  .link.injects { stroke: #088; stroke-dasharray: 5 2; }
+  .link.provides { stroke: #088; }
+  .node circle { stroke: #fff; stroke-width: 1.5px; cursor: pointer; }
+  .node.file circle { fill: #4a7bd0; }
+  .node.external circle { fill: #bbb; }
+  .node.symbol circle { fill: #d07a4a; }
+  .node text { font-size: 10px; pointer-events: none; fill: #333; }
+  .node.selected circle { stroke: #e33; stroke-width: 3px; }
+  .node.dim, .link.dim { opacity: 0.15; }
+  #hint { position: absolute; bottom: 8px; right: 12px; color: #999; font-size: 11px; }
+</style>
+</head>
+<body>
+<div id="sidebar">
+  <h1>__TITLE__</h1>
+  <input id="search" type="search" placeholder="Search files and symbols...">
+  <div id="list"></div>
+  <div id="details"><p>Select a node to see its imports, exports, users and calls.</p></div>
+</div>
+<div id="graph">
+  <svg id="svg"><g id="viewport"><g id="links"></g><g id="nodes"></g></g></svg>
+  <div id="hint">Scroll to zoom, drag the background to pan, drag nodes to move them.</div>
+</div>
+<script>
+const DATA = __DATA__;
+(function () {
+  const SVG_NS = 'http://www.w3.org/2000/svg';
+  const svg = document.getElementById('svg');
+  const viewport = document.getElementById('viewport');
+  const nodes = DATA.nodes;
+  const links = DATA.links;
+  let view = { x: 0, y: 0, k: 1 };
+  let selected = null;
+
+  // Initial positions on a circle so the layout starts untangled
+  nodes.forEach(function (node, i) {
+    const angle = 2 * Math.PI * i / Math.max(nodes.length, 1);
+    const radius = 40 + 12 * Math.sqrt(nodes.length) * 4;
+    node.x = radius * Math.cos(angle);
+    node.y = radius * Math.sin(angle);
+    node.vx = 0;
+    node.vy = 0;
+    node.degree = 0;
+  });
+  links.forEach(function (link) { nodes[link.source].degree++; nodes[link.target].degree++; });
+
+  const linkElements = links.map(function (link) {
+    const line = document.createElementNS(SVG_NS, 'line');
+    line.setAttribute('class', 'link ' + link.kind);
+    document.getElementById('links').appendChild(line);
+    return line;
+  });
+
+  const nodeElements = nodes.map(function (node, i) {
+    const group = document.createElementNS(SVG_NS, 'g');
+    group.setAttribute('class', 'node ' + node.kind);
+    const circle = document.createElementNS(SVG_NS, 'circle');
+    circle.setAttribute('r', 5 + Math.min(node.degree, 12));
+    const label = document.createElementNS(SVG_NS, 'text');
+    label.setAttribute('dx', 8 + Math.min(node.degree, 12));
+    label.setAttribute('dy', 3);
+    label.textContent = node.label;
+    const title = document.createElementNS(SVG_NS, 'title');
+    title.textContent = node.title;
+    group.appendChild(circle);
+    group.appendChild(label);
+    group.appendChild(title);
+    group.addEventListener('mousedown', function (event) { startDrag(event, node); });
+    group.addEventListener('click', function (event) { event.stopPropagation(); select(i); });
+    document.getElementById('nodes').appendChild(group);
+    return group;
+  });
+
+  // Force simulation: pairwise repulsion, springs along links and gravity to the center
+  let alpha = 1;
+  function tick() {
+    for (let i = 0; i < nodes.length; i++) {
+      for (let j = i + 1; j < nodes.length; j++) {
+        const a = nodes[i], b = nodes[j];
+        let dx = b.x - a.x, dy = b.y - a.y;
+        let distance2 = dx * dx + dy * dy || 0.01;
+        if (distance2 > 250000) continue;
+        const force = 900 * alpha / distance2;
+        const distance = Math.sqrt(distance2);
+        dx /= distance; dy /= distance;
+        a.vx -= dx * force; a.vy -= dy * force;
+        b.vx += dx * force; b.vy += dy * force;
+      }
+    }
+    links.forEach(function (link) {
+      const a = nodes[link.source], b = nodes[link.target];
+      const dx = b.x - a.x, dy = b.y - a.y;
+      const distance = Math.sqrt(dx * dx + dy * dy) || 0.01;
+      const force = (distance - 80) * 0.03 * alpha;
+      a.vx += dx / distance * force; a.vy += dy / distance * force;
+      b.vx -= dx / distance * force; b.vy -= dy / distance * force;
+    });
+    nodes.forEach(function (node) {
+      node.vx -= node.x * 0.002 * alpha;
+      node.vy -= node.y * 0.002 * alpha;
+      if (!node.fixed) {
+        node.x += node.vx;
+        node.y += node.vy;
+      }
+      node.vx *= 0.6;
+      node.vy *= 0.6;
+    });
+    alpha = Math.max(alpha * 0.985, 0.002);
+  }
+
+  function draw() {
+    links.forEach(function (link, i) {
+      const a = nodes[link.source], b = nodes[link.target];
+      linkElements[i].setAttribute('x1', a.x);
+      linkElements[i].setAttribute('y1', a.y);
+      linkElements[i].setAttribute('x2', b.x);
+      linkElements[i].setAttribute('y2', b.y);
+    });
+    nodes.forEach(function (node, i) {
+      nodeElements[i].setAttribute('transform', 'translate(' + node.x + ',' + node.y + ')');
+    });
+  }
+
+  function frame() {
+    if (alpha > 0.002) {
+      tick();
+      draw();
+    }
+    requestAnimationFrame(frame);
+  }
+
+  function applyView() {
+    viewport.setAttribute('transform', 'translate(' + view.x + ',' + view.y + ') scale(' + view.k + ')');
+  }
+
+  function centerOn(node) {
+    const rect = svg.getBoundingClientRect();
+    view.x = rect.width / 2 - node.x * view.k;
+    view.y = rect.height / 2 - node.y * view.k;
+    applyView();
+  }
+
+  // Zoom around the cursor
+  svg.addEventListener('wheel', function (event) {
+    event.preventDefault();
+    const rect = svg.getBoundingClientRect();
+    const mx = event.clientX - rect.left, my = event.clientY - rect.top;
+    const factor = event.deltaY < 0 ? 1.15 : 1 / 1.15;
+    const k = Math.min(Math.max(view.k * factor, 0.05), 8);
+    view.x = mx - (mx - view.x) * k / view.k;
+    view.y = my - (my - view.y) * k / view.k;
+    view.k = k;
+    applyView();
+  }, { passive: false });
+
+  // Pan by dragging the background, move nodes by dragging them
+  let drag = null;
+  svg.addEventListener('mousedown', function (event) {
+    if (!drag) drag = { pan: true, x: event.clientX, y: event.clientY, vx: view.x, vy: view.y };
+  });
+  function startDrag(event, node) {
+    drag = { node: node, moved: false };
+    node.fixed = true;
+    event.stopPropagation();
+  }
+  window.addEventListener('mousemove', function (event) {
+    if (!drag) return;
+    if (drag.pan) {
+      view.x = drag.vx + event.clientX - drag.x;
+      view.y = drag.vy + event.clientY - drag.y;
+      applyView();
+    } else {
+      const rect = svg.getBoundingClientRect();
+      drag.node.x = (event.clientX - rect.left - view.x) / view.k;
+      drag.node.y = (event.clientY - rect.top - view.y) / view.k;
+      alpha = Math.max(alpha, 0.1);
+      draw();
+    }
+  });
+  window.addEventListener('mouseup', function () {
+    if (drag && drag.node) drag.node.fixed = false;
+    drag = null;
+  });
+  svg.addEventListener('click', function () { select(null); });
+
+  function escapeHtml(text) {
+    return String(text).replace(/[&<>"]/g, function (c) {
+      return { '&': '&amp;', '<': '&lt;', '>': '&gt;', '"': '&quot;' }[c];
+    });
+  }
+
+  function select(index) {
+    selected = index;
+    const neighbours = new Set();
+    links.forEach(function (link, i) {
+      const touches = index !== null && (link.source === index || link.target === index);
+      if (touches) { neighbours.add(link.source); neighbours.add(link.target); }
+      linkElements[i].classList.toggle('dim', index !== null && !touches);
+    });
+    nodeElements.forEach(function (element, i) {
+      element.classList.toggle('selected', i === index);
+      element.classList.toggle('dim', index !== null && i !== index && !neighbours.has(i));
+    });
+
+    const details = document.getElementById('details');
+    if (index === null) {
+      details.innerHTML = '<p>Select a node to see its imports, exports, users and calls.</p>';
+      return;
+    }
+    const node = nodes[index];
+    let html = '<h2>' + escapeHtml(node.title) + '</h2>';
+    node.sections.forEach(function (section) {
+      html += '<h3>' + escapeHtml(section.title) + ' (' + section.items.length + ')</h3>';
+      if (section.items.length) {
+        html += '<ul>' + section.items.map(function (item) { return '<li>' + escapeHtml(item) + '</li>'; }).join('') + '</ul>';
+      } else {
+        html += '<p>None</p>';
+      }
+    });
+    details.innerHTML = html;
+    renderList();
+  }
+
+  function renderList() {
+    const query = document.getElementById('search').value.trim().toLowerCase();
+    const list = document.getElementById('list');
+    list.innerHTML = '';
+    nodes.forEach(function (node, i) {
+      let match = '';
+      if (query) {
+        if (node.title.toLowerCase().indexOf(query) === -1) {
+          const symbol = node.search.find(function (term) { return term.toLowerCase().indexOf(query) !== -1; });
+          if (!symbol) return;
+          match = symbol;
+        }
+      }
+      const item = document.createElement('div');
+      item.innerHTML = escapeHtml(node.title) + (match ? ' <span class="match">defines ' + escapeHtml(match) + '</span>' : '');
+      item.title = node.title;
+      if (i === selected) item.className = 'active';
+      item.addEventListener('click', function () { select(i); centerOn(node); });
+      list.appendChild(item);
+    });
+  }
+
+  document.getElementById('search').addEventListener('input', renderList);
+  const rect = svg.getBoundingClientRect();
+  view.x = rect.width / 2;
+  view.y = rect.height / 2;
+  applyView();
+  renderList();
+  frame();
+})();
+</script>
+</body>
+</html>
+"""
+
+def render_relation_html(model):
+    """Render a relation model as a self-contained interactive HTML report."""
+    title = f"Code Relation Report for: {model['root']}"
+    # Keep the embedded JSON from closing the script tag early
+    data = json.dumps(_html_report_data(model)).replace('</', '<\\/')
+    title_html = title.replace('&', '&amp;').replace('<', '&lt;').replace('>', '&gt;')
+    return HTML_TEMPLATE.replace('__TITLE__', title_html).replace('__DATA__', data)
+
+def _crate_edge_label(edge):
+    """Describe a crate dependency edge: version requirement, kind, target cfg and optionality."""
+    parts = [edge['requirement']]
+    if edge['kind'] != 'normal':
+        parts.append(edge['kind'])
+    if edge['cfg']:
+        parts.append(edge['cfg'])
+    if edge['optional']:
+        parts.append('optional')
+    if edge['alias']:
+        parts.append(f"as {edge['alias']}")
+    return ', '.join(parts)
+
+def render_crates_mermaid(graph):
+    """Render a Cargo crate graph as a Mermaid flowchart."""
+    result = ["graph LR"]
+    crate_ids = NodeIds('crate')
+    external_ids = NodeIds('ext')
+
+    workspace_members = [member for workspace in graph['workspaces'] for member in workspace['members']]
+
+    def crate_node(crate):
+        label = f"{crate['name']} {crate['version']}" if crate['version'] else crate['name']
+        return f'{crate_ids.get(crate["name"])}["{_mermaid_label(label)}"]'
+
+    if workspace_members:
+        result.append('    subgraph workspace ["Workspace"]')
+        for crate in graph['crates']:
+            if crate['name'] in workspace_members:
+                result.append(f"        {crate_node(crate)}")
+        result.append("    end")
+    for crate in graph['crates']:
+        if crate['name'] not in workspace_members:
+            result.append(f"    {crate_node(crate)}")
+
+    if graph['external']:
+        result.append('    subgraph external ["External Crates"]')
+        for name, external in graph['external'].items():
+            label = f"{name} {' | '.join(external['requirements'])}"
+            result.append(f'        {external_ids.get(name)}["{_mermaid_label(label)}"]')
+        result.append("    end")
+
+    for edge in graph['edges']:
+        target_id = crate_ids.get(edge['target']) if edge['internal'] else external_ids.get(edge['target'])
+        arrow = '-->' if edge['kind'] == 'normal' else '-.->'
+        label = _mermaid_label(_crate_edge_label(edge))
+        result.append(f'    {crate_ids.get(edge["source"])} {arrow}|"{label}"| {target_id}')
+
+    return "\n".join(result)
+
+def render_crates_dot(graph):
+    """Render a Cargo crate graph as Graphviz DOT, with workspace members in a cluster."""
+    result = _dot_header(graph['root'])
+    crate_ids = NodeIds('crate')
+    external_ids = NodeIds('ext')
+
+    workspace_members = [member for workspace in graph['workspaces'] for member in workspace['members']]
+
+    def crate_node(crate):
+        label = _dot_label(crate['name'])
+        if crate['version']:
+            label += f"\\n{_dot_label(crate['version'])}"
+        return f'{crate_ids.get(crate["name"])} [label="{label}", shape=component];'
+
+    if workspace_members:
+        result.append("    subgraph cluster_workspace {")
+        result.append('        label="workspace";')
+        for crate in graph['crates']:
+            if crate['name'] in workspace_members:
+                result.append(f"        {crate_node(crate)}")
+        result.append("    }")
+    for crate in graph['crates']:
+        if crate['name'] not in workspace_members:
+            result.append(f"    {crate_node(crate)}")
+
+    if graph['external']:
+        result.append("    subgraph cluster_external {")
+        result.append('        label="external crates";')
+        result.append('        style=dashed;')
+        for name, external in graph['external'].items():
+            label = f"{_dot_label(name)}\\n{_dot_label(' | '.join(external['requirements']))}"
+            result.append(f'        {external_ids.get(name)} [label="{label}", shape=box, style=rounded];')
+        result.append("    }")
+
+    for edge in graph['edges']:
+        target_id = crate_ids.get(edge['target']) if edge['internal'] else external_ids.get(edge['target'])
+        if edge['kind'] != 'normal':
+            kind = edge['kind']
+        else:
+            kind = 'local' if edge['internal'] else 'external'
+        result.append(f"    {_dot_edge(crate_ids.get(edge['source']), target_id, kind, _crate_edge_label(edge))}")
+
+    result.append("}")
+    return "\n".join(result)
+
+def render_crates_json(graph):
+    """Render a Cargo crate graph as versioned JSON."""
+    return _json_document('crates', graph['root'], workspaces=graph['workspaces'], crates=graph['crates'],
+                          edges=graph['edges'], external=graph['external'])
+
+def render_traits_json(matrix):
+    """Render a Rust trait matrix as versioned JSON."""
+    traits = [dict(trait, name=name) for name, trait in matrix['traits'].items()]
+    return _json_document('traits', matrix['root'], types=matrix['types'], traits=traits, impls=matrix['impls'])
+
+def render_api_json(surface):
+    """Render the public API surface of Rust crates as versioned JSON."""
+    return _json_document('api', surface['root'], crates=surface['crates'])
+
+def render_components_mermaid(tree):
+    """Render a component tree as a Mermaid flowchart, labelling each edge with its props."""
+    result = ["graph TD"]
+    component_ids = NodeIds('component')
+    external_ids = NodeIds('ext')
+
+    for component in tree['components']:
+        label = f"{component['name']} ({component['file']})"
+        result.append(f'    {component_ids.get(component["id"])}["{_mermaid_label(label)}"]')
+
+    external = {}
+    for usage in tree['usages']:
+        if not usage['child']:
+            external.setdefault(f"{usage['source'] or ''}:{usage['name']}", usage)
+    if external:
+        result.append('    subgraph external ["External Components"]')
+        for key, usage in external.items():
+            label = f"{usage['name']} ({usage['source']})" if usage['source'] else usage['name']
+            result.append(f'        {external_ids.get(key)}["{_mermaid_label(label)}"]')
+        result.append("    end")
+
+    for usage in tree['usages']:
+        if usage['child']:
+            target_id = component_ids.get(usage['child'])
+        else:
+            target_id = external_ids.get(f"{usage['source'] or ''}:{usage['name']}")
+        source_id = component_ids.get(usage['parent'])
+        if usage['props']:
+            # Props may hold JSX, which Mermaid would otherwise read as HTML
+            label = _mermaid_label(", ".join(usage['props'])).replace('<', '#lt;').replace('>', '#gt;')
+            result.append(f'    {source_id} -->|"{label}"| {target_id}')
+        else:
+            result.append(f"    {source_id} --> {target_id}")
+
+    return "\n".join(result)
+
+def render_components_json(tree):
+    """Render a component tree as versioned JSON."""
+    return _json_document('components', tree['root'], components=tree['components'], usages=tree['usages'],
+                          roots=tree['roots'])
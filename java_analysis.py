@@ -0,0 +1,617 @@
+"""
+Java source analysis for CLIArt.
+
+Types are identified by their fully qualified names: the file's `package` declaration plus
+the type's name, with nested types under their enclosing type. Imports resolve through these
+names to the files that declare the types, whatever the source root: single-type, on-demand
+(`com.acme.*`) and static imports, plus the types of the same package, which need no import.
+
+Declarations are read with their annotations, from which the Spring (and Jakarta CDI) bean
+graph is built: the beans a project declares, what each has injected, and the HTTP endpoints
+its controllers map.
+"""
+
+import re
+
+JAVA_MODIFIERS = {'public', 'protected', 'private', 'static', 'final', 'abstract', 'sealed', 'non-sealed', 'strictfp',
+                  'synchronized', 'native', 'transient', 'volatile', 'default'}
+JAVA_PACKAGE_PATTERN = re.compile(r'^\s*package\s+([\w$.]+)\s*;', re.MULTILINE)
+JAVA_IMPORT_PATTERN = re.compile(r'^\s*import\s+(static\s+)?([\w$]+(?:\s*\.\s*[\w$]+)*)(\s*\.\s*\*)?\s*;', re.MULTILINE)
+# A capitalized simple name, or a qualified name such as com.acme.Order
+JAVA_TYPE_REFERENCE_PATTERN = re.compile(r'(?<![\w$.])((?:[a-z_][\w$]*\.)*[A-Z][\w$]*)')
+
+def mask_java_source(content):
+    """Blank out comments and the contents of string, text block and char literals.
+
+    The result has the same length and line breaks as the input, so positions found in
+    the masked text are positions in the source.
+    """
+    chars = list(content)
+    length = len(content)
+    i = 0
+
+    def blank(start, end):
+        for j in range(start, min(end, length)):
+            if chars[j] != '\n':
+                chars[j] = ' '
+
+    while i < length:
+        if content.startswith('//', i):
+            end = content.find('\n', i)
+            end = length if end == -1 else end
+            blank(i, end)
+            i = end
+        elif content.startswith('/*', i):
+            end = content.find('*/', i + 2)
+            end = length if end == -1 else end + 2
+            blank(i, end)
+            i = end
+        elif content.startswith('"""', i):
+            end = content.find('"""', i + 3)
+            end = length if end == -1 else end
+            blank(i + 3, end)
+            i = end + 3
+        elif content[i] in '"\'':
+            quote = content[i]
+            j = i + 1
+            while j < length and content[j] != quote and content[j] != '\n':
+                j += 2 if content[j] == '\\' else 1
+            blank(i + 1, j)
+            i = j + 1
+        else:
+            i += 1
+    return ''.join(chars)
+
+def _line_of(content, pos):
+    return content.count('\n', 0, pos) + 1
+
+def find_java_package(content):
+    """Return the package a Java file declares, or '' for the default package."""
+    match = JAVA_PACKAGE_PATTERN.search(mask_java_source(content))
+    return match.group(1) if match else ''
+
+def find_java_imports(content):
+    """Return a Java file's imports as {'name', 'static', 'wildcard', 'line'}.
+
+    name is the imported type (`com.acme.Order`), static member (`com.acme.Money.format`),
+    package (`com.acme` for `com.acme.*`) or type whose static members or nested types are
+    imported on demand.
+    """
+    masked = mask_java_source(content)
+    return [{'name': re.sub(r'\s+', '', match.group(2)), 'static': bool(match.group(1)), 'wildcard': bool(match.group(3)),
+             'line': _line_of(masked, match.start())}
+            for match in JAVA_IMPORT_PATTERN.finditer(masked)]
+
+def java_import_string(imported):
+    """Format an import from find_java_imports as written, e.g. `com.acme.*`."""
+    return imported['name'] + ('.*' if imported['wildcard'] else '')
+
+def _closing(masked, pos, open_char, close_char):
+    """Return the index just past the bracket closing the one at pos."""
+    depth = 0
+    for i in range(pos, len(masked)):
+        if masked[i] == open_char:
+            depth += 1
+        elif masked[i] == close_char:
+            depth -= 1
+            if depth == 0:
+                return i + 1
+    return len(masked)
+
+def _split_top_level(text):
+    """Split text at the commas outside brackets and generic type arguments."""
+    parts = []
+    depth = 0
+    start = 0
+    for i, char in enumerate(text):
+        if char in '([{<':
+            depth += 1
+        elif char in ')]}>':
+            depth -= 1
+        elif char == ',' and depth == 0:
+            parts.append(text[start:i])
+            start = i + 1
+    parts.append(text[start:])
+    return [part.strip() for part in parts if part.strip()]
+
+def _read_modifiers(masked, content, pos, end):
+    """Read the annotations and modifiers at the start of a declaration.
+
+    Returns (annotations, modifiers, position after them). Annotations are {'name', 'args'}
+    with the arguments as written, e.g. {'name': 'GetMapping', 'args': '"/{id}"'}.
+    """
+    annotations = []
+    modifiers = []
+    while pos < end:
+        while pos < end and masked[pos].isspace():
+            pos += 1
+        annotation = re.compile(r'@\s*([\w$]+(?:\s*\.\s*[\w$]+)*)').match(masked, pos, end)
+        if annotation and annotation.group(1) != 'interface':
+            pos = annotation.end()
+            args = ''
+            after = pos
+            while after < end and masked[after].isspace():
+                after += 1
+            if after < end and masked[after] == '(':
+                close = _closing(masked, after, '(', ')')
+                args = ' '.join(content[after + 1:close - 1].split())
+                pos = close
+            annotations.append({'name': re.sub(r'\s+', '', annotation.group(1)), 'args': args})
+            continue
+        word = re.compile(r'[\w$-]+').match(masked, pos, end)
+        if word and word.group(0) in JAVA_MODIFIERS:
+            modifiers.append(word.group(0))
+            pos = word.end()
+            continue
+        break
+    return annotations, modifiers, pos
+
+def _parse_java_parameters(masked, content, start, end):
+    """Parse a parameter list as [{'name', 'type', 'annotations'}]."""
+    params = []
+    offset = start
+    for part in _split_top_level(masked[start:end]):
+        part_start = masked.find(part, offset, end)
+        offset = part_start + len(part)
+        annotations, _, pos = _read_modifiers(masked, content, part_start, offset)
+        declaration = re.match(r'(.+?)\s*(\.\.\.)?\s*\b([\w$]+)\s*((?:\[\s*\])*)$', ' '.join(content[pos:offset].split()))
+        if declaration:
+            params.append({'name': declaration.group(3),
+                           'type': declaration.group(1) + ('...' if declaration.group(2) else '') + declaration.group(4),
+                           'annotations': annotations})
+    return params
+
+def _type_names(text):
+    """Split an extends or implements clause into type names without generic arguments."""
+    return [re.sub(r'<.*', '', name).strip() for name in _split_top_level(text or '')]
+
+def parse_java_declarations(content):
+    """Parse the type and member declarations of a Java file.
+
+    Returns (types, members). Types are {'name', 'qualname', 'kind', 'annotations',
+    'modifiers', 'public', 'extends', 'implements', 'components', 'line', 'start', 'body',
+    'end'}, where qualname includes enclosing types (`Order.Line`), components are a record's
+    parameters, and body and end delimit the braces.
+    Members are {'owner', 'kind', 'name', 'type', 'params', 'annotations', 'modifiers',
+    'line'} with kind 'field', 'method' or 'constructor'; owner is the type's qualname, type
+    is a field's type or a method's return type, and params are {'name', 'type', 'annotations'}.
+    """
+    masked = mask_java_source(content)
+    types = []
+    members = []
+
+    def declaration(start, end, owner, body):
+        """Record the declaration spanning start..end, whose block (if any) is body."""
+        annotations, modifiers, pos = _read_modifiers(masked, content, start, end)
+        rest = masked[pos:end]
+        if not rest.strip() or re.match(r'\s*(package|import)\b', rest):
+            return
+        line = _line_of(masked, pos + len(rest) - len(rest.lstrip()))
+        type_match = re.match(r'\s*(class|interface|enum|record|@\s*interface)\s+([\w$]+)', rest)
+        if type_match:
+            kind = 'annotation' if type_match.group(1).startswith('@') else type_match.group(1)
+            header = ' '.join(content[pos + type_match.end():end].split())
+            header = re.sub(r'^<[^{]*?>(?=\s*(?:\(|extends|implements|permits|$))', '', header)
+            components = []
+            if kind == 'record':
+                # The record header's components, which are also its canonical constructor's parameters
+                paren = masked.find('(', pos + type_match.end(), end)
+                if paren != -1:
+                    components = _parse_java_parameters(masked, content, paren + 1, _closing(masked, paren, '(', ')') - 1)
+                    header = ' '.join(content[_closing(masked, paren, '(', ')'):end].split())
+            extends = re.search(r'\bextends\s+(.+?)(?=\s+(?:implements|permits)\b|$)', header)
+            implements = re.search(r'\bimplements\s+(.+?)(?=\s+permits\b|$)', header)
+            java_type = {'name': type_match.group(2), 'qualname': f"{owner['qualname']}.{type_match.group(2)}" if owner else type_match.group(2),
+                         'kind': kind, 'annotations': annotations, 'modifiers': modifiers, 'public': 'public' in modifiers,
+                         'extends': _type_names(extends.group(1)) if extends else [],
+                         'implements': _type_names(implements.group(1)) if implements else [], 'components': components,
+                         'line': line, 'start': pos + rest.index(type_match.group(1)),
+                         'body': body[0] if body else None, 'end': body[1] if body else end}
+            types.append(java_type)
+            if body:
+                scan(body[0] + 1, body[1] - 1, java_type)
+            return
+        if owner is None:
+            return
+        paren = rest.find('(')
+        equals = rest.find('=')
+        if paren != -1 and (equals == -1 or paren < equals):
+            # A method or constructor: [<T>] [ReturnType] name(params) [throws ...]
+            signature = re.match(r'\s*(?:<.*?>\s*)?(?:(.*?)\s+)?([\w$]+)\s*$', ' '.join(masked[pos:pos + paren].split()))
+            if not signature:
+                return
+            close = _closing(masked, pos + paren, '(', ')')
+            return_type = ' '.join(content[pos:pos + paren].split())
+            return_type = re.sub(r'^<.*?>\s*', '', return_type)[:-len(signature.group(2))].strip() or None
+            members.append({'owner': owner['qualname'], 'kind': 'method' if return_type else 'constructor',
+                            'name': signature.group(2), 'type': return_type,
+                            'params': _parse_java_parameters(masked, content, pos + paren + 1, close - 1),
+                            'annotations': annotations, 'modifiers': modifiers, 'line': line})
+            return
+        # A field, possibly declaring several variables
+        variables = _split_top_level(' '.join(masked[pos:end].split()))
+        field = re.match(r'(.+?)\s+([\w$]+)\s*((?:\[\s*\])*)$', variables[0].split('=')[0].strip()) if variables else None
+        if field:
+            names = [field.group(2)] + [variable.split('=')[0].strip() for variable in variables[1:]]
+            for name in names:
+                members.append({'owner': owner['qualname'], 'kind': 'field', 'name': name,
+                                'type': field.group(1) + field.group(3), 'params': [],
+                                'annotations': annotations, 'modifiers': modifiers, 'line': line})
+
+    def scan(start, end, owner):
+        """Split a file or type body into declarations, recursing into nested types."""
+        i = start
+        if owner and owner['kind'] == 'enum':
+            # Skip the enum constants, which end at the first top-level semicolon
+            while i < end and masked[i] != ';':
+                i = _closing(masked, i, masked[i], ')' if masked[i] == '(' else '}') if masked[i] in '({' else i + 1
+            i += 1
+        segment = i
+        initializer = False
+        while i < end:
+            char = masked[i]
+            if char == '(':
+                i = _closing(masked, i, '(', ')')
+                continue
+            if char == '=' and masked[i + 1:i + 2] != '=':
+                initializer = True
+            elif char == ';':
+                declaration(segment, i, owner, None)
+                segment = i + 1
+                initializer = False
+            elif char == '{':
+                close = _closing(masked, i, '{', '}')
+                if not initializer:
+                    # A type or method body, or an initializer block
+                    declaration(segment, i, owner, (i, close))
+                    segment = close
+                i = close
+                continue
+            i += 1
+        declaration(segment, end, owner, None)
+
+    scan(0, len(masked), None)
+    types.sort(key=lambda java_type: java_type['start'])
+    return types, members
+
+def find_java_types(content):
+    """Return the classes, interfaces, enums, records and annotation types a Java file declares.
+    See parse_java_declarations for their fields."""
+    return parse_java_declarations(content)[0]
+
+def find_java_type_references(content):
+    """Return the type names a Java file's code mentions, simple (`Order`) or qualified
+    (`com.acme.Order`), leaving out its package and import declarations."""
+    masked = mask_java_source(content)
+    masked = JAVA_IMPORT_PATTERN.sub('', JAVA_PACKAGE_PATTERN.sub('', masked))
+    return set(JAVA_TYPE_REFERENCE_PATTERN.findall(masked))
+
+def resolve_java_imports(java_files):
+    """Resolve each Java file's imports and same-package references to the declaring files.
+
+    java_files maps absolute file paths to the relative paths used in diagrams. The result
+    maps each relative path to {import string: [relative target paths]}. Import strings are
+    the imports as written (see java_import_string); an on-demand import resolves to the
+    files of the types the file mentions. Types of the file's own package that it mentions
+    without importing appear under their fully qualified names, as do fully qualified
+    references in code. The JDK and libraries stay unresolved.
+    """
+    declared = {}  # fully qualified name -> files
+    packages = {}  # package -> {top-level type name -> files}
+    parsed = {}
+    for file_path in java_files:
+        try:
+            with open(file_path, 'r', encoding='utf-8', errors='ignore') as f:
+                content = f.read()
+        except (OSError, IOError):
+            continue
+        package = find_java_package(content)
+        parsed[file_path] = (package, find_java_imports(content), find_java_type_references(content))
+        for java_type in find_java_types(content):
+            qualified = f"{package}.{java_type['qualname']}" if package else java_type['qualname']
+            declared.setdefault(qualified, []).append(file_path)
+            if '.' not in java_type['qualname']:
+                packages.setdefault(package, {}).setdefault(java_type['name'], []).append(file_path)
+
+    resolved = {}
+    for file_path, (package, imports, references) in parsed.items():
+        simple_references = {reference.split('.')[0] for reference in references}
+        file_imports = {}
+
+        def add(imported, files):
+            targets = [java_files[target] for target in dict.fromkeys(files) if target != file_path]
+            if targets:
+                file_imports.setdefault(imported, [])
+                file_imports[imported].extend(target for target in targets if target not in file_imports[imported])
+
+        # Single-type imports shadow the package's own types, which shadow on-demand imports
+        single = {imported['name'].split('.')[-1] for imported in imports if not imported['wildcard']}
+        own = {name for name in packages.get(package, {}) if name in simple_references and name not in single}
+
+        for imported in imports:
+            name = imported['name']
+            if imported['static']:
+                # The type owning the imported static member(s)
+                add(java_import_string(imported), declared.get(name if imported['wildcard'] else name.rsplit('.', 1)[0], []))
+            elif imported['wildcard']:
+                # A package's types, or a type's nested types
+                nested = {qualified.split('.')[-1]: files for qualified, files in declared.items()
+                          if qualified.rsplit('.', 1)[0] == name}
+                members = dict(packages.get(name, {}), **nested)
+                add(java_import_string(imported), [target for type_name, files in sorted(members.items())
+                                                   if type_name in simple_references and type_name not in single | own
+                                                   for target in files])
+            else:
+                add(name, declared.get(name, []))
+
+        for type_name in sorted(own):
+            add(f"{package}.{type_name}" if package else type_name, packages[package][type_name])
+        for reference in sorted(references):
+            if '.' in reference and reference in declared:
+                add(reference, declared[reference])
+
+        if file_imports:
+            resolved[java_files[file_path]] = file_imports
+    return resolved
+
+# Class annotations that make a type a Spring or Jakarta (CDI) managed bean
+BEAN_STEREOTYPES = {'Component', 'Service', 'Repository', 'Controller', 'RestController', 'Configuration',
+                    'SpringBootApplication', 'Named', 'ApplicationScoped', 'RequestScoped', 'SessionScoped',
+                    'Dependent', 'Singleton'}
+# Methods whose return value is a bean: Spring @Bean and CDI @Produces
+BEAN_PRODUCERS = {'Bean', 'Produces'}
+INJECT_ANNOTATIONS = {'Autowired', 'Inject'}
+# Spring Data interfaces, implemented at runtime, that make a repository interface a bean
+SPRING_DATA_REPOSITORIES = {'Repository', 'CrudRepository', 'ListCrudRepository', 'PagingAndSortingRepository',
+                            'JpaRepository', 'MongoRepository', 'ReactiveCrudRepository'}
+# Wrappers injected for their type argument, and containers receiving every matching bean
+INJECTION_WRAPPERS = {'Optional', 'Provider', 'ObjectProvider', 'ObjectFactory', 'Instance', 'Lazy'}
+INJECTION_COLLECTIONS = {'List', 'Set', 'Collection', 'Iterable', 'Map'}
+SPRING_MAPPINGS = {'GetMapping': 'GET', 'PostMapping': 'POST', 'PutMapping': 'PUT', 'DeleteMapping': 'DELETE',
+                   'PatchMapping': 'PATCH', 'RequestMapping': None}
+JAX_RS_METHODS = {'GET', 'POST', 'PUT', 'DELETE', 'PATCH', 'HEAD', 'OPTIONS'}
+
+def java_annotation(item, names):
+    """Return the first annotation of a type, member or parameter with one of the given simple
+    names, whether written qualified or not, or None."""
+    for annotation in item['annotations']:
+        if annotation['name'].split('.')[-1] in names:
+            return annotation
+    return None
+
+def java_annotation_values(args, attribute='value'):
+    """Return the string literals given to an annotation attribute.
+
+    `value` is also the attribute of an annotation's unnamed argument, so
+    `@GetMapping("/a")` and `@GetMapping(value = {"/a", "/b"})` both give their paths.
+    """
+    for part in _split_top_level(args):
+        named = re.match(r'([\w$]+)\s*=\s*(.*)$', part, re.DOTALL)
+        if (named.group(1) if named else 'value') == attribute:
+            return re.findall(r'"((?:[^"\\]|\\.)*)"', named.group(2) if named else part)
+    return []
+
+def _simple_type(type_name):
+    """Reduce a type as written (`java.util.List<Order>[]`) to its simple name (`List`)."""
+    type_name = re.sub(r'@[\w$.]+(?:\([^)]*\))?\s*', '', type_name)
+    return re.sub(r'<.*|\[.*|\.\.\.$', '', type_name).strip().split('.')[-1]
+
+def java_injected_type(type_name):
+    """Return (simple type, whether every matching bean is injected) for an injection point,
+    looking through wrappers such as Optional and collections such as List."""
+    type_name = re.sub(r'@[\w$.]+(?:\([^)]*\))?\s*', '', type_name).strip()
+    outer = _simple_type(type_name)
+    arguments = re.search(r'<(.*)>', type_name)
+    if arguments and outer in INJECTION_WRAPPERS | INJECTION_COLLECTIONS:
+        inner, collection = java_injected_type(_split_top_level(arguments.group(1))[-1])
+        return inner, collection or outer in INJECTION_COLLECTIONS
+    return outer, False
+
+def _bean_name(name):
+    """Spring's default bean name: the decapitalized simple name, unless it starts with an acronym."""
+    return name if name[1:2].isupper() else name[:1].lower() + name[1:]
+
+def _join_paths(*paths):
+    """Join request mapping paths into one path starting with a slash."""
+    parts = [path.strip('/') for path in paths if path.strip('/')]
+    return '/' + '/'.join(parts)
+
+def find_java_beans(types, members):
+    """Return the beans a Java file declares: stereotyped classes and producer methods.
+
+    Each bean is {'name', 'type', 'stereotype', 'owner', 'method', 'primary', 'qualifiers',
+    'line'}. type is the simple name of the class or of the method's return type; owner is
+    the declaring type's qualname and method the producer method, None for classes.
+    """
+    beans = []
+    for java_type in types:
+        stereotype = java_annotation(java_type, BEAN_STEREOTYPES)
+        if stereotype is None and java_type['kind'] == 'interface' and \
+                any(name.split('.')[-1] in SPRING_DATA_REPOSITORIES for name in java_type['extends']):
+            stereotype = {'name': 'Repository', 'args': ''}
+        if stereotype is None or 'abstract' in java_type['modifiers'] or java_type['kind'] == 'annotation':
+            continue
+        names = java_annotation_values(stereotype['args'])
+        qualifier = java_annotation(java_type, {'Qualifier', 'Named'})
+        beans.append({'name': names[0] if names else _bean_name(java_type['name']), 'type': java_type['name'],
+                      'stereotype': stereotype['name'].split('.')[-1], 'owner': java_type['qualname'], 'method': None,
+                      'primary': java_annotation(java_type, {'Primary'}) is not None,
+                      'qualifiers': java_annotation_values(qualifier['args']) if qualifier else [],
+                      'line': java_type['line']})
+    for member in members:
+        producer = java_annotation(member, BEAN_PRODUCERS)
+        if member['kind'] != 'method' or producer is None:
+            continue
+        names = java_annotation_values(producer['args']) or java_annotation_values(producer['args'], 'name')
+        qualifier = java_annotation(member, {'Qualifier', 'Named'})
+        beans.append({'name': names[0] if names else member['name'], 'type': _simple_type(member['type']),
+                      'stereotype': producer['name'].split('.')[-1], 'owner': member['owner'], 'method': member['name'],
+                      'primary': java_annotation(member, {'Primary'}) is not None,
+                      'qualifiers': java_annotation_values(qualifier['args']) if qualifier else [],
+                      'line': member['line']})
+    return beans
+
+def find_java_injection_points(types, members):
+    """Return the dependencies each type asks the container for.
+
+    A type's dependencies are the parameters of its @Autowired/@Inject constructors, or of its
+    only constructor, or a record's components, or the fields of a Lombok @RequiredArgsConstructor (final fields) or
+    @AllArgsConstructor; plus @Autowired/@Inject fields and setters, and the parameters of
+    producer methods. Each point is {'owner', 'method', 'via', 'name', 'type', 'qualifier',
+    'line'}, where via is 'constructor', 'field', 'setter' or 'bean method', and method is
+    the producer method for 'bean method' points. @Value parameters are configuration, not beans.
+    """
+    points = []
+
+    def add(owner, via, item, line, method=None):
+        if java_annotation(item, {'Value'}):
+            return
+        qualifier = java_annotation(item, {'Qualifier', 'Named'})
+        points.append({'owner': owner, 'method': method, 'via': via, 'name': item['name'], 'type': item['type'],
+                       'qualifier': (java_annotation_values(qualifier['args']) or [None])[0] if qualifier else None,
+                       'line': line})
+
+    for java_type in types:
+        if java_type['kind'] not in ['class', 'record']:
+            continue
+        own = [member for member in members if member['owner'] == java_type['qualname']]
+        constructors = [member for member in own if member['kind'] == 'constructor']
+        injected = [member for member in constructors if java_annotation(member, INJECT_ANNOTATIONS)]
+        if not injected and len(constructors) == 1:
+            injected = constructors
+        for constructor in injected:
+            for param in constructor['params']:
+                add(java_type['qualname'], 'constructor', param, constructor['line'])
+        if not constructors and java_type['kind'] == 'record':
+            for component in java_type['components']:
+                add(java_type['qualname'], 'constructor', component, java_type['line'])
+        elif not constructors:
+            fields = [member for member in own if member['kind'] == 'field' and 'static' not in member['modifiers']]
+            if java_annotation(java_type, {'AllArgsConstructor'}):
+                lombok = fields
+            elif java_annotation(java_type, {'RequiredArgsConstructor'}):
+                lombok = [field for field in fields if 'final' in field['modifiers'] or java_annotation(field, {'NonNull'})]
+            else:
+                lombok = []
+            for field in lombok:
+                add(java_type['qualname'], 'constructor', field, field['line'])
+        for member in own:
+            if member['kind'] == 'field' and java_annotation(member, INJECT_ANNOTATIONS):
+                add(java_type['qualname'], 'field', member, member['line'])
+            elif member['kind'] == 'method' and java_annotation(member, INJECT_ANNOTATIONS):
+                for param in member['params']:
+                    add(java_type['qualname'], 'setter', param, member['line'])
+            elif member['kind'] == 'method' and java_annotation(member, BEAN_PRODUCERS):
+                for param in member['params']:
+                    add(java_type['qualname'], 'bean method', param, member['line'], member['name'])
+    return points
+
+def find_java_endpoints(types, members):
+    """Return the HTTP endpoints a Java file's controllers declare.
+
+    Spring @GetMapping, @PostMapping and the like, and @RequestMapping (with its `method`
+    attribute, otherwise any method), are joined to the class's @RequestMapping path; JAX-RS
+    methods annotated @GET, @POST and the like to the class's @Path. Each endpoint is
+    {'method', 'path', 'handler', 'line'} with handler `Class.method`.
+    """
+    endpoints = []
+    type_of = {java_type['qualname']: java_type for java_type in types}
+    for member in members:
+        owner = type_of.get(member['owner'])
+        if member['kind'] != 'method' or owner is None:
+            continue
+        handler = f"{owner['name']}.{member['name']}"
+        mapping = java_annotation(member, SPRING_MAPPINGS)
+        if mapping:
+            prefix = java_annotation(owner, {'RequestMapping'})
+            prefixes = (java_annotation_values(prefix['args']) or java_annotation_values(prefix['args'], 'path')
+                        if prefix else []) or ['']
+            paths = java_annotation_values(mapping['args']) or java_annotation_values(mapping['args'], 'path') or ['']
+            methods = [SPRING_MAPPINGS[mapping['name'].split('.')[-1]]]
+            if methods == [None]:
+                methods = [method for part in _split_top_level(mapping['args']) if re.match(r'method\s*=', part)
+                           for method in re.findall(r'\b(?:RequestMethod\.)?([A-Z]+)\b', part.split('=', 1)[1])] or ['ANY']
+        else:
+            verbs = [annotation['name'].split('.')[-1] for annotation in member['annotations']
+                     if annotation['name'].split('.')[-1] in JAX_RS_METHODS]
+            if not verbs:
+                continue
+            prefix = java_annotation(owner, {'Path'})
+            path = java_annotation(member, {'Path'})
+            prefixes = (java_annotation_values(prefix['args']) if prefix else []) or ['']
+            paths = (java_annotation_values(path['args']) if path else []) or ['']
+            methods = verbs
+        for method in methods:
+            for prefix_path in prefixes:
+                for path in paths:
+                    endpoints.append({'method': method, 'path': _join_paths(prefix_path, path),
+                                      'handler': handler, 'line': member['line']})
+    return endpoints
+
+def build_spring_graph(java_files):
+    """Build the dependency injection graph and HTTP endpoints of a Spring or Jakarta project.
+
+    java_files maps absolute file paths to relative paths. Returns {'beans', 'injections',
+    'endpoints'}. Beans are as in find_java_beans, with their 'file'. Injections are as in
+    find_java_injection_points, with 'consumer' (the owning bean's name, or the produced bean
+    for a producer method's parameters), 'file' and 'targets', the names of the beans that
+    satisfy them: beans of the type or of a subtype declared in the project, narrowed by
+    @Qualifier, then by @Primary, then by the parameter name, as Spring does. 'ambiguous' is
+    set when several beans remain; no targets means no bean in the project provides the type.
+    Endpoints are as in find_java_endpoints, with their 'file'.
+    """
+    graph = {'beans': [], 'injections': [], 'endpoints': []}
+    supertypes = {}
+    points = []
+    for file_path, rel_path in java_files.items():
+        try:
+            with open(file_path, 'r', encoding='utf-8', errors='ignore') as f:
+                content = f.read()
+        except (OSError, IOError):
+            continue
+        types, members = parse_java_declarations(content)
+        for java_type in types:
+            supertypes.setdefault(java_type['name'], set()).update(
+                _simple_type(name) for name in java_type['extends'] + java_type['implements'])
+        graph['beans'].extend(dict(bean, file=rel_path) for bean in find_java_beans(types, members))
+        points.extend(dict(point, file=rel_path) for point in find_java_injection_points(types, members))
+        graph['endpoints'].extend(dict(endpoint, file=rel_path) for endpoint in find_java_endpoints(types, members))
+
+    def assignable(type_name, seen=None):
+        """The type and every project supertype it extends or implements."""
+        seen = set() if seen is None else seen
+        if type_name not in seen:
+            seen.add(type_name)
+            for parent in supertypes.get(type_name, ()):
+                assignable(parent, seen)
+        return seen
+
+    bean_types = [(bean, assignable(bean['type'])) for bean in graph['beans']]
+    class_beans = {(bean['file'], bean['owner']): bean for bean in graph['beans'] if bean['method'] is None}
+    method_beans = {(bean['file'], bean['owner'], bean['method']): bean for bean in graph['beans'] if bean['method']}
+    for point in points:
+        consumer = (method_beans.get((point['file'], point['owner'], point['method'])) if point['method']
+                    else class_beans.get((point['file'], point['owner'])))
+        if consumer is None:
+            # Only container-managed objects are injected
+            continue
+        type_name, collection = java_injected_type(point['type'])
+        # A bean never satisfies its own dependencies, e.g. a decorating @Bean method's parameter
+        candidates = [bean for bean, types in bean_types if type_name in types and bean is not consumer]
+        if point['qualifier']:
+            candidates = [bean for bean in candidates
+                          if point['qualifier'] == bean['name'] or point['qualifier'] in bean['qualifiers']]
+        if len(candidates) > 1 and not collection:
+            primary = [bean for bean in candidates if bean['primary']]
+            named = [bean for bean in candidates if bean['name'] == point['name']]
+            candidates = primary if len(primary) == 1 else named if len(named) == 1 else candidates
+        injection = dict(point, consumer=consumer['name'], type=type_name, targets=[bean['name'] for bean in candidates])
+        del injection['method'], injection['owner']
+        if len(candidates) > 1 and not collection:
+            injection['ambiguous'] = True
+        graph['injections'].append(injection)
+
+    graph['beans'].sort(key=lambda bean: (bean['file'], bean['line']))
+    graph['injections'].sort(key=lambda injection: (injection['file'], injection['line']))
+    graph['endpoints'].sort(key=lambda endpoint: (endpoint['path'], endpoint['method']))
+    return graph
@@ -0,0 +1,952 @@
+"""
+JavaScript and TypeScript module analysis for CLIArt.
+
+Imports are read with regular expressions over comment-free source, and resolved the way
+Node and the TypeScript compiler resolve them: relative paths with extension and index
+probing, package.json `exports` and `main`, tsconfig `baseUrl` and `paths` aliases, and
+the packages of an npm, Yarn or pnpm workspace.
+"""
+
+import os
+import re
+import json
+import glob
+
+# Extensions tried, in order, for a specifier without one (TypeScript sources first)
+JS_EXTENSIONS = ['.ts', '.tsx', '.d.ts', '.js', '.jsx', '.mjs', '.cjs', '.mts', '.cts']
+
+# A compiled-output extension in a TypeScript import may name a TypeScript source
+TS_SOURCE_EXTENSIONS = {'.js': ['.ts', '.tsx'], '.jsx': ['.tsx'], '.mjs': ['.mts'], '.cjs': ['.cts']}
+
+# package.json export conditions, in the order a bundler resolving source files prefers them
+EXPORT_CONDITIONS = ['source', 'types', 'import', 'module', 'browser', 'node', 'require', 'default']
+
+IMPORT_PATTERN = re.compile(
+    r'\bimport\s+(type\s+)?([\w$]+\s*,\s*)?(?:\{([^}]*)\}|\*\s+as\s+([\w$]+)|([\w$]+))?\s*'
+    r'(?:from\s*)?([\'"])([^\'"\n]+)\6'
+)
+REQUIRE_PATTERN = re.compile(r'\brequire\s*\(\s*([\'"])([^\'"\n]+)\1\s*\)')
+REQUIRE_RESOLVE_PATTERN = re.compile(r'\brequire\.resolve\s*\(\s*([\'"])([^\'"\n]+)\1')
+# `const x = require('x')` or `const { a, b: c } = require('x')`, up to the require call
+REQUIRE_BINDING_PATTERN = re.compile(r'\b(?:const|let|var)\s+(?:([\w$]+)|\{([^}]*)\})\s*=\s*$')
+# import('x') with a literal specifier; template literals with ${} are left out
+DYNAMIC_IMPORT_PATTERN = re.compile(r'(?<![\w$.])import\s*\(\s*([\'"`])([^\'"`\n$]+)\1\s*[,)]')
+# `const Page = lazy(() => import('./Page'))`, up to the import call
+LAZY_BINDING_PATTERN = re.compile(
+    r'\b(?:const|let|var)\s+([\w$]+)\s*=\s*(?:React\.)?lazy\s*\(\s*(?:async\s*)?\(\s*\)\s*=>\s*\{?\s*(?:return\s+)?$')
+
+DECLARATION_EXPORT_PATTERN = re.compile(
+    r'\bexport\s+(?:declare\s+)?(default\s+)?(?:abstract\s+)?(?:async\s+)?'
+    r'(?:function\s*\*?|class|const|let|var|interface|type|enum|namespace)\s+([\w$]+)'
+)
+CJS_EXPORTS_PATTERN = re.compile(r'\bmodule\.exports\s*=(?!=)\s*')
+CJS_NAMED_EXPORT_PATTERN = re.compile(r'(?<![\w$.])(?:module\.)?exports\.([\w$]+)\s*=(?!=)')
+DEFAULT_EXPRESSION_PATTERN = re.compile(r'\bexport\s+default\s+(?!(?:abstract\s+|async\s+)?(?:function|class)\b)([\w$]+)?')
+NAMED_EXPORT_PATTERN = re.compile(r'\bexport\s+(type\s+)?\{([^}]*)\}(?:\s*from\s*([\'"])([^\'"\n]+)\3)?')
+STAR_EXPORT_PATTERN = re.compile(r'\bexport\s+(type\s+)?\*\s*(?:as\s+([\w$]+)\s*)?from\s*([\'"])([^\'"\n]+)\3')
+
+def strip_js_comments(content):
+    """Blank out `//` and `/* */` comments, keeping strings, line numbers and positions intact."""
+    result = []
+    i = 0
+    length = len(content)
+    quote = None
+    while i < length:
+        char = content[i]
+        if quote:
+            result.append(char)
+            if char == '\\' and i + 1 < length:
+                result.append(content[i + 1])
+                i += 2
+                continue
+            if char == quote or (char == '\n' and quote != '`'):
+                quote = None
+            i += 1
+        elif char in '\'"`':
+            quote = char
+            result.append(char)
+            i += 1
+        elif content.startswith('//', i):
+            end = content.find('\n', i)
+            end = length if end == -1 else end
+            result.append(' ' * (end - i))
+            i = end
+        elif content.startswith('/*', i):
+            end = content.find('*/', i + 2)
+            end = length if end == -1 else end + 2
+            result.append(re.sub(r'[^\n]', ' ', content[i:end]))
+            i = end
+        else:
+            result.append(char)
+            i += 1
+    return ''.join(result)
+
+def load_jsonc(file_path):
+    """Load a JSON file that may contain comments and trailing commas, like tsconfig.json.
+    Returns None when it cannot be read."""
+    try:
+        with open(file_path, 'r', encoding='utf-8', errors='ignore') as f:
+            content = strip_js_comments(f.read())
+        return json.loads(re.sub(r',(\s*[}\]])', r'\1', content))
+    except (OSError, IOError, ValueError):
+        return None
+
+def _line_of(content, pos):
+    return content.count('\n', 0, pos) + 1
+
+def find_js_imports(content):
+    """Return the modules a JavaScript or TypeScript file imports.
+
+    Each import is {'specifier', 'default', 'namespace', 'names', 'type_names', 'type_only', 'kind',
+    'line'} where names holds (name, alias) pairs from `import { a as b }` and type_names the
+    names marked `type` (`import { type A }`). type_only is set for `import type`, or when
+    every name is a type. The kind is one of:
+    - 'import' for ES imports, with or without bindings
+    - 'require' for `require('x')`; `const x = require('x')` binds x as the default and
+      `const { a } = require('x')` binds names
+    - 'dynamic' for `import('x')`, bound as the default by `const X = lazy(() => import('x'))`
+    - 'require.resolve' for `require.resolve('x')`, which locates a module without loading it
+    - 'reexport' for `export ... from` statements, which bind no local names
+    """
+    stripped = strip_js_comments(content)
+    imports = []
+
+    def add(specifier, kind, match, default=None, namespace=None, names=None, type_names=None, type_only=False):
+        imports.append({'specifier': specifier, 'default': default, 'namespace': namespace, 'names': names or [],
+                        'type_names': type_names or set(), 'type_only': type_only, 'kind': kind,
+                        'line': _line_of(stripped, match.start())})
+
+    for match in IMPORT_PATTERN.finditer(stripped):
+        default = (match.group(2) or '').rstrip(', \t\n') or match.group(5)
+        if default in ['from', 'type']:
+            continue
+        names = []
+        type_names = set()
+        for entry in (match.group(3) or '').split(','):
+            parts = entry.split()
+            is_type = bool(parts) and parts[0] == 'type' and len(parts) > 1
+            if is_type:
+                parts = parts[1:]
+            if not parts:
+                continue
+            names.append((parts[0], parts[2] if len(parts) == 3 and parts[1] == 'as' else None))
+            if is_type:
+                type_names.add(parts[0])
+        type_only = bool(match.group(1)) or (bool(names) and not default and len(type_names) == len(names))
+        add(match.group(7), 'import', match, default, match.group(4), names, type_names, type_only)
+    for match in REQUIRE_PATTERN.finditer(stripped):
+        line_start = stripped.rfind('\n', 0, match.start()) + 1
+        binding = REQUIRE_BINDING_PATTERN.search(stripped[line_start:match.start()])
+        names = []
+        if binding and binding.group(2):
+            for entry in binding.group(2).split(','):
+                parts = [part.strip() for part in entry.split(':')]
+                if parts[0] and not parts[0].startswith('...'):
+                    names.append((parts[0], parts[1] if len(parts) == 2 and parts[1] != parts[0] else None))
+        add(match.group(2), 'require', match, default=binding.group(1) if binding else None, names=names)
+    for match in REQUIRE_RESOLVE_PATTERN.finditer(stripped):
+        add(match.group(2), 'require.resolve', match)
+    for match in DYNAMIC_IMPORT_PATTERN.finditer(stripped):
+        line_start = stripped.rfind('\n', 0, match.start()) + 1
+        binding = LAZY_BINDING_PATTERN.search(stripped[line_start:match.start()])
+        add(match.group(2), 'dynamic', match, default=binding.group(1) if binding else None)
+    reexports = {}
+    for export in find_js_exports(content):
+        if export['specifier'] is None:
+            continue
+        key = (export['specifier'], export['line'])
+        if key not in reexports:
+            reexports[key] = {'specifier': export['specifier'], 'default': None, 'namespace': None, 'names': [],
+                              'type_names': set(), 'type_only': export['type_only'], 'kind': 'reexport',
+                              'line': export['line']}
+        if export['kind'] == 'reexport' and export['imported'] == '*':
+            reexports[key]['namespace'] = export['name']
+        elif export['kind'] == 'reexport':
+            reexports[key]['names'].append((export['imported'], export['name']))
+    imports.extend(reexports.values())
+    return sorted(imports, key=lambda imported: imported['line'])
+
+def find_js_exports(content):
+    """Return the names a JavaScript or TypeScript module exports.
+
+    Each export is {'kind', 'name', 'local', 'imported', 'specifier', 'type_only', 'line'}:
+    - 'local' exports a binding of the file, `local` (None for `export default <expression>`).
+      CommonJS `module.exports = ...` is the default export, and `exports.foo =` or the keys
+      of a `module.exports = { ... }` object are named exports
+    - 'reexport' exports `imported` from another module as `name` (`export { A as B } from`);
+      imported is '*' for `export * as ns from`
+    - 'star' exports every name of another module but its default (`export * from`)
+    Default exports have the name 'default'.
+    """
+    stripped = strip_js_comments(content)
+    exports = []
+
+    def add(kind, name, match, local=None, imported=None, specifier=None, type_only=False):
+        exports.append({'kind': kind, 'name': name, 'local': local, 'imported': imported, 'specifier': specifier,
+                        'type_only': type_only, 'line': _line_of(stripped, match.start())})
+
+    for match in DECLARATION_EXPORT_PATTERN.finditer(stripped):
+        add('local', 'default' if match.group(1) else match.group(2), match, local=match.group(2))
+    for match in DEFAULT_EXPRESSION_PATTERN.finditer(stripped):
+        # Only a bare identifier names a binding; `export default memo(App)` is an expression
+        rest = stripped[match.end():].lstrip(' \t')
+        local = match.group(1) if match.group(1) and (not rest or rest[0] in ';\n') else None
+        add('local', 'default', match, local=local)
+    for match in NAMED_EXPORT_PATTERN.finditer(stripped):
+        for entry in match.group(2).split(','):
+            parts = entry.split()
+            if parts and parts[0] == 'type' and len(parts) > 1:
+                parts = parts[1:]
+            if not parts:
+                continue
+            name = parts[2] if len(parts) == 3 and parts[1] == 'as' else parts[0]
+            if match.group(4):
+                add('reexport', name, match, imported=parts[0], specifier=match.group(4), type_only=bool(match.group(1)))
+            else:
+                add('local', name, match, local=parts[0])
+    for match in CJS_EXPORTS_PATTERN.finditer(stripped):
+        if stripped.startswith('{', match.end()):
+            # module.exports = { a, b: c, d() {} } exports each key
+            body = stripped[match.end() + 1:_read_balanced(stripped, match.end(), '{', '}') - 1]
+            for entry in _split_top_level(body):
+                key = re.match(r'(?:async\s+)?\*?\s*([\w$]+)\s*(:\s*([\w$]+)\s*$)?', entry.strip())
+                if key and not entry.strip().startswith('...'):
+                    add('local', key.group(1), match, local=key.group(3) or key.group(1))
+            add('local', 'default', match)
+        else:
+            identifier = re.match(r'([\w$]+)\s*(?:;|\n|$)', stripped[match.end():])
+            add('local', 'default', match, local=identifier.group(1) if identifier else None)
+    for match in CJS_NAMED_EXPORT_PATTERN.finditer(stripped):
+        add('local', match.group(1), match, local=match.group(1))
+    for match in STAR_EXPORT_PATTERN.finditer(stripped):
+        if match.group(2):
+            add('reexport', match.group(2), match, imported='*', specifier=match.group(4), type_only=bool(match.group(1)))
+        else:
+            add('star', None, match, specifier=match.group(4), type_only=bool(match.group(1)))
+    return sorted(exports, key=lambda export: export['line'])
+
+def js_import_strings(imported):
+    """Format an import from find_js_imports as (import string, symbol) pairs.
+
+    `import { Button } from './Button'` gives ('./Button.Button', 'Button'), a default import
+    gives the local name with symbol 'default', and namespace, side-effect and require
+    imports give the specifier with symbol None.
+    """
+    specifier = imported['specifier']
+    strings = []
+    if imported['default']:
+        strings.append((f"{specifier}.{imported['default']}", 'default'))
+    if imported['namespace']:
+        strings.append((f"{specifier}.{imported['namespace']}", None))
+    for name, _ in imported['names']:
+        strings.append((f"{specifier}.{name}", name))
+    return strings or [(specifier, None)]
+
+def js_import_kinds(imported):
+    """Return {import string: edge kind} for an import from find_js_imports.
+
+    The kind is 'dynamic' for modules loaded or located at run time (`import('x')`,
+    `require.resolve('x')`), 'type' for names only used as types, and None otherwise.
+    """
+    kinds = {}
+    for imp, symbol in js_import_strings(imported):
+        if imported['kind'] in ['dynamic', 'require.resolve']:
+            kinds[imp] = 'dynamic'
+        elif imported['type_only'] or symbol in imported['type_names']:
+            kinds[imp] = 'type'
+        else:
+            kinds[imp] = None
+    return kinds
+
+def find_js_import_kinds(js_files):
+    """Return {relative path: {import string: edge kind}} for the dynamic and type-only
+    imports of JavaScript and TypeScript files. A string imported statically anywhere in the
+    file is left out, since the static import already couples the modules at run time."""
+    import_kinds = {}
+    for file_path, rel_path in js_files.items():
+        try:
+            with open(file_path, 'r', encoding='utf-8', errors='ignore') as f:
+                content = f.read()
+        except (OSError, IOError):
+            continue
+        kinds = {}
+        for imported in find_js_imports(content):
+            for imp, kind in js_import_kinds(imported).items():
+                if kinds.get(imp, kind) is not None:
+                    kinds[imp] = kind
+        kinds = {imp: kind for imp, kind in kinds.items() if kind}
+        if kinds:
+            import_kinds[rel_path] = kinds
+    return import_kinds
+
+def find_tsconfig(directory):
+    """Return the compiler options of the nearest tsconfig.json or jsconfig.json above a
+    directory as {'base_url', 'paths'}, with `extends` applied and paths made absolute."""
+    while True:
+        for name in ['tsconfig.json', 'jsconfig.json']:
+            candidate = os.path.join(directory, name)
+            if os.path.isfile(candidate):
+                return _load_tsconfig(candidate, set())
+        parent = os.path.dirname(directory)
+        if parent == directory:
+            return {'base_url': None, 'paths': {}}
+        directory = parent
+
+def _load_tsconfig(file_path, visited):
+    """Read a tsconfig's baseUrl and paths, following relative `extends` chains."""
+    options = {'base_url': None, 'paths': {}}
+    if file_path in visited:
+        return options
+    visited.add(file_path)
+    data = load_jsonc(file_path) or {}
+    directory = os.path.dirname(file_path)
+
+    extends = data.get('extends')
+    for parent in [extends] if isinstance(extends, str) else extends or []:
+        if parent.startswith('.'):
+            parent_path = os.path.normpath(os.path.join(directory, parent))
+            if not parent_path.endswith('.json'):
+                parent_path += '.json'
+            inherited = _load_tsconfig(parent_path, visited)
+            options['base_url'] = inherited['base_url'] or options['base_url']
+            options['paths'].update(inherited['paths'])
+
+    compiler_options = data.get('compilerOptions', {})
+    if compiler_options.get('baseUrl'):
+        options['base_url'] = os.path.normpath(os.path.join(directory, compiler_options['baseUrl']))
+    if compiler_options.get('paths'):
+        # Paths are relative to baseUrl, or to the tsconfig that declares them without one
+        paths_base = options['base_url'] or directory
+        options['paths'] = {pattern: [os.path.normpath(os.path.join(paths_base, target)) for target in targets]
+                            for pattern, targets in compiler_options['paths'].items()}
+    return options
+
+def find_workspace_packages(directories):
+    """Return {package name: directory} for the workspace packages of the npm, Yarn or pnpm
+    workspaces whose root is above any of the given directories."""
+    packages = {}
+    roots = set()
+    for directory in directories:
+        while True:
+            if directory in roots:
+                break
+            patterns = _workspace_patterns(directory)
+            if patterns:
+                roots.add(directory)
+                for pattern in patterns:
+                    for package_dir in glob.glob(os.path.join(directory, pattern)):
+                        manifest = _read_package_json(package_dir)
+                        if manifest and manifest.get('name'):
+                            packages.setdefault(manifest['name'], os.path.normpath(package_dir))
+                break
+            parent = os.path.dirname(directory)
+            if parent == directory:
+                break
+            directory = parent
+    return packages
+
+def _workspace_patterns(directory):
+    """Return the workspace globs a directory's package.json or pnpm-workspace.yaml declares."""
+    manifest = _read_package_json(directory)
+    workspaces = manifest.get('workspaces') if manifest else None
+    if isinstance(workspaces, dict):
+        workspaces = workspaces.get('packages')
+    if workspaces:
+        return [pattern for pattern in workspaces if isinstance(pattern, str) and not pattern.startswith('!')]
+
+    pnpm = os.path.join(directory, 'pnpm-workspace.yaml')
+    if os.path.isfile(pnpm):
+        try:
+            with open(pnpm, 'r', encoding='utf-8', errors='ignore') as f:
+                content = f.read()
+        except (OSError, IOError):
+            return []
+        # The `packages:` list, as `- 'packages/*'` lines
+        section = re.search(r'^packages:\s*\n((?:[ \t]+-.*\n?|[ \t]*#.*\n?|\s*\n)*)', content, re.MULTILINE)
+        if section:
+            return [pattern for pattern in re.findall(r'-\s*[\'"]?([^\'"\n#]+?)[\'"]?\s*(?:#.*)?$', section.group(1), re.MULTILINE)
+                    if not pattern.startswith('!')]
+    return []
+
+def _read_package_json(directory):
+    """Return the parsed package.json in a directory, or None."""
+    try:
+        with open(os.path.join(directory, 'package.json'), 'r', encoding='utf-8', errors='ignore') as f:
+            data = json.load(f)
+        return data if isinstance(data, dict) else None
+    except (OSError, IOError, ValueError):
+        return None
+
+def _export_targets(value):
+    """Flatten a package.json `exports` value into candidate paths, preferred conditions first."""
+    if isinstance(value, str):
+        return [value]
+    if isinstance(value, list):
+        return [target for entry in value for target in _export_targets(entry)]
+    if isinstance(value, dict):
+        targets = []
+        for condition in EXPORT_CONDITIONS + [key for key in value if key not in EXPORT_CONDITIONS]:
+            if condition in value:
+                targets.extend(_export_targets(value[condition]))
+        return targets
+    return []
+
+def _package_entries(package_dir, subpath):
+    """Return the candidate files for `subpath` ('.' or './x') of a package directory."""
+    manifest = _read_package_json(package_dir) or {}
+    exports = manifest.get('exports')
+    candidates = []
+    if exports is not None:
+        if not isinstance(exports, dict) or not any(key.startswith('.') for key in exports):
+            exports = {'.': exports}
+        if subpath in exports:
+            candidates.extend(_export_targets(exports[subpath]))
+        for pattern, value in exports.items():
+            # Subpath patterns such as "./utils/*": "./src/utils/*.ts"
+            if '*' in pattern:
+                prefix, suffix = pattern.split('*', 1)
+                if subpath.startswith(prefix) and subpath.endswith(suffix) and len(subpath) >= len(prefix) + len(suffix):
+                    matched = subpath[len(prefix):len(subpath) - len(suffix)]
+                    candidates.extend(target.replace('*', matched) for target in _export_targets(value))
+    if subpath == '.':
+        candidates.extend(manifest[field] for field in ['source', 'module', 'main', 'types', 'typings']
+                          if isinstance(manifest.get(field), str))
+        candidates.append('./index')
+    else:
+        candidates.append(subpath)
+    return [os.path.normpath(os.path.join(package_dir, candidate)) for candidate in candidates]
+
+def resolve_js_module(base, files):
+    """Resolve a path without its extension, or a directory, to a file in `files`.
+
+    Tries the path itself, the path with each of JS_EXTENSIONS, a TypeScript source for a
+    `.js` path, and then a directory's package.json entry point or `index` file.
+    """
+    if base in files:
+        return base
+    for extension in JS_EXTENSIONS:
+        if base + extension in files:
+            return base + extension
+    stem, extension = os.path.splitext(base)
+    for source_extension in TS_SOURCE_EXTENSIONS.get(extension, []):
+        if stem + source_extension in files:
+            return stem + source_extension
+    if os.path.isdir(base):
+        for candidate in _package_entries(base, '.'):
+            resolved = resolve_js_module(candidate, files) if candidate != base else None
+            if resolved:
+                return resolved
+    return None
+
+def js_module_resolver(files):
+    """Return a function resolving (specifier, importing file) to a file in `files`, or None.
+
+    files holds absolute paths. Relative specifiers resolve against the importing file;
+    bare ones through the nearest tsconfig `paths` and `baseUrl`, then workspace packages.
+    Packages outside the analyzed files (node_modules) are left unresolved.
+    """
+    tsconfigs = {}
+    workspace = find_workspace_packages(sorted({os.path.dirname(file_path) for file_path in files}))
+
+    def resolve_specifier(specifier, importer):
+        directory = os.path.dirname(importer)
+        if specifier.startswith(('./', '../')) or specifier in ['.', '..']:
+            return resolve_js_module(os.path.normpath(os.path.join(directory, specifier)), files)
+        if specifier.startswith('/'):
+            return resolve_js_module(os.path.normpath(specifier), files)
+
+        if directory not in tsconfigs:
+            tsconfigs[directory] = find_tsconfig(directory)
+        options = tsconfigs[directory]
+        # The longest matching `paths` pattern wins
+        for pattern in sorted(options['paths'], key=lambda pattern: -len(pattern.split('*')[0])):
+            prefix, _, suffix = pattern.partition('*')
+            if '*' not in pattern and specifier != pattern:
+                continue
+            if '*' in pattern and not (specifier.startswith(prefix) and specifier.endswith(suffix)):
+                continue
+            matched = specifier[len(prefix):len(specifier) - len(suffix)] if '*' in pattern else ''
+            for target in options['paths'][pattern]:
+                resolved = resolve_js_module(target.replace('*', matched), files)
+                if resolved:
+                    return resolved
+        if options['base_url']:
+            resolved = resolve_js_module(os.path.normpath(os.path.join(options['base_url'], specifier)), files)
+            if resolved:
+                return resolved
+
+        for name in sorted(workspace, key=len, reverse=True):
+            if specifier == name or specifier.startswith(name + '/'):
+                subpath = '.' + specifier[len(name):]
+                for candidate in _package_entries(workspace[name], subpath):
+                    resolved = resolve_js_module(candidate, files)
+                    if resolved:
+                        return resolved
+        return None
+
+    return resolve_specifier
+
+def js_export_resolver(files, resolve_specifier):
+    """Return a function giving the names a file exports, with re-exports followed.
+
+    The function maps a file in `files` to {exported name: [(file, symbol, via)]}: the file
+    defining the name, the name it has there (None for a namespace) and the barrel files
+    passed on the way, starting with the file itself. A name gets several definitions when
+    `export *` statements bring it in from several modules.
+    """
+    tables = {}
+    parsed = {}
+    in_progress = set()
+
+    def parse(file_path):
+        if file_path not in parsed:
+            try:
+                with open(file_path, 'r', encoding='utf-8', errors='ignore') as f:
+                    content = f.read()
+            except (OSError, IOError):
+                content = ''
+            if os.path.splitext(file_path)[1].lower() in ['.vue', '.svelte']:
+                content = split_sfc(content)[0]
+            # What each imported local name refers to, for `import { A } from './a'; export { A }`
+            bindings = {}
+            for imported in find_js_imports(content):
+                if imported['kind'] not in ['import', 'require']:
+                    continue
+                if imported['default']:
+                    bindings[imported['default']] = (imported['specifier'], 'default')
+                if imported['namespace']:
+                    bindings[imported['namespace']] = (imported['specifier'], '*')
+                for name, alias in imported['names']:
+                    bindings[alias or name] = (imported['specifier'], name)
+            parsed[file_path] = (find_js_exports(content), bindings)
+        return parsed[file_path]
+
+    def follow(file_path, specifier, name):
+        target = resolve_specifier(specifier, file_path)
+        if target is None or target == file_path:
+            # Defined outside the analyzed files
+            return [(file_path, name, ())]
+        if name == '*':
+            return [(target, None, (file_path,))]
+        found = exports_of(target).get(name) or [(target, name, ())]
+        return [(defined, symbol, (file_path,) + via) for defined, symbol, via in found]
+
+    def exports_of(file_path):
+        if file_path in tables:
+            return tables[file_path]
+        if file_path in in_progress:
+            return {}  # a re-export cycle
+        in_progress.add(file_path)
+        exports, bindings = parse(file_path)
+        table = {}
+        for export in exports:
+            if export['kind'] == 'reexport':
+                table[export['name']] = follow(file_path, export['specifier'], export['imported'])
+            elif export['kind'] == 'local' and export['local'] in bindings:
+                table[export['name']] = follow(file_path, *bindings[export['local']])
+            elif export['kind'] == 'local':
+                symbol = 'default' if export['name'] == 'default' else export['local']
+                table[export['name']] = [(file_path, symbol, ())]
+
+        # Names from `export *` never override the module's own exports, nor include its default
+        starred = {}
+        for export in exports:
+            if export['kind'] != 'star':
+                continue
+            target = resolve_specifier(export['specifier'], file_path)
+            if target is None or target == file_path:
+                continue
+            for name, found in exports_of(target).items():
+                if name == 'default' or name in table:
+                    continue
+                for defined, symbol, via in found:
+                    if not any((defined, symbol) == (other[0], other[1]) for other in starred.get(name, [])):
+                        starred.setdefault(name, []).append((defined, symbol, (file_path,) + via))
+        table.update(starred)
+
+        in_progress.discard(file_path)
+        tables[file_path] = table
+        return table
+
+    return exports_of
+
+def resolve_js_imports(js_files):
+    """Resolve each JavaScript or TypeScript file's imports to the files that define them.
+
+    js_files maps absolute file paths to the relative paths used in diagrams. Returns
+    (resolved, reexports, collisions):
+    - resolved maps each relative path to {import string: (relative target path, symbol)},
+      using the import strings extract_dependencies produces. Imports from barrel files are
+      followed through `export ... from` statements to the file that defines the symbol, and
+      symbol is the name it has there.
+    - reexports maps each relative path to {import string: {'via', 'candidates'}} for the
+      imports that went through barrels: the barrel files passed, and every (file, symbol)
+      the name may refer to. There are several candidates when `export *` statements of a
+      barrel export the name from several modules, which TypeScript reports as ambiguous.
+    - collisions lists those ambiguous names as {'file', 'name', 'sources'}.
+    See js_module_resolver for how specifiers resolve.
+    """
+    files = {os.path.abspath(file_path): rel_path for file_path, rel_path in js_files.items()}
+    resolve_specifier = js_module_resolver(files)
+    exports_of = js_export_resolver(files, resolve_specifier)
+
+    resolved = {}
+    reexports = {}
+    for file_path, rel_path in files.items():
+        try:
+            with open(file_path, 'r', encoding='utf-8', errors='ignore') as f:
+                content = f.read()
+        except (OSError, IOError):
+            continue
+        file_imports = {}
+        for imported in find_js_imports(content):
+            target = resolve_specifier(imported['specifier'], file_path)
+            if target is None or target == file_path:
+                continue
+            for imp, symbol in js_import_strings(imported):
+                found = exports_of(target).get(symbol) if symbol else None
+                if not found:
+                    file_imports[imp] = (files[target], symbol)
+                    continue
+                defined, name, via = found[0]
+                file_imports[imp] = (files[defined], name)
+                if via or len(found) > 1:
+                    reexports.setdefault(rel_path, {})[imp] = {
+                        'via': [files[barrel] for barrel in via],
+                        'candidates': [(files[candidate], candidate_symbol) for candidate, candidate_symbol, _ in found]
+                    }
+        if file_imports:
+            resolved[rel_path] = file_imports
+
+    # A barrel's own collisions are names its `export *` statements bring in from different modules
+    collisions = []
+    for file_path, rel_path in files.items():
+        for name, found in exports_of(file_path).items():
+            entered = {via[1] if len(via) > 1 else defined for defined, _, via in found}
+            if len(found) > 1 and len(entered) > 1:
+                collisions.append({'file': rel_path, 'name': name,
+                                   'sources': sorted({files[defined] for defined, _, _ in found})})
+    return resolved, reexports, collisions
+
+# Files the component tree looks at, and directories it skips
+COMPONENT_EXTENSIONS = ['.jsx', '.tsx', '.js', '.ts', '.mjs', '.vue', '.svelte']
+EXCLUDED_DIRECTORIES = ['node_modules', '.git', 'dist', 'build', 'coverage', '.next', '.nuxt', '.svelte-kit', 'out']
+
+# Base classes that make a class a React component
+COMPONENT_BASES = {'Component', 'PureComponent', 'React.Component', 'React.PureComponent'}
+
+# Declarations starting at column 0 end the previous component's source span
+TOP_LEVEL_PATTERN = re.compile(
+    r'^(?:export\s+)?(?:default\s+)?(?:declare\s+)?(?:async\s+)?(?:function\b|const\b|let\b|var\b|class\b|interface\b|type\b|enum\b)',
+    re.MULTILINE)
+FUNCTION_COMPONENT_PATTERN = re.compile(r'^(export\s+)?(default\s+)?(?:async\s+)?function\s*\*?\s*([A-Z][\w$]*)?\s*[(<]', re.MULTILINE)
+VARIABLE_COMPONENT_PATTERN = re.compile(r'^(export\s+)?(?:const|let|var)\s+([A-Z][\w$]*)\b[^=\n]*=(?!=)', re.MULTILINE)
+CLASS_COMPONENT_PATTERN = re.compile(r'^(export\s+)?(default\s+)?class\s+([A-Z][\w$]*)(?:\s+extends\s+([\w$.]+))?', re.MULTILINE)
+DEFAULT_EXPORT_PATTERN = re.compile(r'^export\s+default\s+(?:[\w$.]+\()?([A-Z][\w$]*)\)?\s*;?\s*$', re.MULTILINE)
+
+# JSX starts after return, =>, an opening paren, a ternary branch or a logical operator
+JSX_PATTERN = re.compile(r'(?:\breturn|=>|[(?:,]|&&|\|\|)\s*<(?:[A-Za-z>])')
+# A capitalized (or dotted) JSX tag, not a TypeScript generic such as Array<Item>
+JSX_TAG_PATTERN = re.compile(r'(?<![\w$)\].])<([A-Z][\w$]*(?:\.[\w$]+)*)(?=[\s/>])')
+# Vue and Svelte templates also use kebab-case tags such as <user-card>
+TEMPLATE_TAG_PATTERN = re.compile(r'<([A-Z][\w$]*(?:\.[\w$]+)*|[a-z][\w]*(?:-[\w]+)+)(?=[\s/>])')
+
+def split_sfc(content):
+    """Split a Vue or Svelte single-file component into (script, markup).
+
+    Both keep the file's length and line breaks, with everything else blanked out, so
+    positions in them are positions in the file. Vue markup is the `<template>` block;
+    Svelte markup is everything outside `<script>` and `<style>`.
+    """
+    def blank(text):
+        return re.sub(r'[^\n]', ' ', text)
+
+    script = []
+    markup = []
+    position = 0
+    for match in re.finditer(r'<(script|style)\b[^>]*>([\s\S]*?)</\1\s*>', content):
+        markup.append(content[position:match.start()])
+        before_body = match.start(2)
+        script.append(blank(content[position:before_body]))
+        if match.group(1) == 'script':
+            script.append(match.group(2))
+        else:
+            script.append(blank(match.group(2)))
+        script.append(blank(content[match.end(2):match.end()]))
+        markup.append(blank(content[match.start():match.end()]))
+        position = match.end()
+    markup.append(content[position:])
+    script.append(blank(content[position:]))
+    script = ''.join(script)
+    markup = ''.join(markup)
+
+    template = re.search(r'<template\b[^>]*>([\s\S]*)</template\s*>', markup)
+    if template:
+        markup = blank(markup[:template.start(1)]) + template.group(1) + blank(markup[template.end(1):])
+    return script, markup
+
+def _pascal_case(name):
+    """Turn `user-card` or `user_card` into `UserCard`; PascalCase names are kept."""
+    if '-' not in name and '_' not in name:
+        return name[0].upper() + name[1:]
+    return ''.join(part[:1].upper() + part[1:] for part in re.split(r'[-_]', name) if part)
+
+def find_js_components(content, file_path):
+    """Return the components a file defines.
+
+    Each is {'name', 'kind', 'line', 'start', 'end', 'exported', 'default'}. A `.vue` or
+    `.svelte` file is one single-file component named after the file (or its Vue `name`).
+    In JavaScript and TypeScript, capitalized functions and variables whose source renders
+    JSX, and classes extending React's Component, are components. A component's source runs
+    until the next declaration at the start of a line.
+    """
+    extension = os.path.splitext(file_path)[1].lower()
+    file_name = _pascal_case(os.path.basename(file_path).split('.')[0])
+    if extension in ['.vue', '.svelte']:
+        script, _ = split_sfc(content)
+        options = re.search(r'\bexport\s+default\s*(?:defineComponent\s*\(\s*)?\{', strip_js_comments(script))
+        named = re.compile(r'\bname\s*:\s*[\'"]([\w-]+)[\'"]').search(strip_js_comments(script), options.end()) \
+            if extension == '.vue' and options else None
+        return [{'name': _pascal_case(named.group(1)) if named else file_name, 'kind': extension[1:], 'line': 1,
+                 'start': 0, 'end': len(content), 'exported': True, 'default': True}]
+
+    stripped = strip_js_comments(content)
+    boundaries = [match.start() for match in TOP_LEVEL_PATTERN.finditer(stripped)] + [len(stripped)]
+
+    def span_end(start):
+        return next(boundary for boundary in boundaries if boundary > start)
+
+    defaults = {match.group(1) for match in DEFAULT_EXPORT_PATTERN.finditer(stripped)}
+    exported_names = {name.split()[-1] for match in re.finditer(r'^export\s*\{([^}]*)\}', stripped, re.MULTILINE)
+                      for name in match.group(1).split(',') if name.strip()}
+    components = []
+
+    def add(name, kind, match, exported, default):
+        end = span_end(match.start())
+        if kind != 'class' and not JSX_PATTERN.search(stripped, match.end(), end):
+            return
+        components.append({'name': name, 'kind': kind, 'line': _line_of(stripped, match.start()), 'start': match.start(),
+                           'end': end, 'exported': bool(exported) or name in exported_names or name in defaults,
+                           'default': bool(default) or name in defaults})
+
+    for match in FUNCTION_COMPONENT_PATTERN.finditer(stripped):
+        if match.group(3) or match.group(2):
+            add(match.group(3) or file_name, 'function', match, match.group(1), match.group(2))
+    for match in VARIABLE_COMPONENT_PATTERN.finditer(stripped):
+        add(match.group(2), 'function', match, match.group(1), False)
+    for match in CLASS_COMPONENT_PATTERN.finditer(stripped):
+        if match.group(4) in COMPONENT_BASES:
+            add(match.group(3), 'class', match, match.group(1), match.group(2))
+    return sorted(components, key=lambda component: component['start'])
+
+def _read_balanced(text, pos, open_char, close_char):
+    """Return the index just past the bracket that closes the one at pos, skipping strings."""
+    depth = 0
+    quote = None
+    i = pos
+    while i < len(text):
+        char = text[i]
+        if quote:
+            if char == '\\':
+                i += 1
+            elif char == quote:
+                quote = None
+        elif char in '\'"`':
+            quote = char
+        elif char == open_char:
+            depth += 1
+        elif char == close_char:
+            depth -= 1
+            if depth == 0:
+                return i + 1
+        i += 1
+    return len(text)
+
+def _split_top_level(text):
+    """Split text at the commas outside brackets and strings."""
+    parts = []
+    depth = 0
+    quote = None
+    start = 0
+    for i, char in enumerate(text):
+        if quote:
+            if char == quote and text[i - 1] != '\\':
+                quote = None
+        elif char in '\'"`':
+            quote = char
+        elif char in '([{':
+            depth += 1
+        elif char in ')]}':
+            depth -= 1
+        elif char == ',' and depth == 0:
+            parts.append(text[start:i])
+            start = i + 1
+    parts.append(text[start:])
+    return [part for part in parts if part.strip()]
+
+def _format_prop_value(value, limit=40):
+    """Collapse whitespace in a prop value and shorten long expressions."""
+    value = ' '.join(value.split())
+    return value if len(value) <= limit else value[:limit - 3] + '...'
+
+def parse_tag_props(text, pos):
+    """Read the attributes of a JSX or template tag starting at pos (just after its name).
+
+    Returns (props, end) where props are strings such as `label="Save"`, `onClick={save}`,
+    `disabled` or `{...rest}`, and end is the position after the tag's `>` or `/>`.
+    """
+    props = []
+    i = pos
+    while i < len(text):
+        while i < len(text) and text[i].isspace():
+            i += 1
+        if text.startswith('/>', i):
+            return props, i + 2
+        if i >= len(text) or text[i] == '>':
+            return props, i + 1
+        if text[i] == '{':
+            end = _read_balanced(text, i, '{', '}')
+            props.append(_format_prop_value(text[i:end]))
+            i = end
+            continue
+        name = re.match(r'[^\s=/>{]+', text[i:])
+        if not name:
+            i += 1
+            continue
+        i += len(name.group(0))
+        if text.startswith('=', i):
+            i += 1
+            if i < len(text) and text[i] in '\'"':
+                end = text.find(text[i], i + 1)
+                end = len(text) - 1 if end == -1 else end
+                value = text[i:end + 1]
+                i = end + 1
+            elif i < len(text) and text[i] == '{':
+                end = _read_balanced(text, i, '{', '}')
+                value = text[i:end]
+                i = end
+            else:
+                value = re.match(r'[^\s/>]*', text[i:]).group(0)
+                i += len(value)
+            props.append(f"{name.group(0)}={_format_prop_value(value)}")
+        else:
+            props.append(name.group(0))
+    return props, len(text)
+
+def find_component_usages(content, file_path, components):
+    """Return the component tags a file renders, as {'parent', 'tag', 'name', 'props', 'line'}.
+
+    parent is the name of the component whose source contains the tag. Vue and Svelte
+    templates also match kebab-case tags, whose name is given in PascalCase.
+    """
+    extension = os.path.splitext(file_path)[1].lower()
+    if extension in ['.vue', '.svelte']:
+        _, markup = split_sfc(content)
+        pattern = TEMPLATE_TAG_PATTERN
+    else:
+        markup = strip_js_comments(content)
+        pattern = JSX_TAG_PATTERN
+
+    usages = []
+    for match in pattern.finditer(markup):
+        parent = next((component for component in reversed(components)
+                       if component['start'] <= match.start() < component['end']), None)
+        if parent is None:
+            continue
+        props, _ = parse_tag_props(markup, match.end())
+        tag = match.group(1)
+        usages.append({'parent': parent['name'], 'tag': tag, 'name': _pascal_case(tag) if '-' in tag else tag,
+                       'props': props, 'line': _line_of(markup, match.start())})
+    return usages
+
+def collect_component_files(path):
+    """Collect the JavaScript, TypeScript, Vue and Svelte files under a path."""
+    if not os.path.isdir(path):
+        return [path]
+    component_files = []
+    for root, dirs, files in os.walk(path):
+        dirs[:] = sorted(d for d in dirs if d not in EXCLUDED_DIRECTORIES)
+        for file in sorted(files):
+            if os.path.splitext(file)[1].lower() in COMPONENT_EXTENSIONS and not file.endswith('.d.ts'):
+                component_files.append(os.path.join(root, file))
+    return component_files
+
+def build_component_tree(path):
+    """Analyze React, Vue and Svelte components and which components render which.
+
+    Returns {'root', 'components', 'usages', 'roots'}. Components are {'id', 'name', 'kind',
+    'file', 'line', 'exported', 'default'} with id `file#Name`. Usages are {'parent', 'child',
+    'name', 'source', 'props', 'file', 'line'}: child is the id of the rendered component,
+    or None when it comes from a package (source is then the import specifier) or cannot be
+    found. roots are the components no other component renders.
+    """
+    component_files = collect_component_files(path)
+    files = {os.path.abspath(file_path): os.path.relpath(file_path, path) if os.path.isdir(path) else os.path.basename(file_path)
+             for file_path in component_files}
+    resolve_specifier = js_module_resolver(files)
+    exports_of = js_export_resolver(files, resolve_specifier)
+
+    sources = {}
+    components = {}
+    for file_path in files:
+        try:
+            with open(file_path, 'r', encoding='utf-8', errors='ignore') as f:
+                sources[file_path] = f.read()
+        except (OSError, IOError):
+            continue
+        components[file_path] = find_js_components(sources[file_path], file_path)
+
+    def component_id(file_path, component):
+        return f"{files[file_path]}#{component['name']}"
+
+    usages = []
+    for file_path, content in sources.items():
+        if not components[file_path]:
+            continue
+        script = split_sfc(content)[0] if os.path.splitext(file_path)[1].lower() in ['.vue', '.svelte'] else content
+
+        # The component each local name refers to: (target file, exported name or 'default')
+        bindings = {}
+        for imported in find_js_imports(script):
+            if imported['kind'] == 'reexport':
+                continue
+            target = resolve_specifier(imported['specifier'], file_path)
+            if imported['default']:
+                bindings[imported['default']] = (target, 'default', imported['specifier'])
+            if imported['namespace']:
+                bindings[imported['namespace']] = (target, '*', imported['specifier'])
+            for name, alias in imported['names']:
+                bindings[alias or name] = (target, name, imported['specifier'])
+
+        local = {component['name']: component for component in components[file_path]}
+        for usage in find_component_usages(content, file_path, components[file_path]):
+            child = None
+            source = None
+            head, _, member = usage['name'].partition('.')
+            if usage['name'] in local:
+                child = component_id(file_path, local[usage['name']])
+            elif head in bindings:
+                target, name, source = bindings[head]
+                if name == '*' and member:
+                    name = member.split('.')[-1]
+                if target in files:
+                    # Follow barrel files to the module defining the component
+                    target, name, _ = (exports_of(target).get(name) or [(target, name, ())])[0]
+                match = next((component for component in components.get(target, [])
+                              if (component['default'] if name == 'default' else component['name'] == name)), None)
+                if match:
+                    child = component_id(target, match)
+                    source = None
+            elif '-' in usage['tag']:
+                continue  # a custom element rather than a component
+            parent = component_id(file_path, local[usage['parent']])
+            usages.append({'parent': parent, 'child': child, 'name': usage['name'], 'source': source,
+                           'props': usage['props'], 'file': files[file_path], 'line': usage['line']})
+
+    component_list = [{'id': component_id(file_path, component), 'name': component['name'], 'kind': component['kind'],
+                       'file': files[file_path], 'line': component['line'], 'exported': component['exported'],
+                       'default': component['default']}
+                      for file_path in files if file_path in components for component in components[file_path]]
+    rendered = {usage['child'] for usage in usages if usage['child'] and usage['child'] != usage['parent']}
+    roots = [component['id'] for component in component_list if component['id'] not in rendered]
+    return {'root': os.path.basename(os.path.abspath(path)), 'components': component_list, 'usages': usages, 'roots': roots}
@@ -9,6 +9,17 @@ import json
 import xml.etree.ElementTree as ET
 from collections import defaultdict
 
+try:
+    import tomllib
+except ImportError:  # Python < 3.11
+    tomllib = None
+
+CARGO_DEPENDENCY_SECTIONS = [
+    ('normal', 'dependencies'),
+    ('dev', 'dev-dependencies'),
+    ('build', 'build-dependencies'),
+]
+
 def parse_project_file(file_path):
     """Parse a project file and extract dependencies."""
     imports = []
@@ -103,6 +114,28 @@ def parse_project_file(file_path):
             except IOError:
                 pass
         
+        # Rust/Cargo Project Files
+        elif file_name == 'Cargo.toml':
+            manifest = parse_cargo_manifest(file_path)
+            if manifest:
+                package = manifest['package']
+                if package:
+                    exports.append(f"Crate:{package['name']}")
+                    if package['version']:
+                        symbols.append(f"Version:{package['version']}")
+                    if package['edition']:
+                        symbols.append(f"Edition:{package['edition']}")
+                
+                if manifest['workspace']:
+                    for member in manifest['workspace']['members']:
+                        symbols.append(f"WorkspaceMember:{member}")
+                    for dependency in manifest['workspace']['dependencies'].values():
+                        imports.append(f"workspace-{cargo_dependency_import(dependency)}")
+                
+                # Extract dependencies, including target-specific ones
+                for dependency in manifest['dependencies']:
+                    imports.append(cargo_dependency_import(dependency))
+        
         # Maven Project Files
         elif file_name == 'pom.xml':
             try:
@@ -157,3 +190,187 @@ def parse_project_file(file_path):
         print(f"Warning: Error parsing project file {file_path}: {str(e)}")
     
     return imports, exports, symbols
+
+def parse_cargo_manifest(file_path):
+    """Parse a Cargo.toml into its package, dependency and workspace sections.
+    
+    Returns None when the manifest cannot be read. Dependencies are listed in the order
+    [dependencies], [dev-dependencies], [build-dependencies] and then the
+    [target.'cfg(...)'.*] tables, each as a dict with the dependency kind and target.
+    """
+    if tomllib is None:
+        return None
+    
+    try:
+        with open(file_path, 'rb') as f:
+            data = tomllib.load(f)
+    except (tomllib.TOMLDecodeError, IOError):
+        return None
+    
+    manifest = {
+        'package': None,
+        'lib': None,
+        'dependencies': [],
+        'features': data.get('features', {}),
+        'workspace': None,
+    }
+    
+    package = data.get('package')
+    if isinstance(package, dict) and 'name' in package:
+        manifest['package'] = {
+            'name': package['name'],
+            'version': _cargo_inheritable(package.get('version')),
+            'edition': _cargo_inheritable(package.get('edition')),
+        }
+        lib = data.get('lib', {})
+        manifest['lib'] = {
+            'name': lib.get('name', package['name'].replace('-', '_')),
+            'path': lib.get('path', os.path.join('src', 'lib.rs')),
+        }
+    
+    for kind, section in CARGO_DEPENDENCY_SECTIONS:
+        for name, spec in data.get(section, {}).items():
+            manifest['dependencies'].append(_cargo_dependency(name, spec, kind, None))
+    
+    for target, target_data in data.get('target', {}).items():
+        for kind, section in CARGO_DEPENDENCY_SECTIONS:
+            for name, spec in target_data.get(section, {}).items():
+                manifest['dependencies'].append(_cargo_dependency(name, spec, kind, target))
+    
+    workspace = data.get('workspace')
+    if isinstance(workspace, dict):
+        manifest['workspace'] = {
+            'members': workspace.get('members', []),
+            'exclude': workspace.get('exclude', []),
+            'dependencies': {name: _cargo_dependency(name, spec, 'normal', None)
+                             for name, spec in workspace.get('dependencies', {}).items()},
+            'package': workspace.get('package', {}),
+        }
+    
+    return manifest
+
+def _cargo_inheritable(value):
+    """Return a package field, or 'workspace' when it is inherited with `field.workspace = true`."""
+    if isinstance(value, dict):
+        return 'workspace' if value.get('workspace') else None
+    return value
+
+def _cargo_dependency(name, spec, kind, target):
+    """Normalize a dependency entry, which is either a version string or an inline table."""
+    if isinstance(spec, str):
+        spec = {'version': spec}
+    
+    return {
+        'name': name,
+        'package': spec.get('package', name),
+        'kind': kind,
+        'target': target,
+        'version': spec.get('version'),
+        'path': spec.get('path'),
+        'git': spec.get('git'),
+        'workspace': bool(spec.get('workspace')),
+        'optional': bool(spec.get('optional')),
+        'features': spec.get('features', []),
+        'default_features': spec.get('default-features', spec.get('default_features', True)),
+    }
+
+def cargo_dependency_requirement(dependency):
+    """Describe where a dependency comes from: its version requirement, path, git URL or the workspace."""
+    if dependency['path']:
+        return f"path {dependency['path']}"
+    if dependency['git']:
+        return f"git {dependency['git']}"
+    if dependency['workspace']:
+        return 'workspace'
+    return dependency['version'] or '*'
+
+def cargo_dependency_import(dependency):
+    """Format a dependency as an import string, e.g. `dev-dependency[cfg(unix)]:libc (0.2)`."""
+    dep_type = 'dependency' if dependency['kind'] == 'normal' else f"{dependency['kind']}-dependency"
+    if dependency['target']:
+        dep_type += f"[{dependency['target']}]"
+    return f"{dep_type}:{dependency['name']} ({cargo_dependency_requirement(dependency)})"
+
+def parse_cargo_lock(file_path):
+    """Parse the [[package]] entries of a Cargo.lock.
+    
+    Returns a list of {name, version, source, dependencies}, where each dependency is a
+    (name, version, source) triple. Lockfiles only spell out the version of a dependency
+    when several versions of that crate are locked, and its source when the same version
+    comes from several sources, so either may be None.
+    """
+    if tomllib is None:
+        return None
+    
+    try:
+        with open(file_path, 'rb') as f:
+            data = tomllib.load(f)
+    except (tomllib.TOMLDecodeError, IOError):
+        return None
+    
+    packages = []
+    for package in data.get('package', []):
+        dependencies = []
+        for dependency in package.get('dependencies', []):
+            # "name", "name version" or "name version (source)"
+            parts = dependency.split(' ', 2)
+            dependencies.append((parts[0], parts[1] if len(parts) > 1 else None,
+                                 parts[2].strip('()') if len(parts) > 2 else None))
+        packages.append({
+            'name': package['name'],
+            'version': package['version'],
+            'source': package.get('source'),
+            'dependencies': dependencies,
+        })
+    
+    return packages
+
+def parse_pyproject_source_roots(file_path):
+    """Return the source directories a pyproject.toml puts on sys.path, relative to it.
+    
+    Covers setuptools `package-dir` and `packages.find.where`, Poetry `packages` entries
+    with `from`, Hatch wheel `packages`, PDM `package-dir` and pytest `pythonpath`.
+    Returns None when the file cannot be read.
+    """
+    if tomllib is None:
+        return None
+    
+    try:
+        with open(file_path, 'rb') as f:
+            data = tomllib.load(f)
+    except (tomllib.TOMLDecodeError, IOError):
+        return None
+    
+    tool = data.get('tool', {})
+    roots = []
+    
+    def add(directory):
+        directory = os.path.normpath(directory)
+        if directory not in roots:
+            roots.append(directory)
+    
+    setuptools = tool.get('setuptools', {})
+    package_dir = setuptools.get('package-dir', {})
+    if isinstance(package_dir, dict) and '' in package_dir:
+        add(package_dir[''])
+    packages = setuptools.get('packages', {})
+    if isinstance(packages, dict):
+        for where in packages.get('find', {}).get('where', []):
+            add(where)
+    
+    for package in tool.get('poetry', {}).get('packages', []):
+        if isinstance(package, dict):
+            add(package.get('from', '.'))
+    
+    for package in tool.get('hatch', {}).get('build', {}).get('targets', {}).get('wheel', {}).get('packages', []):
+        add(os.path.dirname(package) or '.')
+    
+    pdm_package_dir = tool.get('pdm', {}).get('build', {}).get('package-dir')
+    if isinstance(pdm_package_dir, str):
+        add(pdm_package_dir)
+    
+    pythonpath = tool.get('pytest', {}).get('ini_options', {}).get('pythonpath', [])
+    for directory in [pythonpath] if isinstance(pythonpath, str) else pythonpath:
+        add(directory)
+    
+    return roots
@@ -0,0 +1,667 @@
+"""
+Python source analysis for CLIArt, built on the standard library ast module.
+
+The syntax tree gives exact class and function nesting, decorators, async definitions,
+line numbers and call expressions, which the regex-based parsers could only guess at.
+"""
+
+import ast
+import os
+import re
+
+from project_parsers import parse_pyproject_source_roots
+
+# Class decorators and base classes whose annotated class attributes are instance fields
+DATACLASS_DECORATORS = {'dataclass', 'dataclasses.dataclass', 'pydantic.dataclasses.dataclass'}
+ATTRS_DECORATORS = {'attr.s', 'attr.attrs', 'attr.define', 'attr.frozen', 'attr.mutable', 'attrs.define',
+                    'attrs.frozen', 'attrs.mutable', 'define', 'frozen', 'mutable', 'attrs'}
+ATTRS_FIELD_CALLS = {'attr.ib', 'attr.attrib', 'attr.field', 'attrs.field', 'attrib'}
+PYDANTIC_BASES = {'BaseModel', 'pydantic.BaseModel', 'BaseSettings', 'pydantic_settings.BaseSettings', 'RootModel'}
+
+# Files that failed to parse, so the regex fallback warning prints once per file
+_regex_parsed_files = set()
+
+
+def parse_python(content, file_path='<unknown>'):
+    """Parse Python source into a module AST.
+
+    Source that is not valid Python 3 (a Python 2 `print "hi"`, a merge conflict) falls back
+    to the regex scan of _regex_python_module, with a warning printed once per file.
+    """
+    try:
+        return ast.parse(content, filename=file_path)
+    except (SyntaxError, ValueError) as e:
+        # callers pass the same file both relative and absolute
+        key = os.path.abspath(file_path)
+        if key not in _regex_parsed_files:
+            _regex_parsed_files.add(key)
+            print(f"Warning: {file_path} is not valid Python 3 ({e}); falling back to regex parsing")
+        return _regex_python_module(content)
+
+def _stub_definition(source, fallback):
+    """Parse a one-line `def`/`class` stub, or the fallback stub if its header does not parse."""
+    try:
+        return ast.parse(source).body[0]
+    except SyntaxError:
+        return ast.parse(fallback).body[0]
+
+def _regex_python_module(content):
+    """Build a module AST from the `import`, `class` and `def` lines of unparseable source.
+
+    Definitions are nested by indentation and get their header's parameters and bases;
+    their bodies only hold the nested definitions, so calls inside them are not seen.
+    """
+    module = ast.Module(body=[], type_ignores=[])
+    lines = content.split('\n')
+    pattern = (r'^([ \t]*)(?:(async\s+)?def\s+(\w+)\s*\(([^)]*)\)\s*(->[^:\n]*)?|'
+               r'class\s+(\w+)(?:\s*\(([^)]*)\))?)\s*:'
+               r'|^[ \t]*((?:import|from)\s[^\n]*)')
+    stack = []  # (indent, node) of the enclosing definitions
+    for match in re.finditer(pattern, content, re.MULTILINE):
+        line = content.count('\n', 0, match.start()) + 1
+        if match.group(8):
+            try:
+                statements = ast.parse(match.group(8).strip()).body
+            except SyntaxError:
+                continue
+            for statement in statements:
+                statement.lineno = statement.end_lineno = line
+            module.body.extend(statements)
+            continue
+        indent = len(match.group(1).expandtabs())
+        if match.group(3):
+            params = ' '.join(match.group(4).split())
+            prefix = match.group(2) or ''
+            node = _stub_definition(f"{prefix}def {match.group(3)}({params}){match.group(5) or ''}: pass",
+                                    f"{prefix}def {match.group(3)}(): pass")
+        else:
+            bases = ' '.join((match.group(7) or '').split())
+            node = _stub_definition(f"class {match.group(6)}({bases}): pass", f"class {match.group(6)}: pass")
+        node.body = []
+
+        # The block runs until the next non-blank line indented no deeper than the header
+        end = line + match.group(0).count('\n')
+        for number in range(end, len(lines)):
+            text = lines[number].expandtabs()
+            if text.strip() and not text.lstrip().startswith('#'):
+                if len(text) - len(text.lstrip()) <= indent:
+                    break
+                end = number + 1
+        node.lineno, node.end_lineno = line, end
+
+        while stack and stack[-1][0] >= indent:
+            stack.pop()
+        (stack[-1][1].body if stack else module.body).append(node)
+        stack.append((indent, node))
+    return module
+
+def python_params(arguments):
+    """Format a function's parameters as written, e.g. `self, name: str, count: int = 0, *args, **kwargs`."""
+    def parameter(argument, default=None, prefix=''):
+        text = prefix + argument.arg
+        if argument.annotation:
+            text += f": {ast.unparse(argument.annotation)}"
+        if default is not None:
+            text += f" = {ast.unparse(default)}" if argument.annotation else f"={ast.unparse(default)}"
+        return text
+    
+    params = []
+    positional = arguments.posonlyargs + arguments.args
+    defaults = [None] * (len(positional) - len(arguments.defaults)) + arguments.defaults
+    for index, (argument, default) in enumerate(zip(positional, defaults)):
+        params.append(parameter(argument, default))
+        if index == len(arguments.posonlyargs) - 1:
+            params.append('/')
+    if arguments.vararg:
+        params.append(parameter(arguments.vararg, prefix='*'))
+    elif arguments.kwonlyargs:
+        params.append('*')
+    for argument, default in zip(arguments.kwonlyargs, arguments.kw_defaults):
+        params.append(parameter(argument, default))
+    if arguments.kwarg:
+        params.append(parameter(arguments.kwarg, prefix='**'))
+    return ', '.join(params)
+
+def _unparse(node):
+    """Return the source text of an expression node, or None."""
+    return ast.unparse(node) if node is not None else None
+
+def find_python_definitions(tree):
+    """Walk a module AST and return (classes, functions) with their exact nesting.
+
+    Classes are {'name', 'qualname', 'bases', 'keywords', 'decorators', 'scope', 'parent',
+    'line', 'end_line', 'node'}. Functions are {'name', 'qualname', 'params', 'owner',
+    'scope', 'parent', 'decorators', 'is_async', 'returns', 'line', 'end_line', 'node'}.
+
+    'scope' is where the definition sits: 'module', 'class' or 'function'. 'parent' is the
+    qualified name of the enclosing class or function, and 'owner' is the name of the class
+    whose body directly contains a method (None for functions and nested functions).
+    """
+    classes = []
+    functions = []
+
+    def visit(body, scope, parent, owner):
+        for node in body:
+            if isinstance(node, ast.ClassDef):
+                qualname = f"{parent}.{node.name}" if parent else node.name
+                classes.append({
+                    'name': node.name,
+                    'qualname': qualname,
+                    'bases': [_unparse(base) for base in node.bases],
+                    'keywords': {keyword.arg: _unparse(keyword.value) for keyword in node.keywords if keyword.arg},
+                    'decorators': [_unparse(decorator) for decorator in node.decorator_list],
+                    'scope': scope,
+                    'parent': parent,
+                    'line': node.lineno,
+                    'end_line': node.end_lineno,
+                    'node': node,
+                })
+                visit(node.body, 'class', qualname, node.name)
+            elif isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef)):
+                qualname = f"{parent}.{node.name}" if parent else node.name
+                functions.append({
+                    'name': node.name,
+                    'qualname': qualname,
+                    'params': python_params(node.args),
+                    'owner': owner if scope == 'class' else None,
+                    'scope': scope,
+                    'parent': parent,
+                    'decorators': [_unparse(decorator) for decorator in node.decorator_list],
+                    'is_async': isinstance(node, ast.AsyncFunctionDef),
+                    'returns': _unparse(node.returns),
+                    'line': node.lineno,
+                    'end_line': node.end_lineno,
+                    'node': node,
+                })
+                visit(node.body, 'function', qualname, None)
+            else:
+                # Definitions under if/try/with/for blocks keep the enclosing scope
+                for field in ['body', 'orelse', 'finalbody', 'handlers']:
+                    nested = getattr(node, field, None)
+                    if isinstance(nested, list):
+                        visit([child for child in nested if isinstance(child, ast.AST)], scope, parent, owner)
+
+    visit(tree.body, 'module', None, None)
+    return classes, functions
+
+def python_data_model(cls):
+    """Return 'dataclass', 'attrs' or 'pydantic' when a class from find_python_definitions
+    declares its instance fields as annotated class attributes, otherwise None."""
+    for decorator in cls['decorators']:
+        name = decorator.split('(')[0]
+        if name in DATACLASS_DECORATORS:
+            return 'dataclass'
+        if name in ATTRS_DECORATORS:
+            return 'attrs'
+    if any(base.split('[')[0] in PYDANTIC_BASES for base in cls['bases']):
+        return 'pydantic'
+    return None
+
+def find_python_fields(cls):
+    """Return the attributes a class declares, in source order.
+    
+    Each is {'name', 'type', 'default', 'visibility', 'kind'}. The kind is 'field' for the
+    annotated attributes of a dataclass, attrs or pydantic class (and `attr.ib()` ones),
+    'class attribute' for other class-level assignments and `ClassVar` annotations, and
+    'attribute' for annotations without a value and for attributes `__init__` sets on self.
+    An attribute set from an annotated `__init__` parameter takes that parameter's type.
+    """
+    data_model = python_data_model(cls)
+    fields = []
+    names = set()
+    
+    def add(name, annotation, value, kind):
+        if name in names:
+            return
+        names.add(name)
+        fields.append({'name': name, 'type': _unparse(annotation), 'default': _unparse(value),
+                       'visibility': 'private' if name.startswith('_') and not name.endswith('__') else 'public',
+                       'kind': kind})
+    
+    for node in cls['node'].body:
+        if isinstance(node, ast.AnnAssign) and isinstance(node.target, ast.Name):
+            class_var = _unparse(node.annotation).split('[')[0] in ['ClassVar', 'typing.ClassVar']
+            if class_var:
+                kind = 'class attribute'
+            elif data_model:
+                kind = 'field'
+            else:
+                kind = 'attribute' if node.value is None else 'class attribute'
+            add(node.target.id, node.annotation, node.value, kind)
+        elif isinstance(node, ast.Assign):
+            attrs_field = (data_model == 'attrs' and isinstance(node.value, ast.Call)
+                           and _dotted_name(node.value.func) in ATTRS_FIELD_CALLS)
+            for target in node.targets:
+                if isinstance(target, ast.Name) and target.id not in ['__slots__', '__all__']:
+                    add(target.id, None, node.value, 'field' if attrs_field else 'class attribute')
+    
+    for node in cls['node'].body:
+        if not (isinstance(node, ast.FunctionDef) and node.name == '__init__'):
+            continue
+        arguments = node.args.posonlyargs + node.args.args + node.args.kwonlyargs
+        parameter_types = {argument.arg: argument.annotation for argument in arguments if argument.annotation}
+        for statement in ast.walk(node):
+            if isinstance(statement, ast.Assign):
+                targets, annotation = statement.targets, None
+            elif isinstance(statement, ast.AnnAssign):
+                targets, annotation = [statement.target], statement.annotation
+            else:
+                continue
+            for target in targets:
+                if (isinstance(target, ast.Attribute) and isinstance(target.value, ast.Name)
+                        and target.value.id == 'self'):
+                    if annotation is None and isinstance(statement.value, ast.Name):
+                        annotation = parameter_types.get(statement.value.id)
+                    add(target.attr, annotation, None, 'attribute')
+    return fields
+
+def python_has_a_edges(classes, project_classes):
+    """Return (owner, field, target) for fields whose annotated type mentions another project class.
+    
+    classes are class entries from the code model with their 'fields'. String annotations
+    such as `"Post"` and `list["Post"]` count too. Class attributes, such as `ClassVar`
+    registries, are shared by every instance rather than held by one, so they are skipped.
+    """
+    edges = []
+    for cls in classes:
+        for field in cls.get('fields') or []:
+            if field['kind'] == 'class attribute':
+                continue
+            for reference in dict.fromkeys(re.findall(r'[A-Za-z_]\w*', field['type'] or '')):
+                if reference in project_classes and (cls['name'], field['name'], reference) not in edges:
+                    edges.append((cls['name'], field['name'], reference))
+    return edges
+
+def find_python_calls(function_node):
+    """Return the calls made in a function's own body, leaving out nested functions and classes.
+
+    Each call is {'name', 'receiver', 'line'}: `foo()` has receiver None, `self.bar()` has
+    receiver 'self', `module.baz()` has 'module' and `a.b.c()` has 'a.b'. Calls on other
+    expressions (`get()()`, `items[0].run()`) have receiver '?'.
+    """
+    calls = []
+    definitions = (ast.FunctionDef, ast.AsyncFunctionDef, ast.ClassDef)
+
+    def visit(node):
+        if isinstance(node, ast.Call):
+            func = node.func
+            if isinstance(func, ast.Name):
+                calls.append({'name': func.id, 'receiver': None, 'line': node.lineno})
+            elif isinstance(func, ast.Attribute):
+                calls.append({'name': func.attr, 'receiver': _dotted_name(func.value) or '?', 'line': node.lineno})
+        for child in ast.iter_child_nodes(node):
+            if not isinstance(child, definitions):
+                visit(child)
+
+    for statement in function_node.body:
+        if not isinstance(statement, definitions):
+            visit(statement)
+    return calls
+
+def find_python_instances(function_node):
+    """Return {variable: class expression} for `x = ClassName(...)` assignments in a
+    function's own body, so that later `x.method()` calls can be resolved."""
+    instances = {}
+    definitions = (ast.FunctionDef, ast.AsyncFunctionDef, ast.ClassDef)
+    
+    def visit(node):
+        if isinstance(node, ast.Assign) and isinstance(node.value, ast.Call):
+            callee = _dotted_name(node.value.func)
+            for target in node.targets:
+                if isinstance(target, ast.Name) and callee:
+                    instances[target.id] = callee
+        for child in ast.iter_child_nodes(node):
+            if not isinstance(child, definitions):
+                visit(child)
+    
+    for statement in function_node.body:
+        if not isinstance(statement, definitions):
+            visit(statement)
+    return instances
+
+def _dotted_name(node):
+    """Return `a.b.c` for a chain of Name/Attribute nodes, or None for other expressions."""
+    parts = []
+    while isinstance(node, ast.Attribute):
+        parts.insert(0, node.attr)
+        node = node.value
+    if isinstance(node, ast.Name):
+        return '.'.join([node.id] + parts)
+    return None
+
+def find_python_imports(tree):
+    """Return every import statement in a module, including ones inside functions.
+
+    Each import is {'module', 'names', 'alias', 'level', 'line', 'from'} where names holds
+    (name, alias) pairs. For `import a.b` the module is 'a.b' and names is empty;
+    level counts the leading dots of a relative `from` import.
+    """
+    imports = []
+    for node in ast.walk(tree):
+        if isinstance(node, ast.Import):
+            for alias in node.names:
+                imports.append({'module': alias.name, 'names': [], 'alias': alias.asname, 'level': 0,
+                                'line': node.lineno, 'from': False})
+        elif isinstance(node, ast.ImportFrom):
+            imports.append({'module': node.module, 'names': [(alias.name, alias.asname) for alias in node.names],
+                            'alias': None, 'level': node.level, 'line': node.lineno, 'from': True})
+    return sorted(imports, key=lambda imported: imported['line'])
+
+def python_import_strings(imported):
+    """Format an import from find_python_imports as import strings, one per imported name.
+
+    `import a.b` gives `a.b`, `from a import b` gives `a.b`, and relative imports keep their
+    dots: `from .models import User` gives `.models.User` and `from . import x` gives `.x`.
+    """
+    if not imported['from']:
+        return [imported['module']]
+    base = '.' * imported['level'] + (imported['module'] or '')
+    separator = '.' if imported['module'] else ''
+    return [f"{base}{separator}{name}" for name, _ in imported['names']]
+
+def find_python_all(tree):
+    """Return the names listed in a module's `__all__`, or None when it has none."""
+    names = None
+    for node in tree.body:
+        targets = []
+        if isinstance(node, ast.Assign):
+            targets = node.targets
+        elif isinstance(node, (ast.AugAssign, ast.AnnAssign)):
+            targets = [node.target]
+        if not any(isinstance(target, ast.Name) and target.id == '__all__' for target in targets):
+            continue
+        if isinstance(node.value, (ast.List, ast.Tuple)):
+            values = [element.value for element in node.value.elts
+                      if isinstance(element, ast.Constant) and isinstance(element.value, str)]
+            names = (names or []) + values if isinstance(node, ast.AugAssign) else values
+    return names
+
+def find_python_roots(file_path):
+    """Return the sys.path roots that absolute imports in a Python file resolve against.
+    
+    Roots come from the nearest pyproject.toml (the source directories it configures, a
+    `src/` directory next to it, and its own directory), and from the directory above the
+    file's topmost package (the last directory with an `__init__.py`).
+    """
+    roots = []
+    
+    def add(directory):
+        if directory not in roots:
+            roots.append(directory)
+    
+    directory = os.path.dirname(os.path.abspath(file_path))
+    while True:
+        pyproject = os.path.join(directory, 'pyproject.toml')
+        if os.path.isfile(pyproject):
+            configured = parse_pyproject_source_roots(pyproject) or []
+            for source in configured:
+                add(os.path.normpath(os.path.join(directory, source)))
+            if os.path.isdir(os.path.join(directory, 'src')):
+                add(os.path.join(directory, 'src'))
+            add(directory)
+            break
+        parent = os.path.dirname(directory)
+        if parent == directory:
+            break
+        directory = parent
+    
+    directory = os.path.dirname(os.path.abspath(file_path))
+    if os.path.isfile(os.path.join(directory, '__init__.py')):
+        while os.path.isfile(os.path.join(directory, '__init__.py')) and os.path.dirname(directory) != directory:
+            directory = os.path.dirname(directory)
+        add(directory)
+    
+    return roots
+
+def _python_module_path(directory, parts, files):
+    """Return the file for module `parts` under directory: a `.py` module, a package's
+    `__init__.py`, or the directory itself for a namespace package. None if there is none."""
+    base = os.path.join(directory, *parts)
+    for candidate in [base + '.py', os.path.join(base, '__init__.py')]:
+        if candidate in files:
+            return candidate
+    if parts and any(file_path.startswith(base + os.sep) for file_path in files):
+        return base
+    return None
+
+def _python_bindings(tree):
+    """Map the names a module binds at the top level to ('def', None) for classes,
+    functions and assignments, or ('import', (import, name)) for names it imports."""
+    bindings = {}
+    stars = []
+    for imported in find_python_imports(tree):
+        if imported['from']:
+            for name, alias in imported['names']:
+                if name == '*':
+                    stars.append(imported)
+                else:
+                    bindings[alias or name] = ('import', (imported, name))
+        elif imported['alias']:
+            bindings[imported['alias']] = ('import', (imported, None))
+    for node in tree.body:
+        targets = []
+        if isinstance(node, (ast.ClassDef, ast.FunctionDef, ast.AsyncFunctionDef)):
+            bindings[node.name] = ('def', None)
+        elif isinstance(node, ast.Assign):
+            targets = node.targets
+        elif isinstance(node, (ast.AnnAssign, ast.AugAssign)):
+            targets = [node.target]
+        for target in targets:
+            if isinstance(target, ast.Name):
+                bindings[target.id] = ('def', None)
+    return bindings, stars
+
+def resolve_python_imports(python_files):
+    """Resolve each Python file's imports to the file and symbol that define them.
+    
+    python_files maps absolute file paths to the relative paths used in diagrams. The result
+    maps each relative path to {import string: (relative target path, symbol)}, using the
+    import strings extract_dependencies produces. Relative imports resolve against the
+    file's package, and absolute ones against the roots from find_python_roots and then the
+    file's own directory, as when it is run as a script. Names re-exported by an
+    `__init__.py` are followed to their definition. The symbol is None for module imports.
+    """
+    files = {os.path.abspath(file_path): rel_path for file_path, rel_path in python_files.items()}
+    roots = {}
+    trees = {}
+    
+    def tree_of(file_path):
+        if file_path not in trees:
+            try:
+                with open(file_path, 'r', encoding='utf-8', errors='ignore') as f:
+                    trees[file_path] = _python_bindings(parse_python(f.read(), file_path))
+            except (OSError, IOError, SyntaxError, ValueError):
+                trees[file_path] = ({}, [])
+        return trees[file_path]
+    
+    def find_module(imported, importer):
+        parts = imported['module'].split('.') if imported['module'] else []
+        if imported['level']:
+            directory = os.path.dirname(importer)
+            for _ in range(imported['level'] - 1):
+                directory = os.path.dirname(directory)
+            # `from . import x` imports from the package itself, which may be a namespace package
+            return _python_module_path(directory, parts, files) or (None if parts else directory)
+        if importer not in roots:
+            roots[importer] = find_python_roots(importer) + [os.path.dirname(importer)]
+        for root in roots[importer]:
+            module = _python_module_path(root, parts, files)
+            if module:
+                return module
+        return None
+    
+    def find_name(module, name, visited):
+        """Follow `name` in a module to the (file, symbol) that defines it."""
+        if (module, name) in visited:
+            return None
+        visited.add((module, name))
+        if module in files:
+            bindings, stars = tree_of(module)
+            binding = bindings.get(name)
+            if binding and binding[0] == 'def':
+                return module, name
+            if binding:
+                imported, original = binding[1]
+                source = find_module(imported, module)
+                if source and original is None:
+                    return (source, None) if source in files else None
+                # `from . import util` in an __init__.py binds the name to the package itself,
+                # so it and bindings that lead nowhere fall through to the submodule lookup
+                found = source and source != module and find_name(source, original, visited)
+                if found:
+                    return found
+            for imported in stars:
+                source = find_module(imported, module)
+                found = source and find_name(source, name, visited)
+                if found:
+                    return found
+        # Packages (an __init__.py or a namespace directory) can also hold a submodule of that name
+        package = os.path.dirname(module) if os.path.basename(module) == '__init__.py' else module
+        submodule = _python_module_path(package, [name], files) if package != module or module not in files else None
+        if submodule in files:
+            return submodule, None
+        return (module, name) if module in files else None
+    
+    resolved = {}
+    for file_path, rel_path in files.items():
+        try:
+            with open(file_path, 'r', encoding='utf-8', errors='ignore') as f:
+                tree = parse_python(f.read(), file_path)
+        except (OSError, IOError, SyntaxError, ValueError):
+            continue
+        
+        file_imports = {}
+        for imported in find_python_imports(tree):
+            module = find_module(imported, file_path)
+            if module is None:
+                continue
+            if imported['from']:
+                targets = [(imp, (module, None) if name == '*' else find_name(module, name, set()))
+                           for imp, (name, _) in zip(python_import_strings(imported), imported['names'])]
+            else:
+                targets = [(python_import_strings(imported)[0], (module, None))]
+            for imp, target in targets:
+                if target and target[0] in files and target[0] != file_path:
+                    file_imports[imp] = (files[target[0]], target[1])
+        if file_imports:
+            resolved[rel_path] = file_imports
+    
+    return resolved
+
+def find_python_call_graph(python_files):
+    """Resolve the calls in each Python function to the functions and classes they call.
+    
+    python_files maps absolute file paths to the relative paths used in diagrams. Returns a
+    list of {'caller', 'caller_file', 'callee', 'callee_file', 'line'} with qualified names
+    (`PostService.publish`). `foo()` resolves to a nested, module-level or imported function,
+    `ClassName()` to the class, `self.bar()` to a method of the class or one of its bases,
+    `module.baz()` to a function in an imported module, and `x.run()` to a method when x
+    was assigned `ClassName(...)` in the same function. Other calls are left out.
+    """
+    resolved = resolve_python_imports(python_files)
+    modules = {}
+    for file_path, rel_path in python_files.items():
+        try:
+            with open(file_path, 'r', encoding='utf-8', errors='ignore') as f:
+                tree = parse_python(f.read(), file_path)
+        except (OSError, IOError, SyntaxError, ValueError):
+            continue
+        classes, functions = find_python_definitions(tree)
+        module = {'definitions': {}, 'top': {}, 'names': {}, 'modules': {}, 'stars': [], 'functions': functions}
+        for definition in classes + functions:
+            definition['kind'] = 'class' if definition in classes else 'function'
+            module['definitions'].setdefault(definition['qualname'], definition)
+            if definition['scope'] == 'module':
+                module['top'][definition['name']] = definition
+        
+        # What each imported name refers to: (target file, symbol), symbol None for a module
+        file_imports = resolved.get(rel_path, {})
+        for imported in find_python_imports(tree):
+            strings = python_import_strings(imported)
+            if not imported['from']:
+                if strings[0] in file_imports:
+                    if imported['alias']:
+                        module['names'][imported['alias']] = file_imports[strings[0]]
+                    else:
+                        module['modules'][strings[0]] = file_imports[strings[0]][0]
+                continue
+            for (name, alias), imp in zip(imported['names'], strings):
+                if imp not in file_imports:
+                    continue
+                if name == '*':
+                    module['stars'].append(file_imports[imp][0])
+                else:
+                    module['names'][alias or name] = file_imports[imp]
+        modules[rel_path] = module
+    
+    def lookup(rel_path, name):
+        """(file, definition) for a class or function a module-level name refers to."""
+        module = modules.get(rel_path)
+        if module is None:
+            return None
+        if name in module['top']:
+            return rel_path, module['top'][name]
+        target, symbol = module['names'].get(name, (None, None))
+        if symbol and target in modules and symbol in modules[target]['top']:
+            return target, modules[target]['top'][symbol]
+        for star in module['stars']:
+            if star in modules and name in modules[star]['top'] and not name.startswith('_'):
+                return star, modules[star]['top'][name]
+        return None
+    
+    def module_of(rel_path, receiver):
+        """The file of the module a receiver such as `markdown` or `blog.models` names."""
+        module = modules[rel_path]
+        target, symbol = module['names'].get(receiver, (None, None))
+        if target and symbol is None:
+            return target
+        return module['modules'].get(receiver)
+    
+    def resolve_expression(rel_path, dotted):
+        """(file, definition) for a name or `module.name` expression."""
+        if '.' not in dotted:
+            return lookup(rel_path, dotted)
+        receiver, name = dotted.rsplit('.', 1)
+        target = module_of(rel_path, receiver)
+        return lookup(target, name) if target else None
+    
+    def find_method(rel_path, cls, name, visited):
+        """(file, definition) for a method of a class, searching its bases in order."""
+        if (rel_path, cls['qualname']) in visited:
+            return None
+        visited.add((rel_path, cls['qualname']))
+        method = modules[rel_path]['definitions'].get(f"{cls['qualname']}.{name}")
+        if method and method['kind'] == 'function':
+            return rel_path, method
+        for base in cls['bases']:
+            found = resolve_expression(rel_path, base.split('[')[0])
+            if found and found[1]['kind'] == 'class':
+                found = find_method(found[0], found[1], name, visited)
+                if found:
+                    return found
+        return None
+    
+    calls = []
+    for rel_path, module in modules.items():
+        for func in module['functions']:
+            owner = module['definitions'].get(func['parent']) if func['owner'] else None
+            instances = find_python_instances(func['node'])
+            for call in find_python_calls(func['node']):
+                receiver = call['receiver']
+                target = None
+                if receiver is None:
+                    nested = module['definitions'].get(f"{func['qualname']}.{call['name']}")
+                    target = (rel_path, nested) if nested else lookup(rel_path, call['name'])
+                elif receiver in ['self', 'cls'] and owner:
+                    target = find_method(rel_path, owner, call['name'], set())
+                elif receiver != '?':
+                    # A local created with `x = ClassName(...)` is an instance of that class
+                    found = resolve_expression(rel_path, instances.get(receiver, receiver))
+                    if found and found[1]['kind'] == 'class':
+                        target = find_method(found[0], found[1], call['name'], set())
+                    elif module_of(rel_path, receiver):
+                        target = lookup(module_of(rel_path, receiver), call['name'])
+                if target and target[1] is not func:
+                    calls.append({'caller': func['qualname'], 'caller_file': rel_path, 'callee': target[1]['qualname'],
+                                  'callee_file': target[0], 'line': call['line']})
+    return calls
@@ -0,0 +1,2119 @@
+"""
+Module for analyzing Rust crates.
+This gives CLIArt an understanding of the Rust module tree so `use` paths resolve to files.
+"""
+
+import os
+import re
+import glob
+from project_parsers import parse_cargo_manifest, parse_cargo_lock, cargo_dependency_import, cargo_dependency_requirement
+
+# Module-level items that can be the target of a `use` path
+RUST_ITEM_PATTERN = (r'(?:^|(?<=[\s;}]))(pub(?:\s*\([^)]*\))?\s+)?'
+                     r'(?:(?:unsafe|async|const|default|extern(?:\s+"[^"]*")?)\s+)*'
+                     r'(struct|enum|trait|fn|type|const|static|union|mod)\s+(\w+)')
+MACRO_RULES_PATTERN = r'macro_rules!\s*(\w+)'
+
+def mask_rust_source(content):
+    """Blank out comments and the contents of string and char literals.
+
+    The result has the same length and line breaks as the input, so positions found in
+    the masked text can be used to slice the original. Lifetimes ('a) are left intact.
+    """
+    chars = list(content)
+    length = len(content)
+    i = 0
+
+    def blank(start, end):
+        for j in range(start, min(end, length)):
+            if chars[j] != '\n':
+                chars[j] = ' '
+
+    while i < length:
+        c = content[i]
+        nxt = content[i + 1] if i + 1 < length else ''
+
+        if c == '/' and nxt == '/':
+            # Line comment
+            end = content.find('\n', i)
+            end = length if end == -1 else end
+            blank(i, end)
+            i = end
+        elif c == '/' and nxt == '*':
+            # Block comment, which may nest
+            depth = 0
+            j = i
+            while j < length:
+                if content.startswith('/*', j):
+                    depth += 1
+                    j += 2
+                elif content.startswith('*/', j):
+                    depth -= 1
+                    j += 2
+                    if depth == 0:
+                        break
+                else:
+                    j += 1
+            blank(i, j)
+            i = j
+        elif (c == 'r' or (c == 'b' and nxt == 'r')) and re.match(r'b?r#*"', content[i:i + 260]) and (i == 0 or not (content[i - 1].isalnum() or content[i - 1] == '_')):
+            # Raw string: r"..." or r#"..."#, optionally a byte string
+            prefix = re.match(r'b?r(#*)"', content[i:]).group(0)
+            hashes = prefix.count('#')
+            start = i + len(prefix)
+            end = content.find('"' + '#' * hashes, start)
+            end = length if end == -1 else end
+            blank(start, end)
+            i = end + 1 + hashes
+        elif c == '"':
+            # Regular (or byte) string with escapes
+            j = i + 1
+            while j < length and content[j] != '"':
+                j += 2 if content[j] == '\\' else 1
+            blank(i + 1, j)
+            i = j + 1
+        elif c == "'":
+            # Char literal ('a', '\n', '\u{1F600}') or lifetime ('a)
+            match = re.match(r"'(?:\\(?:u\{[0-9a-fA-F]+\}|x[0-9a-fA-F]{2}|.)|[^\\'\n])'", content[i:i + 12])
+            if match:
+                blank(i + 1, i + len(match.group(0)) - 1)
+                i += len(match.group(0))
+            else:
+                i += 1
+        else:
+            i += 1
+
+    return ''.join(chars)
+
+def find_matching_brace(masked, open_pos):
+    """Return the position of the brace closing the one at open_pos in masked source."""
+    depth = 0
+    for i in range(open_pos, len(masked)):
+        if masked[i] == '{':
+            depth += 1
+        elif masked[i] == '}':
+            depth -= 1
+            if depth == 0:
+                return i
+    return len(masked)
+
+def top_level_text(masked, start, end):
+    """Return masked[start:end] with everything inside nested braces blanked out."""
+    chars = []
+    depth = 0
+    for c in masked[start:end]:
+        if c == '{':
+            chars.append(c if depth == 0 else ' ')
+            depth += 1
+        elif c == '}':
+            depth -= 1
+            chars.append(c if depth == 0 else ' ')
+        elif depth > 0 and c != '\n':
+            chars.append(' ')
+        else:
+            chars.append(c)
+    return ''.join(chars)
+
+def find_rust_uses(content):
+    """Return (use tree text, position) for every `use` declaration in Rust source."""
+    masked = mask_rust_source(content)
+    uses = []
+    for match in re.finditer(r'\buse\s+([^;]+);', masked):
+        uses.append((' '.join(match.group(1).split()), match.start()))
+    return uses
+
+def find_rust_mod_declarations(content):
+    """Return (name, position) for every out-of-line `mod name;` declaration."""
+    masked = mask_rust_source(content)
+    return [(match.group(1), match.start()) for match in re.finditer(r'\bmod\s+(\w+)\s*;', masked)]
+
+def expand_use_tree(text):
+    """Expand a use tree like `a::{b, c::d as e, *}` into (segments, alias) pairs."""
+    normalized = re.sub(r'\s+as\s+', ' as ', ' '.join(text.split()))
+    results = []
+
+    def split_top_level(body):
+        parts = []
+        depth = 0
+        current = ''
+        for c in body:
+            if c == '{':
+                depth += 1
+            elif c == '}':
+                depth -= 1
+            if c == ',' and depth == 0:
+                parts.append(current.strip())
+                current = ''
+            else:
+                current += c
+        if current.strip():
+            parts.append(current.strip())
+        return parts
+
+    def expand(prefix, part):
+        part = part.strip()
+        if not part:
+            return
+        brace = part.find('{')
+        if brace != -1 and part.endswith('}'):
+            head = [segment.strip() for segment in part[:brace].split('::') if segment.strip()]
+            for child in split_top_level(part[brace + 1:-1]):
+                if child == 'self':
+                    results.append((prefix + head, None))
+                else:
+                    expand(prefix + head, child)
+            return
+        alias = None
+        if ' as ' in part:
+            part, alias = [piece.strip() for piece in part.rsplit(' as ', 1)]
+        segments = [segment.strip() for segment in part.split('::') if segment.strip()]
+        results.append((prefix + segments, alias))
+
+    expand([], normalized)
+    return results
+
+def rust_imported_names(imported):
+    """Return the names an import string from extract_dependencies brings into scope.
+
+    Names are in the namespaces find_rust_symbols uses: `mod:name` for modules, `name!` for
+    macros and the bare name for other items. A `use` path can name any of the three.
+    """
+    if imported.startswith('mod:'):
+        return [imported]
+    if imported.startswith('macro:'):
+        return [f"{imported[len('macro:'):].split('::')[-1]}!"]
+    names = []
+    for segments, _ in expand_use_tree(imported):
+        if segments and segments[-1] not in ['*', 'self']:
+            names.extend([segments[-1], f"mod:{segments[-1]}", f"{segments[-1]}!"])
+    return names
+
+def _module_items(content, masked, start, end):
+    """Collect the items declared at the top level of a module body."""
+    flat = top_level_text(masked, start, end)
+    items = {}
+    for match in re.finditer(RUST_ITEM_PATTERN, flat, re.MULTILINE):
+        kind = match.group(2)
+        name = match.group(3)
+        visibility = ' '.join(match.group(1).split()) if match.group(1) else 'private'
+        items.setdefault(name, {'kind': kind, 'visibility': visibility, 'position': start + match.start(2),
+                                'start': start + match.start()})
+    for match in re.finditer(MACRO_RULES_PATTERN, flat):
+        items.setdefault(match.group(1), {'kind': 'macro', 'visibility': 'private', 'position': start + match.start()})
+    return items
+
+def _module_uses(masked, start, end):
+    """Collect the top-level `use` declarations of a module body with their visibility."""
+    flat = top_level_text(masked, start, end)
+    uses = []
+    for match in re.finditer(r'(?:^|(?<=[\s;}]))(pub(?:\s*\([^)]*\))?\s+)?use\s+([^;]+);', flat, re.MULTILINE):
+        visibility = ' '.join(match.group(1).split()) if match.group(1) else 'private'
+        # Nested braces of the use tree were blanked by top_level_text, so read it from masked
+        text = masked[start + match.start(2):start + match.end(2)]
+        uses.append({'visibility': visibility, 'tree': ' '.join(text.split()), 'position': start + match.start()})
+    return uses
+
+def _path_attribute(content, masked, position):
+    """Return the value of a #[path = "..."] attribute right before a declaration, if any."""
+    before = masked[:position]
+    match = re.search(r'#\[\s*path\s*=\s*"([^"]*)"\s*\]\s*(?:#\[[^\]]*\]\s*)*(?:pub(?:\s*\([^)]*\))?\s+)?$', before)
+    if not match:
+        return None
+    return content[match.start(1):match.end(1)]
+
+def build_module_tree(root_file):
+    """Walk `mod` declarations from a crate root and map each module path to its source.
+
+    Returns {('crate', 'a', 'b'): module} where module has 'file', the 'span' of its body
+    in that file, its top-level 'items' and 'uses', whether it is 'inline', and the 'cfg'
+    conditions on the `mod` declarations leading to it.
+    """
+    modules = {}
+    visited = set()
+
+    def parse_file(file_path, module_path, children_dir, cfg):
+        real_path = os.path.realpath(file_path)
+        if real_path in visited:
+            return
+        visited.add(real_path)
+        try:
+            with open(file_path, 'r', encoding='utf-8', errors='ignore') as f:
+                content = f.read()
+        except (OSError, IOError):
+            return
+        masked = mask_rust_source(content)
+        regions = find_cfg_regions(content)
+        parse_body(file_path, content, masked, regions, 0, len(content), module_path, children_dir, False, cfg)
+
+    def parse_body(file_path, content, masked, regions, start, end, module_path, children_dir, inline, cfg):
+        modules[module_path] = {
+            'path': module_path,
+            'file': file_path,
+            'span': (start, end),
+            'inline': inline,
+            'cfg': cfg,
+            'items': _module_items(content, masked, start, end),
+            'uses': _module_uses(masked, start, end)
+        }
+
+        flat = top_level_text(masked, start, end)
+        for match in re.finditer(r'\bmod\s+(\w+)\s*([;{])', flat):
+            name = match.group(1)
+            child_path = module_path + (name,)
+            declaration = start + match.start()
+            attribute_path = _path_attribute(content, masked, declaration)
+            child_cfg = cfg + [condition for condition in cfg_conditions_at(regions, declaration) if condition not in cfg]
+
+            if match.group(2) == '{':
+                # Inline module: same file, children live one directory deeper
+                open_pos = start + match.end() - 1
+                close_pos = find_matching_brace(masked, open_pos)
+                parse_body(file_path, content, masked, regions, open_pos + 1, close_pos, child_path,
+                           os.path.join(children_dir, attribute_path or name), True, child_cfg)
+                continue
+
+            if attribute_path:
+                # Outside inline modules #[path] is relative to the declaring file's directory
+                base_dir = children_dir if inline else os.path.dirname(file_path)
+                candidates = [os.path.normpath(os.path.join(base_dir, attribute_path))]
+            else:
+                candidates = [os.path.join(children_dir, f"{name}.rs"), os.path.join(children_dir, name, 'mod.rs')]
+
+            for candidate in candidates:
+                if os.path.isfile(candidate):
+                    if attribute_path or os.path.basename(candidate) == 'mod.rs':
+                        grandchildren_dir = os.path.dirname(candidate)
+                    else:
+                        grandchildren_dir = os.path.join(os.path.dirname(candidate), name)
+                    parse_file(candidate, child_path, grandchildren_dir, child_cfg)
+                    break
+
+    parse_file(root_file, ('crate',), os.path.dirname(root_file), [])
+    return modules
+
+def find_crate_roots(rust_files):
+    """Pick the crate root files (lib.rs, main.rs and binary/test/example targets) from a file list."""
+    roots = []
+    for file_path in rust_files:
+        name = os.path.basename(file_path)
+        parent = os.path.basename(os.path.dirname(file_path))
+        if name in ['lib.rs', 'main.rs'] or parent in ['bin', 'examples', 'tests', 'benches']:
+            roots.append(file_path)
+    # Library roots first so shared files are attributed to the library crate
+    return sorted(roots, key=lambda root: (os.path.basename(root) != 'lib.rs', root))
+
+def module_at(modules, file_path, position):
+    """Return the innermost module path whose body in file_path contains position."""
+    best = None
+    for module_path, module in modules.items():
+        if module['file'] != file_path:
+            continue
+        start, end = module['span']
+        if start <= position <= end and (best is None or len(module_path) > len(best)):
+            best = module_path
+    return best
+
+def resolve_use_path(segments, current, modules, extern_crates=None):
+    """Resolve a use path to (crate modules, module path, item name), or None if it is external.
+
+    extern_crates maps crate names to other module trees (e.g. workspace path dependencies).
+    """
+    if not segments:
+        return None
+    tree = modules
+    first = segments[0]
+    rest = segments[1:]
+
+    if first == 'crate':
+        base = ('crate',)
+    elif first == 'self':
+        base = current
+    elif first == 'super':
+        base = current[:-1] if len(current) > 1 else current
+        while rest and rest[0] == 'super':
+            base = base[:-1] if len(base) > 1 else base
+            rest = rest[1:]
+    elif current + (first,) in modules:
+        # 2018 uniform paths: a child module of the current module
+        base = current + (first,)
+    elif ('crate', first) in modules and current == ('crate',):
+        base = ('crate', first)
+    elif extern_crates and first in extern_crates:
+        tree = extern_crates[first]
+        base = ('crate',)
+    else:
+        return None
+
+    module_path = base
+    remaining = list(rest)
+    while remaining and module_path + (remaining[0],) in tree:
+        module_path = module_path + (remaining[0],)
+        remaining.pop(0)
+
+    item = remaining[0] if remaining and remaining[0] != '*' else None
+    return tree, module_path, item
+
+def find_item_definition(tree, module_path, item, extern_crates=None, depth=0):
+    """Follow `use` re-exports from a module to the (tree, module path) that defines item."""
+    module = tree.get(module_path)
+    if module is None or item is None or depth > 8:
+        return tree, module_path
+    if item in module['items']:
+        return tree, module_path
+
+    # Explicit re-exports first, then globs
+    for use in module['uses']:
+        for segments, alias in expand_use_tree(use['tree']):
+            if segments and segments[-1] != '*' and (alias or segments[-1]) == item:
+                resolution = resolve_use_path(segments, module_path, tree, extern_crates)
+                if resolution:
+                    target_tree, target_module, target_item = resolution
+                    return find_item_definition(target_tree, target_module, target_item, extern_crates, depth + 1)
+    for use in module['uses']:
+        for segments, _ in expand_use_tree(use['tree']):
+            if segments and segments[-1] == '*':
+                resolution = resolve_use_path(segments, module_path, tree, extern_crates)
+                if resolution:
+                    target_tree, target_module, _ = resolution
+                    found_tree, found_module = find_item_definition(target_tree, target_module, item, extern_crates, depth + 1)
+                    if item in found_tree[found_module]['items']:
+                        return found_tree, found_module
+    return tree, module_path
+
+def resolve_rust_imports(rust_files, extern_crates=None):
+    """Resolve each Rust file's `use` and `mod` imports to the files that define them.
+
+    rust_files maps absolute file paths to the relative paths used in diagrams. The result
+    maps each relative path to {import string: [relative target paths]}, using the same
+    import strings extract_dependencies produces. Crates named in extern_crates, plus the
+    path dependencies declared in each crate's Cargo.toml, resolve into their own module trees.
+    """
+    resolved = {}
+    trees = {root: build_module_tree(root) for root in find_crate_roots(list(rust_files.keys()))}
+    crate_externs = {root: dict(_cargo_extern_trees(root, trees), **(extern_crates or {})) for root in trees}
+
+    for file_path, rel_path in rust_files.items():
+        owning = [(root, modules) for root, modules in trees.items()
+                  for module in modules.values() if module['file'] == file_path and not module['inline']]
+        if not owning:
+            continue
+        root, modules = owning[0]
+        externs = crate_externs[root]
+
+        try:
+            with open(file_path, 'r', encoding='utf-8', errors='ignore') as f:
+                content = f.read()
+        except (OSError, IOError):
+            continue
+
+        file_imports = {}
+        for tree_text, position in find_rust_uses(content):
+            current = module_at(modules, file_path, position) or ('crate',)
+            targets = []
+            for segments, _ in expand_use_tree(tree_text):
+                resolution = resolve_use_path(segments, current, modules, externs)
+                if resolution is None:
+                    continue
+                target_tree, module_path, item = resolution
+                target_tree, module_path = find_item_definition(target_tree, module_path, item, externs)
+                target = target_tree[module_path]['file']
+                # `use super::*` in an inline `mod tests` resolves to this same file
+                if target in rust_files and rust_files[target] not in targets:
+                    targets.append(rust_files[target])
+            if targets:
+                file_imports[tree_text] = targets
+
+        for name, position in find_rust_mod_declarations(content):
+            current = module_at(modules, file_path, position) or ('crate',)
+            child = modules.get(current + (name,))
+            if child and child['file'] in rust_files and child['file'] != file_path:
+                file_imports[f"mod:{name}"] = [rust_files[child['file']]]
+
+        for path, _ in find_rust_macro_invocations(content):
+            target = find_macro_definition(path, modules, externs)
+            if target in rust_files and target != file_path:
+                file_imports[f"macro:{path}"] = [rust_files[target]]
+
+        resolved[rel_path] = file_imports
+
+    return resolved
+
+def find_macro_definition(path, modules, extern_crates=None):
+    """Return the file defining the macro_rules! macro a `path!` invocation refers to, if local.
+
+    Unqualified names are looked up in the current crate first and then in the external
+    crates (the `#[macro_use] extern crate` style); `krate::name!` looks in that crate only.
+    """
+    segments = path.split('::')
+    name = segments[-1]
+    if name in STD_MACROS and len(segments) == 1:
+        return None
+    if len(segments) == 1:
+        trees = [modules] + list((extern_crates or {}).values())
+    elif segments[0] == 'crate':
+        trees = [modules]
+    elif extern_crates and segments[0] in extern_crates:
+        trees = [extern_crates[segments[0]]]
+    else:
+        return None
+    for tree in trees:
+        for module in tree.values():
+            item = module['items'].get(name)
+            if item and item['kind'] == 'macro':
+                return module['file']
+    return None
+
+def _cargo_extern_trees(root_file, trees):
+    """Map the crate names visible from a crate root to the module trees of local library crates.
+
+    Binaries, tests and examples see their own package's library, and every crate sees its
+    path dependencies (directly or through `workspace = true`).
+    """
+    manifest_path = find_package_manifest(root_file)
+    if manifest_path is None:
+        return {}
+    manifest = parse_cargo_manifest(manifest_path)
+    if not manifest or not manifest['package']:
+        return {}
+
+    libraries = []
+    own_lib = os.path.join(os.path.dirname(manifest_path), manifest['lib']['path'])
+    if os.path.abspath(own_lib) != os.path.abspath(root_file):
+        libraries.append((manifest['lib']['name'], own_lib))
+    for dependency in resolve_cargo_dependencies(manifest_path, manifest):
+        target = dependency['manifest']
+        target_manifest = parse_cargo_manifest(target) if target else None
+        if target_manifest and target_manifest['lib']:
+            lib_file = os.path.join(os.path.dirname(target), target_manifest['lib']['path'])
+            libraries.append((dependency['name'].replace('-', '_'), lib_file))
+
+    externs = {}
+    for name, lib_file in libraries:
+        matching = [root for root in trees if os.path.abspath(root) == os.path.abspath(lib_file)]
+        if matching:
+            externs[name] = trees[matching[0]]
+        elif os.path.isfile(lib_file):
+            externs[name] = build_module_tree(lib_file)
+    return externs
+
+def find_cargo_manifests(path):
+    """Collect the Cargo.toml files under path, skipping build output and hidden directories."""
+    if os.path.isfile(path):
+        return [path] if os.path.basename(path) == 'Cargo.toml' else []
+
+    manifests = []
+    for root, dirs, files in os.walk(path):
+        dirs[:] = sorted(d for d in dirs if not d.startswith('.') and d != 'target')
+        if 'Cargo.toml' in files:
+            manifests.append(os.path.join(root, 'Cargo.toml'))
+    return manifests
+
+def find_package_manifest(file_path):
+    """Return the nearest Cargo.toml with a [package] section above a source file."""
+    directory = os.path.dirname(os.path.abspath(file_path))
+    while True:
+        candidate = os.path.join(directory, 'Cargo.toml')
+        if os.path.isfile(candidate):
+            manifest = parse_cargo_manifest(candidate)
+            if manifest and manifest['package']:
+                return candidate
+        parent = os.path.dirname(directory)
+        if parent == directory:
+            return None
+        directory = parent
+
+def find_workspace_manifest(manifest_path):
+    """Return the Cargo.toml with a [workspace] section that owns a manifest, if any."""
+    directory = os.path.dirname(os.path.abspath(manifest_path))
+    while True:
+        candidate = os.path.join(directory, 'Cargo.toml')
+        if os.path.isfile(candidate):
+            manifest = parse_cargo_manifest(candidate)
+            if manifest and manifest['workspace']:
+                return candidate
+        parent = os.path.dirname(directory)
+        if parent == directory:
+            return None
+        directory = parent
+
+def cargo_workspace_members(workspace_path, workspace):
+    """Expand the member globs of a [workspace] section into member manifest paths."""
+    base_dir = os.path.dirname(os.path.abspath(workspace_path))
+    excluded = {os.path.normpath(os.path.join(base_dir, pattern)) for pattern in workspace['exclude']}
+    members = []
+    for pattern in workspace['members']:
+        for directory in sorted(glob.glob(os.path.join(base_dir, pattern))):
+            directory = os.path.normpath(directory)
+            manifest = os.path.join(directory, 'Cargo.toml')
+            if directory not in excluded and os.path.isfile(manifest) and manifest not in members:
+                members.append(manifest)
+    return members
+
+def resolve_cargo_dependencies(manifest_path, manifest=None):
+    """Return a manifest's dependencies with `workspace = true` entries filled in from the workspace.
+
+    Each dependency also gets its 'import' string (as parse_project_file reports it), its
+    'requirement' after inheritance and the 'manifest' path of a path dependency.
+    """
+    manifest = manifest or parse_cargo_manifest(manifest_path)
+    if not manifest:
+        return []
+
+    workspace_path = None
+    workspace_dependencies = {}
+    if any(dependency['workspace'] for dependency in manifest['dependencies']):
+        workspace_path = find_workspace_manifest(manifest_path)
+        workspace_manifest = parse_cargo_manifest(workspace_path) if workspace_path else None
+        if workspace_manifest:
+            workspace_dependencies = workspace_manifest['workspace']['dependencies']
+
+    resolved = []
+    for dependency in manifest['dependencies']:
+        entry = dict(dependency, import_string=cargo_dependency_import(dependency))
+        base_dir = os.path.dirname(os.path.abspath(manifest_path))
+        inherited = workspace_dependencies.get(dependency['name']) if dependency['workspace'] else None
+        if inherited:
+            base_dir = os.path.dirname(os.path.abspath(workspace_path))
+            entry.update({
+                'package': inherited['package'],
+                'version': inherited['version'],
+                'path': inherited['path'],
+                'git': inherited['git'],
+                'workspace': False,
+                'features': inherited['features'] + [f for f in dependency['features'] if f not in inherited['features']],
+            })
+        entry['requirement'] = cargo_dependency_requirement(entry)
+        entry['manifest'] = None
+        if entry['path']:
+            target = os.path.normpath(os.path.join(base_dir, entry['path'], 'Cargo.toml'))
+            if os.path.isfile(target):
+                entry['manifest'] = target
+        resolved.append(entry)
+    return resolved
+
+def resolve_cargo_imports(manifest_files):
+    """Resolve path dependencies in Cargo.toml files to the manifests of the crates they name.
+
+    manifest_files maps absolute manifest paths to relative paths, as in resolve_rust_imports.
+    """
+    by_path = {os.path.abspath(file_path): rel_path for file_path, rel_path in manifest_files.items()}
+    resolved = {}
+    for file_path, rel_path in manifest_files.items():
+        manifest = parse_cargo_manifest(file_path)
+        if not manifest:
+            continue
+        dependencies = resolve_cargo_dependencies(file_path, manifest)
+        if manifest['workspace']:
+            # [workspace.dependencies] paths are relative to the workspace root itself
+            for dependency in manifest['workspace']['dependencies'].values():
+                entry = dict(dependency, import_string=f"workspace-{cargo_dependency_import(dependency)}", manifest=None)
+                if dependency['path']:
+                    entry['manifest'] = os.path.normpath(os.path.join(os.path.dirname(os.path.abspath(file_path)),
+                                                                      dependency['path'], 'Cargo.toml'))
+                dependencies.append(entry)
+
+        file_imports = {}
+        for dependency in dependencies:
+            target = by_path.get(dependency['manifest'])
+            if target and target != rel_path:
+                file_imports[dependency['import_string']] = [target]
+        resolved[rel_path] = file_imports
+    return resolved
+
+def build_crate_graph(path):
+    """Build the inter-crate dependency graph for the Cargo packages and workspaces under path.
+
+    Returns {'root', 'workspaces', 'crates', 'edges', 'external'}. Edges between local
+    crates are 'internal'; every other dependency is collected in 'external' with the
+    version requirements asked for across the graph.
+    """
+    base_dir = path if os.path.isdir(path) else os.path.dirname(path)
+    manifests = {}
+    for manifest_path in find_cargo_manifests(path):
+        manifest = parse_cargo_manifest(manifest_path)
+        if manifest:
+            manifests[os.path.abspath(manifest_path)] = manifest
+
+    def relative(manifest_path):
+        return os.path.relpath(manifest_path, base_dir)
+
+    def package_version(manifest_path, manifest):
+        version = manifest['package']['version']
+        if version == 'workspace':
+            workspace_path = find_workspace_manifest(manifest_path)
+            workspace = manifests.get(workspace_path) or parse_cargo_manifest(workspace_path) if workspace_path else None
+            version = workspace['workspace']['package'].get('version') if workspace else None
+        return version
+
+    graph = {
+        'root': os.path.basename(os.path.abspath(path)),
+        'workspaces': [],
+        'crates': [],
+        'edges': [],
+        'external': {},
+    }
+
+    for manifest_path, manifest in manifests.items():
+        if manifest['workspace']:
+            members = []
+            for member in cargo_workspace_members(manifest_path, manifest['workspace']):
+                member_manifest = manifests.get(member) or parse_cargo_manifest(member)
+                if member_manifest and member_manifest['package']:
+                    members.append(member_manifest['package']['name'])
+            graph['workspaces'].append({'manifest': relative(manifest_path), 'members': members})
+
+    for manifest_path, manifest in manifests.items():
+        if not manifest['package']:
+            continue
+        name = manifest['package']['name']
+        graph['crates'].append({
+            'name': name,
+            'version': package_version(manifest_path, manifest),
+            'manifest': relative(manifest_path),
+            'lib': manifest['lib']['name'] if os.path.isfile(os.path.join(os.path.dirname(manifest_path), manifest['lib']['path'])) else None,
+        })
+
+        for dependency in resolve_cargo_dependencies(manifest_path, manifest):
+            target_manifest = manifests.get(dependency['manifest'])
+            internal = bool(target_manifest and target_manifest['package'])
+            target = target_manifest['package']['name'] if internal else dependency['package']
+            graph['edges'].append({
+                'source': name,
+                'target': target,
+                'alias': dependency['name'] if dependency['name'] != target else None,
+                'kind': dependency['kind'],
+                'cfg': dependency['target'],
+                'requirement': dependency['requirement'],
+                'internal': internal,
+                'optional': dependency['optional'],
+                'features': dependency['features'],
+            })
+            if not internal:
+                external = graph['external'].setdefault(target, {'requirements': [], 'used_by': []})
+                if dependency['requirement'] not in external['requirements']:
+                    external['requirements'].append(dependency['requirement'])
+                if name not in external['used_by']:
+                    external['used_by'].append(name)
+
+    return graph
+
+def lockfile_package_id(name, version, source=None):
+    """Return the id of a locked package: "name vX.Y.Z", followed by " (source)" unless it is local.
+
+    As in Cargo's own package ids, the source keeps apart packages with the same name and
+    version from different registries or git repositories.
+    """
+    return f"{name} v{version} ({source})" if source else f"{name} v{version}"
+
+def build_lockfile_graph(path):
+    """Build the package graph recorded in a Cargo.lock (path may be the lockfile or its directory).
+
+    Packages are keyed by lockfile_package_id. Returns None when no lockfile can be read,
+    otherwise {'lockfile', 'packages', 'dependencies', 'dependents', 'roots', 'duplicates'}
+    where roots are the local (source-less) packages and duplicates maps crate names locked
+    more than once (at several versions, or one version from several sources) to those
+    packages' ids.
+    """
+    lock_path = os.path.join(path, 'Cargo.lock') if os.path.isdir(path) else path
+    packages = parse_cargo_lock(lock_path)
+    if packages is None:
+        return None
+
+    graph = {
+        'lockfile': lock_path,
+        'packages': {},
+        'dependencies': {},
+        'dependents': {},
+        'roots': [],
+        'duplicates': {},
+    }
+
+    locked = {}
+    for package in packages:
+        package_id = lockfile_package_id(package['name'], package['version'], package['source'])
+        graph['packages'][package_id] = {
+            'name': package['name'],
+            'version': package['version'],
+            'source': package['source'],
+            'local': package['source'] is None,
+        }
+        graph['dependencies'][package_id] = []
+        graph['dependents'][package_id] = []
+        locked.setdefault(package['name'], []).append(package_id)
+        if package['source'] is None:
+            graph['roots'].append(package_id)
+
+    for package in packages:
+        package_id = lockfile_package_id(package['name'], package['version'], package['source'])
+        for name, version, source in package['dependencies']:
+            # The lockfile leaves out the version and source when the name alone is unambiguous
+            candidates = [candidate for candidate in locked.get(name, [])
+                          if version in [None, graph['packages'][candidate]['version']]
+                          and source in [None, graph['packages'][candidate]['source']]]
+            if len(candidates) != 1:
+                continue
+            graph['dependencies'][package_id].append(candidates[0])
+            graph['dependents'][candidates[0]].append(package_id)
+
+    for dependents in graph['dependents'].values():
+        dependents.sort()
+    graph['duplicates'] = {name: found for name, found in locked.items() if len(found) > 1}
+    return graph
+
+def _is_item_start(masked, position):
+    """Check that position starts an item rather than sitting inside an expression or type."""
+    before = masked[:position].rstrip()
+    return not before or before[-1] in ';{}]'
+
+def _line_at(content, position):
+    """Return the 1-based line number of a position."""
+    return content.count('\n', 0, position) + 1
+
+def _matching_angle(masked, open_pos):
+    """Return the position of the '>' closing the '<' at open_pos, ignoring '->' arrows."""
+    depth = 0
+    for i in range(open_pos, len(masked)):
+        c = masked[i]
+        if c == '<':
+            depth += 1
+        elif c == '>' and masked[i - 1] != '-':
+            depth -= 1
+            if depth == 0:
+                return i
+    return len(masked)
+
+def _split_top_level(text, separator=',', values=False):
+    """Split text on a separator that is not nested inside <>, (), [] or {}.
+
+    With values, a top-level `=` starts an expression (an enum discriminant) in which
+    `<` and `>` are operators rather than brackets, up to the next separator.
+    """
+    parts = []
+    depth = 0
+    in_value = False
+    current = ''
+    for i, c in enumerate(text):
+        if values and c == '=' and depth == 0 and text[i + 1:i + 2] not in ['=', '>'] and text[i - 1:i] not in ['=', '!', '<', '>']:
+            in_value = True
+        if c in '([{' or (c == '<' and not in_value):
+            depth += 1
+        elif c in ')]}' or (c == '>' and not in_value and (i == 0 or text[i - 1] != '-')):
+            depth -= 1
+        if c == separator and depth == 0:
+            parts.append(current.strip())
+            current = ''
+            in_value = False
+        else:
+            current += c
+    if current.strip():
+        parts.append(current.strip())
+    return parts
+
+def rust_base_name(type_text):
+    """Reduce a type or trait path like `&'a mut fmt::Display<T>` to its bare name (`Display`)."""
+    text = re.sub(r"^(?:&\s*(?:'\w+\s+)?(?:mut\s+)?|dyn\s+|!)+", '', type_text.strip())
+    angle = text.find('<')
+    if angle != -1:
+        text = text[:angle]
+    return text.split('::')[-1].strip()
+
+def item_attributes(content, masked, position):
+    """Return the outer attributes (`#[...]` contents) written right before an item."""
+    attributes = []
+    end = position
+    while True:
+        before = masked[:end].rstrip()
+        if not before.endswith(']'):
+            break
+        depth = 0
+        start = None
+        for i in range(len(before) - 1, -1, -1):
+            if before[i] == ']':
+                depth += 1
+            elif before[i] == '[':
+                depth -= 1
+                if depth == 0:
+                    start = i
+                    break
+        if start is None or start == 0 or before[start - 1] != '#':
+            break
+        attributes.insert(0, ' '.join(content[start + 1:len(before) - 1].split()))
+        end = start - 1
+    return attributes
+
+def derived_traits(attributes):
+    """Return the trait names listed in `#[derive(...)]` attributes."""
+    traits = []
+    for attribute in attributes:
+        match = re.match(r'derive\s*\((.*)\)$', attribute)
+        if match:
+            traits.extend(rust_base_name(name) for name in _split_top_level(match.group(1)) if name)
+    return traits
+
+def _generic_params(text):
+    """Parse the inside of a `<...>` generic parameter list."""
+    params = []
+    for part in _split_top_level(text):
+        if part.startswith("'"):
+            name, _, bounds = part.partition(':')
+            params.append({'name': name.strip(), 'kind': 'lifetime', 'bounds': bounds.strip()})
+        elif part.startswith('const '):
+            name, _, ty = part[len('const '):].partition(':')
+            params.append({'name': name.strip(), 'kind': 'const', 'bounds': ty.strip()})
+        else:
+            name, _, bounds = part.partition(':')
+            params.append({'name': name.split('=')[0].strip(), 'kind': 'type', 'bounds': bounds.strip()})
+    return params
+
+def _strip_attributes(text):
+    """Remove leading `#[...]` attributes from a field or variant declaration."""
+    text = text.strip()
+    while text.startswith('#['):
+        depth = 0
+        for i, c in enumerate(text):
+            if c == '[':
+                depth += 1
+            elif c == ']':
+                depth -= 1
+                if depth == 0:
+                    text = text[i + 1:].strip()
+                    break
+        else:
+            break
+    return text
+
+def _split_visibility(text):
+    """Split a leading `pub`/`pub(crate)` off a declaration, returning (visibility, rest)."""
+    match = re.match(r'(pub(?:\s*\([^)]*\))?)\s+', text)
+    if match:
+        return ' '.join(match.group(1).split()), text[match.end():]
+    return 'private', text
+
+def _parse_fields(body, tuple_fields):
+    """Parse the fields of a `{ name: Type }` or `(Type, Type)` body."""
+    fields = []
+    for index, part in enumerate(_split_top_level(' '.join(body.split()))):
+        part = _strip_attributes(part)
+        if not part:
+            continue
+        visibility, part = _split_visibility(part)
+        if tuple_fields:
+            fields.append({'name': str(index), 'type': part, 'visibility': visibility})
+        else:
+            name, _, field_type = part.partition(':')
+            fields.append({'name': name.strip(), 'type': field_type.strip(), 'visibility': visibility})
+    return fields
+
+def _parse_variants(body):
+    """Parse enum variants with their payload shape (unit, tuple or struct)."""
+    variants = []
+    for part in _split_top_level(' '.join(body.split()), values=True):
+        part = _strip_attributes(part)
+        match = re.match(r'(\w+)\s*(.*)$', part)
+        if not match:
+            continue
+        payload = match.group(2).strip()
+        if payload.startswith('{'):
+            variants.append({'name': match.group(1), 'shape': 'struct', 'fields': _parse_fields(payload[1:payload.rfind('}')], False)})
+        elif payload.startswith('('):
+            variants.append({'name': match.group(1), 'shape': 'tuple', 'fields': _parse_fields(payload[1:payload.rfind(')')], True)})
+        else:
+            variants.append({'name': match.group(1), 'shape': 'unit', 'fields': []})
+    return variants
+
+def _type_body_start(masked, position):
+    """Return the position of the '{', '(' or ';' that starts a type's body, or None.
+
+    position is just past the generics. A `where` clause before the body is skipped along
+    with the parentheses and angle brackets of its bounds, e.g. `F: Fn(u8) -> u8`.
+    """
+    depth = 0
+    in_where = False
+    for i in range(position, len(masked)):
+        c = masked[i]
+        if depth == 0:
+            if not in_where and re.compile(r'(?<!\w)where\b').match(masked, i):
+                in_where = True
+            if c in '{;' or (c == '(' and not in_where):
+                return i
+        if c in '(<[':
+            depth += 1
+        elif c in ')]' or (c == '>' and masked[i - 1] != '-'):
+            depth -= 1
+    return None
+
+def find_rust_types(content):
+    """Return the structs, enums and unions declared in Rust source.
+
+    Each type has its derives, its 'shape' (named, tuple or unit for structs) and its
+    'fields' with visibility, or for enums its 'variants' with their payload fields.
+    """
+    masked = mask_rust_source(content)
+    types = []
+    for match in re.finditer(r'\b(pub(?:\s*\([^)]*\))?\s+)?(struct|enum|union)\s+(\w+)', masked):
+        if not _is_item_start(masked, match.start()):
+            continue
+        attributes = item_attributes(content, masked, match.start())
+
+        position = match.end()
+        generics = ''
+        rest = masked[position:].lstrip()
+        if rest.startswith('<'):
+            open_angle = masked.index('<', position)
+            close_angle = _matching_angle(masked, open_angle)
+            generics = ' '.join(masked[open_angle + 1:close_angle].split())
+            position = close_angle + 1
+
+        body_start = _type_body_start(masked, position)
+        shape = None if match.group(2) == 'enum' else 'unit'
+        fields = []
+        variants = []
+        if body_start is not None and masked[body_start] == '{':
+            body_end = find_matching_brace(masked, body_start)
+            body = masked[body_start + 1:body_end]
+            if match.group(2) == 'enum':
+                variants = _parse_variants(body)
+            else:
+                shape = 'named'
+                fields = _parse_fields(body, False)
+        elif body_start is not None and masked[body_start] == '(':
+            depth = 0
+            body_end = body_start
+            for i in range(body_start, len(masked)):
+                if masked[i] == '(':
+                    depth += 1
+                elif masked[i] == ')':
+                    depth -= 1
+                    if depth == 0:
+                        body_end = i
+                        break
+            shape = 'tuple'
+            fields = _parse_fields(masked[body_start + 1:body_end], True)
+
+        types.append({
+            'name': match.group(3),
+            'kind': match.group(2),
+            'visibility': ' '.join(match.group(1).split()) if match.group(1) else 'private',
+            'generics': generics,
+            'shape': shape,
+            'fields': fields,
+            'variants': variants,
+            'line': _line_at(content, match.start(2)),
+            'position': match.start(),
+            'attributes': attributes,
+            'derives': derived_traits(attributes),
+        })
+    return types
+
+def rust_has_a_edges(types, project_types):
+    """Return (owner, field, target) for fields whose type mentions another project type.
+
+    types are class entries from the code model (with 'fields' or 'variants'); enum
+    variant fields are named `Variant` or `Variant.field`.
+    """
+    edges = []
+    for rust_type in types:
+        fields = [(field['name'], field['type']) for field in rust_type.get('fields') or []]
+        for variant in rust_type.get('variants') or []:
+            for field in variant['fields']:
+                name = variant['name'] if variant['shape'] == 'tuple' else f"{variant['name']}.{field['name']}"
+                fields.append((name, field['type']))
+        for name, field_type in fields:
+            for reference in dict.fromkeys(re.findall(r'[A-Za-z_]\w*', field_type)):
+                if reference in project_types and (rust_type['name'], name, reference) not in edges:
+                    edges.append((rust_type['name'], name, reference))
+    return edges
+
+def find_rust_traits(content):
+    """Return trait declarations with their supertraits and required vs provided methods."""
+    masked = mask_rust_source(content)
+    traits = []
+    for match in re.finditer(r'\b(pub(?:\s*\([^)]*\))?\s+)?(?:unsafe\s+)?(?:auto\s+)?trait\s+(\w+)', masked):
+        if not _is_item_start(masked, match.start()):
+            continue
+        open_pos = masked.find('{', match.end())
+        semicolon = masked.find(';', match.end())
+        if open_pos == -1 or (semicolon != -1 and semicolon < open_pos):
+            continue  # trait alias or malformed
+        close_pos = find_matching_brace(masked, open_pos)
+
+        header = ' '.join(masked[match.end():open_pos].split())
+        if header.startswith('<'):
+            header = header[_matching_angle(header, 0) + 1:].strip()
+        header = re.split(r'\bwhere\b', header)[0].strip()
+        supertraits = [bound.strip() for bound in _split_top_level(header[1:], '+')] if header.startswith(':') else []
+
+        required = []
+        provided = []
+        flat = top_level_text(masked, open_pos + 1, close_pos)
+        for method in re.finditer(r'\bfn\s+(\w+)', flat):
+            terminator = re.search(r'[;{]', flat[method.end():])
+            if terminator and terminator.group(0) == '{':
+                provided.append(method.group(1))
+            else:
+                required.append(method.group(1))
+
+        traits.append({
+            'name': match.group(2),
+            'visibility': ' '.join(match.group(1).split()) if match.group(1) else 'private',
+            'attributes': item_attributes(content, masked, match.start()),
+            'line': _line_at(content, match.start()),
+            'supertraits': supertraits,
+            'required': required,
+            'provided': provided,
+            'body': (open_pos, close_pos),
+        })
+    return traits
+
+def find_rust_impls(content):
+    """Return every impl block with its generics, trait, self type and body span.
+
+    Trait impls whose self type is one of the impl's own type parameters
+    (`impl<T: Display> Summary for T`) are marked 'blanket'; other impls with type
+    parameters (`impl<T: X> Y for Vec<T>`) are marked 'generic'.
+    """
+    masked = mask_rust_source(content)
+    impls = []
+    for match in re.finditer(r'\b(?:(unsafe)\s+)?impl\b', masked):
+        if not _is_item_start(masked, match.start()):
+            continue
+        position = match.end()
+        while position < len(masked) and masked[position].isspace():
+            position += 1
+
+        params = []
+        if position < len(masked) and masked[position] == '<':
+            close = _matching_angle(masked, position)
+            params = _generic_params(' '.join(masked[position + 1:close].split()))
+            position = close + 1
+
+        open_pos = masked.find('{', position)
+        if open_pos == -1:
+            continue
+        close_pos = find_matching_brace(masked, open_pos)
+
+        header = ' '.join(masked[position:open_pos].split())
+        where = ''
+        where_match = re.search(r'\bwhere\b', header)
+        if where_match:
+            where = header[where_match.end():].strip()
+            header = header[:where_match.start()].strip()
+
+        trait = None
+        self_type = header
+        for for_match in re.finditer(r'\bfor\b', header):
+            before = header[:for_match.start()]
+            depth = before.count('<') - (before.count('>') - before.count('->'))
+            if depth == 0 and not header[for_match.end():].lstrip().startswith('<'):
+                trait = before.strip()
+                self_type = header[for_match.end():].strip()
+                break
+
+        # Where-clause bounds on a type parameter count towards its bounds
+        for clause in _split_top_level(where):
+            name, _, bounds = clause.partition(':')
+            for param in params:
+                if param['name'] == name.strip() and bounds.strip():
+                    param['bounds'] = ' + '.join(filter(None, [param['bounds'], bounds.strip()]))
+
+        type_params = [param['name'] for param in params if param['kind'] == 'type']
+        bare_type = re.sub(r"^(?:&\s*(?:'\w+\s+)?(?:mut\s+)?)+", '', self_type).strip()
+        if trait and bare_type in type_params:
+            shape = 'blanket'
+        elif type_params:
+            shape = 'generic'
+        else:
+            shape = 'concrete'
+
+        impls.append({
+            'trait': trait.lstrip('!').strip() if trait else None,
+            'negative': bool(trait and trait.startswith('!')),
+            'type': self_type,
+            'generics': params,
+            'where': where,
+            'shape': shape,
+            'unsafe': bool(match.group(1)),
+            'attributes': item_attributes(content, masked, match.start()),
+            'line': _line_at(content, match.start()),
+            'position': match.start(),
+            'body': (open_pos, close_pos),
+        })
+    return impls
+
+def find_rust_files(path):
+    """Collect the .rs files under path, skipping build output and hidden directories."""
+    if os.path.isfile(path):
+        return [path] if path.endswith('.rs') else []
+
+    rust_files = []
+    for root, dirs, files in os.walk(path):
+        dirs[:] = sorted(d for d in dirs if not d.startswith('.') and d != 'target')
+        for file in sorted(files):
+            if file.endswith('.rs'):
+                rust_files.append(os.path.join(root, file))
+    return rust_files
+
+def build_trait_matrix(path):
+    """Aggregate trait declarations, derives and impls across every Rust file under path.
+
+    Returns {'root', 'types', 'traits', 'impls'}. 'impls' holds one entry per
+    (type, trait) implementation with its 'shape': derive, concrete, generic or blanket.
+    Traits that are only derived or implemented (e.g. Debug) are included with local False.
+    """
+    base_dir = path if os.path.isdir(path) else os.path.dirname(path)
+    matrix = {
+        'root': os.path.basename(os.path.abspath(path)),
+        'types': [],
+        'traits': {},
+        'impls': [],
+    }
+
+    for file_path in find_rust_files(path):
+        try:
+            with open(file_path, 'r', encoding='utf-8', errors='ignore') as f:
+                content = f.read()
+        except (OSError, IOError):
+            continue
+        rel_path = os.path.relpath(file_path, base_dir)
+
+        for trait in find_rust_traits(content):
+            matrix['traits'].setdefault(trait['name'], {
+                'local': True,
+                'file': rel_path,
+                'line': trait['line'],
+                'supertraits': trait['supertraits'],
+                'required': trait['required'],
+                'provided': trait['provided'],
+            })
+
+        for rust_type in find_rust_types(content):
+            matrix['types'].append({
+                'name': rust_type['name'],
+                'kind': rust_type['kind'],
+                'file': rel_path,
+                'line': rust_type['line'],
+            })
+            for derived in rust_type['derives']:
+                matrix['impls'].append({
+                    'type': rust_type['name'],
+                    'trait': derived,
+                    'shape': 'derive',
+                    'header': f"#[derive({derived})]",
+                    'requires': [],
+                    'file': rel_path,
+                    'line': rust_type['line'],
+                })
+
+        for impl in find_rust_impls(content):
+            if not impl['trait'] or impl['negative']:
+                continue
+            generics = ', '.join(f"{param['name']}: {param['bounds']}" if param['bounds'] else param['name']
+                                 for param in impl['generics'])
+            header = f"impl{'<' + generics + '>' if generics else ''} {impl['trait']} for {impl['type']}"
+            requires = []
+            if impl['shape'] == 'blanket':
+                # The traits a type must implement for the blanket impl to cover it
+                bare_type = re.sub(r"^(?:&\s*(?:'\w+\s+)?(?:mut\s+)?)+", '', impl['type']).strip()
+                for param in impl['generics']:
+                    if param['name'] == bare_type:
+                        requires = [rust_base_name(bound) for bound in _split_top_level(param['bounds'], '+')
+                                    if bound and not bound.startswith('?') and not bound.startswith("'")]
+            matrix['impls'].append({
+                'type': impl['type'],
+                'trait': rust_base_name(impl['trait']),
+                'shape': impl['shape'],
+                'header': header,
+                'requires': requires,
+                'file': rel_path,
+                'line': impl['line'],
+            })
+
+    for impl in matrix['impls']:
+        matrix['traits'].setdefault(impl['trait'], {
+            'local': False,
+            'file': None,
+            'line': None,
+            'supertraits': [],
+            'required': [],
+            'provided': [],
+        })
+
+    return matrix
+
+def _enclosing_brace(masked, position):
+    """Return the position of the innermost '{' whose block contains position, or None."""
+    depth = 0
+    for i in range(position - 1, -1, -1):
+        if masked[i] == '}':
+            depth += 1
+        elif masked[i] == '{':
+            if depth == 0:
+                return i
+            depth -= 1
+    return None
+
+def rust_receiver(params):
+    """Classify a parameter list's receiver as '&self', '&mut self', 'self' or None (associated fn)."""
+    first = _split_top_level(params)[:1]
+    if not first:
+        return None
+    first = ' '.join(first[0].split())
+    typed = re.match(r'(?:mut\s+)?self\s*:\s*(.+)$', first)
+    if typed:
+        # self: &Self, self: &mut Self, self: Box<Self>, ...
+        first_type = typed.group(1)
+        if re.match(r"&\s*(?:'\w+\s+)?mut\b", first_type):
+            return '&mut self'
+        return '&self' if first_type.startswith('&') else 'self'
+    if re.match(r"&\s*(?:'\w+\s+)?mut\s+self$", first):
+        return '&mut self'
+    if re.match(r"&\s*(?:'\w+\s+)?self$", first):
+        return '&self'
+    if re.match(r'(?:mut\s+)?self$', first):
+        return 'self'
+    return None
+
+def find_rust_functions(content):
+    """Return every `fn` in Rust source with the impl or trait block that owns it.
+
+    Braces are matched on masked source, so strings, chars, raw strings, lifetimes and
+    comments cannot throw off which block a function belongs to. Each function records its
+    'owner' (self type for impl methods, trait name for trait declarations), the 'trait' of a
+    trait impl, its 'receiver' ('&self', '&mut self', 'self' or None for associated and free
+    functions), its 'container' ('impl', 'trait' or None) and the 'block_line' of that block.
+    """
+    masked = mask_rust_source(content)
+    blocks = {}
+    for impl in find_rust_impls(content):
+        blocks[impl['body'][0]] = ('impl', rust_base_name(impl['type']), impl['trait'], impl)
+    for trait in find_rust_traits(content):
+        blocks[trait['body'][0]] = ('trait', trait['name'], trait['name'], trait)
+
+    functions = []
+    pattern = (r'\b(pub(?:\s*\([^)]*\))?\s+)?((?:(?:const|async|unsafe|default|extern(?:\s+"[^"]*")?)\s+)*)'
+               r'fn\s+(\w+)')
+    for match in re.finditer(pattern, masked):
+        if not _is_item_start(masked, match.start()):
+            continue
+        position = match.end()
+        rest = masked[position:].lstrip()
+        if rest.startswith('<'):
+            position = _matching_angle(masked, masked.index('<', position)) + 1
+
+        open_paren = masked.find('(', position)
+        if open_paren == -1:
+            continue
+        depth = 0
+        close_paren = len(masked)
+        for i in range(open_paren, len(masked)):
+            if masked[i] == '(':
+                depth += 1
+            elif masked[i] == ')':
+                depth -= 1
+                if depth == 0:
+                    close_paren = i
+                    break
+        params = ' '.join(content[open_paren + 1:close_paren].split())
+
+        terminator = re.compile(r'[{;]').search(masked, close_paren)
+        signature_end = terminator.start() if terminator else len(masked)
+        returns = ' '.join(masked[close_paren + 1:signature_end].split())
+        returns = re.split(r'\bwhere\b', returns)[0].strip()
+        returns = returns[2:].strip() if returns.startswith('->') else None
+        body = None
+        if terminator and terminator.group(0) == '{':
+            body = (terminator.start(), find_matching_brace(masked, terminator.start()))
+
+        container = None
+        owner = None
+        trait = None
+        block_line = None
+        enclosing = _enclosing_brace(masked, match.start())
+        if enclosing in blocks:
+            container, owner, trait, block = blocks[enclosing]
+            block_line = block['line']
+
+        functions.append({
+            'name': match.group(3),
+            'visibility': ' '.join(match.group(1).split()) if match.group(1) else 'private',
+            'qualifiers': ' '.join(content[match.start(2):match.end(2)].split()),
+            'attributes': item_attributes(content, masked, match.start()),
+            'params': params,
+            'returns': returns,
+            'receiver': rust_receiver(params) if container else None,
+            'owner': owner,
+            'trait': trait,
+            'container': container,
+            'block_line': block_line,
+            'line': _line_at(content, match.start()),
+            'position': match.start(),
+            'body': body,
+        })
+    return functions
+
+# Macros from std/core, left out of macro imports so they don't flood the relation diagram
+STD_MACROS = {
+    'assert', 'assert_eq', 'assert_ne', 'cfg', 'column', 'compile_error', 'concat', 'dbg',
+    'debug_assert', 'debug_assert_eq', 'debug_assert_ne', 'env', 'eprint', 'eprintln', 'file',
+    'format', 'format_args', 'include', 'include_bytes', 'include_str', 'line', 'matches',
+    'module_path', 'option_env', 'panic', 'print', 'println', 'stringify', 'thread_local', 'todo',
+    'try', 'unimplemented', 'unreachable', 'vec', 'write', 'writeln', 'macro_rules',
+}
+
+# Attributes built into the compiler, as opposed to attribute macros and derive helpers
+BUILTIN_ATTRIBUTES = {
+    'allow', 'warn', 'deny', 'forbid', 'expect', 'cfg', 'cfg_attr', 'doc', 'inline', 'cold',
+    'must_use', 'deprecated', 'path', 'repr', 'non_exhaustive', 'macro_export', 'macro_use',
+    'test', 'ignore', 'should_panic', 'bench', 'no_mangle', 'export_name', 'link', 'link_name',
+    'link_section', 'used', 'track_caller', 'target_feature', 'automatically_derived', 'derive',
+    'global_allocator', 'proc_macro', 'proc_macro_derive', 'proc_macro_attribute', 'rustfmt',
+    'clippy', 'recursion_limit', 'no_std', 'no_main', 'crate_type', 'crate_name', 'windows_subsystem',
+}
+
+def attribute_macros(attributes):
+    """Return the attributes that are attribute macros or derive helpers (not derive or built-ins)."""
+    macros = []
+    for attribute in attributes:
+        name = re.match(r'[\w:]*', attribute).group(0)
+        if name.split('::')[0] not in BUILTIN_ATTRIBUTES:
+            macros.append(attribute)
+    return macros
+
+def find_rust_macros(content):
+    """Return `macro_rules!` definitions with whether `#[macro_export]` exports them."""
+    masked = mask_rust_source(content)
+    macros = []
+    for match in re.finditer(MACRO_RULES_PATTERN, masked):
+        attributes = item_attributes(content, masked, match.start())
+        macros.append({
+            'name': match.group(1),
+            'exported': any(re.match(r'macro_export\b', attribute) for attribute in attributes),
+            'line': _line_at(content, match.start()),
+            'position': match.start(),
+        })
+    return macros
+
+def find_rust_macro_invocations(content):
+    """Return (macro path, position) for every `name!(...)`, `name![...]` or `name! {...}` call.
+
+    `$crate::name!` inside macro bodies is reported as `crate::name`.
+    """
+    masked = mask_rust_source(content)
+    invocations = []
+    for match in re.finditer(r'(?<![\w:])((?:\$crate::|::)?[A-Za-z_]\w*(?:::[A-Za-z_]\w*)*)!\s*[(\[{]', masked):
+        if match.group(1) != 'macro_rules':
+            invocations.append((re.sub(r'^\$crate::', 'crate::', match.group(1)).lstrip(':'), match.start()))
+    return invocations
+
+def find_rust_symbols(content):
+    """Return the (name, position) pairs a Rust file defines and exports, as two lists.
+
+    Symbols are struct/enum/trait/fn/type items, modules as `mod:name`, `macro_rules!`
+    definitions as `name!` and the traits and types of impl blocks; exports are the `pub`
+    items and modules, every name in a `pub use` and `#[macro_export]` macros. Modules and
+    macros have their own namespaces, so `mod user` and `user!` are different symbols.
+    """
+    masked = mask_rust_source(content)
+    item_pattern = r'(struct|enum|trait|fn|type|mod)\s+(\w+)'
+
+    def item(match):
+        name = f"mod:{match.group(2)}" if match.group(1) == 'mod' else match.group(2)
+        return name, match.start()
+
+    exports = [item(match) for match in re.finditer(r'pub\s+' + item_pattern, masked)]
+    for match in re.finditer(r'pub\s+use\s+([^;]+);', masked):
+        exports.extend((name, match.start()) for name in re.findall(r'\b(\w+)\b', match.group(1)))
+
+    symbols = [item(match) for match in re.finditer(r'(?:pub\s+)?' + item_pattern, masked)]
+    for macro in find_rust_macros(content):
+        symbols.append((f"{macro['name']}!", macro['position']))
+        if macro['exported']:
+            exports.append((f"{macro['name']}!", macro['position']))
+    for impl in find_rust_impls(content):
+        if impl['trait']:
+            symbols.append((impl['trait'], impl['position']))
+        symbols.append((rust_base_name(impl['type']), impl['position']))
+    return symbols, exports
+
+def find_rust_item_attributes(content):
+    """Return {name: {'kind', 'line', 'derives', 'macros'}} for attributed items in Rust source.
+
+    'derives' lists derive macros and 'macros' the other attribute macros and derive helpers,
+    e.g. `tokio::main` or `serde(rename_all = "camelCase")`.
+    """
+    masked = mask_rust_source(content)
+    items = {}
+    pattern = (r'\b(?:pub(?:\s*\([^)]*\))?\s+)?(?:(?:const|async|unsafe|extern(?:\s+"[^"]*")?)\s+)*'
+               r'(struct|enum|union|trait|fn|type|const|static|mod)\s+(\w+)')
+    for match in re.finditer(pattern, masked):
+        if not _is_item_start(masked, match.start()):
+            continue
+        attributes = item_attributes(content, masked, match.start())
+        derives = derived_traits(attributes)
+        macros = attribute_macros(attributes)
+        if derives or macros:
+            items.setdefault(match.group(2), {
+                'kind': match.group(1),
+                'line': _line_at(content, match.start()),
+                'derives': derives,
+                'macros': macros,
+            })
+    return items
+
+def parse_cfg(text):
+    """Parse a cfg predicate such as `all(unix, feature = "serde")` into nested tuples.
+
+    Returns ('all' | 'any', [predicates]), ('not', predicate) or ('option', name, value),
+    where value is None for bare options like `test` or `unix`.
+    """
+    tokens = re.findall(r'"(?:[^"\\]|\\.)*"|[A-Za-z_][\w:]*|[(),=]', text)
+    position = 0
+
+    def parse_predicate():
+        nonlocal position
+        name = tokens[position] if position < len(tokens) else ''
+        position += 1
+        if name in ['all', 'any', 'not'] and position < len(tokens) and tokens[position] == '(':
+            position += 1
+            predicates = []
+            while position < len(tokens) and tokens[position] != ')':
+                predicates.append(parse_predicate())
+                if position < len(tokens) and tokens[position] == ',':
+                    position += 1
+            position += 1
+            if name == 'not':
+                return ('not', predicates[0] if predicates else ('all', []))
+            return (name, predicates)
+        if position < len(tokens) and tokens[position] == '=':
+            value = tokens[position + 1] if position + 1 < len(tokens) else '""'
+            position += 2
+            return ('option', name, value.strip('"'))
+        return ('option', name, None)
+
+    return parse_predicate()
+
+def evaluate_cfg(predicate, features):
+    """Evaluate a parsed cfg predicate against a set of enabled Cargo features.
+
+    Feature options are checked against features and `test`, `doc` and `doctest` are off,
+    as in a normal build. Anything else (target_os, unix, debug_assertions, ...) depends on
+    the build target, so the result is None (unknown) rather than True or False.
+    """
+    kind = predicate[0]
+    if kind == 'option':
+        _, name, value = predicate
+        if name == 'feature':
+            return value in features
+        if name in ['test', 'doc', 'doctest'] and value is None:
+            return False
+        return None
+    if kind == 'not':
+        result = evaluate_cfg(predicate[1], features)
+        return None if result is None else not result
+    results = [evaluate_cfg(child, features) for child in predicate[1]]
+    if kind == 'all':
+        return False if False in results else (None if None in results else True)
+    return True if True in results else (None if None in results else False)
+
+def cfg_enabled(conditions, features):
+    """Return False only when one of the cfg conditions is known to be off for features."""
+    return all(evaluate_cfg(parse_cfg(condition), features) is not False for condition in conditions)
+
+def format_cfg(conditions):
+    """Format a list of cfg conditions as a single `cfg(...)` label."""
+    if len(conditions) == 1:
+        return f"cfg({conditions[0]})"
+    return f"cfg(all({', '.join(conditions)}))"
+
+ITEM_KEYWORD_PATTERN = (r'(?:\s*#\[[^\]]*\])*\s*(?:pub(?:\s*\([^)]*\))?\s+)?(?:(?:const|async|unsafe|default|'
+                        r'extern(?:\s+"[^"]*")?)\s+)*(?:fn|struct|enum|union|impl|mod|use|trait|type|const|'
+                        r'static|macro_rules|extern)\b')
+
+def _cfg_item_end(masked, position):
+    """Return the end of the item, field or statement an outer attribute at position applies to."""
+    is_item = re.match(ITEM_KEYWORD_PATTERN, masked[position:]) is not None
+    depth = 0
+    for i in range(position, len(masked)):
+        char = masked[i]
+        if char in '([':
+            depth += 1
+        elif char in ')]':
+            if depth == 0:
+                return i
+            depth -= 1
+        elif depth == 0:
+            if char == ';' or (char == ',' and not is_item):
+                return i + 1
+            if char == '}':
+                return i
+            if char == '{':
+                return find_matching_brace(masked, i) + 1
+    return len(masked)
+
+def find_cfg_regions(content):
+    """Return the source regions gated by `#[cfg(...)]` and `#![cfg(...)]` attributes.
+
+    Each region is {'start', 'end', 'cfg', 'inner'}. An outer attribute gates the item,
+    field or statement after it; an inner attribute gates its whole enclosing block or file.
+    """
+    masked = mask_rust_source(content)
+    regions = []
+    for match in re.finditer(r'#(!?)\[\s*cfg\s*\(', masked):
+        depth = 0
+        close = len(masked)
+        for i in range(match.end() - 1, len(masked)):
+            if masked[i] in '([':
+                depth += 1
+            elif masked[i] in ')]':
+                depth -= 1
+                if depth == 0:
+                    close = i
+                    break
+        condition = ' '.join(content[match.end():close].split())
+        if match.group(1):
+            enclosing = _enclosing_brace(masked, match.start())
+            start, end = (enclosing, find_matching_brace(masked, enclosing)) if enclosing is not None else (0, len(content))
+        else:
+            start, end = match.start(), _cfg_item_end(masked, masked.index(']', close) + 1)
+        regions.append({'start': start, 'end': end, 'cfg': condition, 'inner': bool(match.group(1))})
+    return regions
+
+def cfg_conditions_at(regions, position):
+    """Return the cfg conditions of every region containing position, outermost first."""
+    return [region['cfg'] for region in sorted(regions, key=lambda region: region['start'])
+            if region['start'] <= position < region['end']]
+
+def rust_cfg_conditions(rust_files):
+    """Collect the cfg conditions gating each Rust file and each of its imports.
+
+    rust_files maps absolute paths to relative paths, as for resolve_rust_imports. Returns
+    {rel_path: {'file': [conditions], 'imports': {import string: [[conditions], ...]},
+    'symbols': {name: [[conditions], ...]}, 'exports': {name: [[conditions], ...]}}}
+    where a file's conditions come from `#[cfg]` on the `mod` declarations leading to it
+    and its own `#![cfg]`, and each import, symbol and export lists the conditions of
+    every place it occurs.
+    """
+    module_cfgs = {}
+    for root in find_crate_roots(list(rust_files.keys())):
+        for module in build_module_tree(root).values():
+            if not module['inline']:
+                module_cfgs.setdefault(module['file'], module['cfg'])
+
+    conditions = {}
+    for file_path, rel_path in rust_files.items():
+        try:
+            with open(file_path, 'r', encoding='utf-8', errors='ignore') as f:
+                content = f.read()
+        except (OSError, IOError):
+            continue
+        regions = find_cfg_regions(content)
+        file_conditions = module_cfgs.get(file_path, []) + [
+            region['cfg'] for region in regions if region['inner'] and region['start'] == 0]
+
+        imports = {}
+        occurrences = ([(tree, position) for tree, position in find_rust_uses(content)] +
+                       [(f"mod:{name}", position) for name, position in find_rust_mod_declarations(content)] +
+                       [(f"macro:{path}", position) for path, position in find_rust_macro_invocations(content)])
+        for imported, position in occurrences:
+            gated = [condition for condition in cfg_conditions_at(regions, position) if condition not in file_conditions]
+            imports.setdefault(imported, []).append(gated)
+
+        symbols, exports = {}, {}
+        for occurrences, names in zip([symbols, exports], find_rust_symbols(content)):
+            for name, position in names:
+                gated = [condition for condition in cfg_conditions_at(regions, position) if condition not in file_conditions]
+                occurrences.setdefault(name, []).append(gated)
+        conditions[rel_path] = {'file': file_conditions, 'imports': imports, 'symbols': symbols, 'exports': exports}
+    return conditions
+
+def import_cfg_conditions(file_conditions, occurrences):
+    """Combine a file's conditions with an import's occurrences into one list of conditions.
+
+    An import that occurs once without a cfg of its own is only gated by its file; one that
+    only occurs under cfg attributes is gated by `any(...)` of them.
+    """
+    if not occurrences or any(not occurrence for occurrence in occurrences):
+        return list(file_conditions)
+    alternatives = list(dict.fromkeys(
+        occurrence[0] if len(occurrence) == 1 else f"all({', '.join(occurrence)})" for occurrence in occurrences))
+    if len(alternatives) == 1:
+        return file_conditions + alternatives
+    return file_conditions + [f"any({', '.join(alternatives)})"]
+
+def cargo_enabled_features(manifest, requested=None, no_default_features=False):
+    """Return the features of a parsed manifest enabled by a Cargo feature selection.
+
+    requested holds names from `--features`; `package/feature` entries only apply to that
+    package. Features are expanded through the [features] table, and optional dependencies
+    enabled along the way turn on their implicit feature of the same name.
+    """
+    package_name = manifest['package']['name'] if manifest and manifest['package'] else None
+    table = manifest['features'] if manifest else {}
+    optional = {dependency['name'] for dependency in (manifest['dependencies'] if manifest else [])
+                if dependency['optional']}
+    explicit_deps = {entry[len('dep:'):] for entries in table.values() for entry in entries if entry.startswith('dep:')}
+
+    pending = []
+    for name in requested or []:
+        if '/' in name:
+            package, name = name.split('/', 1)
+            if package != package_name:
+                continue
+        pending.append(name)
+    if not no_default_features and 'default' in table:
+        pending.append('default')
+
+    enabled = set()
+    while pending:
+        name = pending.pop()
+        if name in enabled:
+            continue
+        enabled.add(name)
+        for entry in table.get(name, []):
+            if entry.startswith('dep:'):
+                continue
+            if '/' in entry:
+                dependency = entry.split('/', 1)[0]
+                if not dependency.endswith('?') and dependency in optional and dependency not in explicit_deps:
+                    pending.append(dependency)
+                continue
+            pending.append(entry)
+    return enabled
+
+def cargo_dependency_conditions(manifest):
+    """Map a manifest's dependency import strings to the cfg conditions that enable them.
+
+    Optional dependencies are gated by the features that pull them in (`dep:x`, `x/feat`
+    or the implicit feature `x`); target-specific ones by their `cfg(...)` target.
+    """
+    table = manifest['features']
+    explicit_deps = {entry[len('dep:'):] for entries in table.values() for entry in entries if entry.startswith('dep:')}
+    conditions = {}
+    for dependency in manifest['dependencies']:
+        gated = []
+        if dependency['target'] and dependency['target'].startswith('cfg(') and dependency['target'].endswith(')'):
+            gated.append(dependency['target'][len('cfg('):-1])
+        if dependency['optional']:
+            name = dependency['name']
+            enabling = [feature for feature, entries in table.items()
+                        if f"dep:{name}" in entries or name in entries
+                        or any(entry.startswith(f"{name}/") for entry in entries)]
+            if name not in explicit_deps:
+                enabling.append(name)
+            options = [f'feature = "{feature}"' for feature in dict.fromkeys(enabling)]
+            gated.append(options[0] if len(options) == 1 else f"any({', '.join(options)})")
+        if gated:
+            conditions[cargo_dependency_import(dependency)] = gated
+    return conditions
+
+def rust_item_signature(content, masked, start, kind):
+    """Return an item's declaration without its body, e.g. `pub fn new(id: u32) -> Self`.
+
+    Constants and statics stop before their value; type aliases keep the aliased type.
+    """
+    depth = 0
+    end = len(masked)
+    for i in range(start, len(masked)):
+        char = masked[i]
+        if char in '([':
+            depth += 1
+        elif char in ')]':
+            depth -= 1
+        elif depth == 0 and (char in '{;' or (char == '=' and kind in ['const', 'static'])):
+            end = i
+            break
+    return ' '.join(content[start:end].split())
+
+def rust_function_signature(func):
+    """Format a function from find_rust_functions as a signature like `pub fn name(params) -> T`."""
+    parts = [] if func['visibility'] == 'private' else [func['visibility']]
+    if func['qualifiers']:
+        parts.append(func['qualifiers'])
+    parts.append(f"fn {func['name']}({func['params']})")
+    if func['returns']:
+        parts.append(f"-> {func['returns']}")
+    return ' '.join(parts)
+
+def find_library_roots(path):
+    """Return (crate name, lib.rs path) for every library crate under path.
+
+    Cargo packages use their [lib] name and path. Without a Cargo.toml, a lib.rs (or
+    src/lib.rs) under path, or path itself when it is a .rs file, is the crate root.
+    """
+    roots = []
+    for manifest_path in find_cargo_manifests(path):
+        manifest = parse_cargo_manifest(manifest_path)
+        if manifest and manifest['package']:
+            lib_file = os.path.normpath(os.path.join(os.path.dirname(manifest_path), manifest['lib']['path']))
+            if os.path.isfile(lib_file):
+                roots.append((manifest['lib']['name'], lib_file))
+    if roots:
+        return roots
+    if os.path.isfile(path):
+        return [(os.path.splitext(os.path.basename(path))[0], path)] if path.endswith('.rs') else []
+    for candidate in [os.path.join(path, 'src', 'lib.rs'), os.path.join(path, 'lib.rs')]:
+        if os.path.isfile(candidate):
+            return [(os.path.basename(os.path.abspath(path)).replace('-', '_'), candidate)]
+    return []
+
+def build_api_surface(path):
+    """List the items of each library crate under path that are public from outside the crate.
+
+    The module tree is walked from lib.rs through `pub mod` declarations and `pub use`
+    re-exports (including globs and renames). Items that are private, `pub(crate)`,
+    `pub(super)` or `pub(in ...)`, or only reachable through private modules, are left out.
+
+    Returns {'root', 'crates': [{'name', 'lib', 'modules', 'items'}]}. Each item has its
+    public 'path', 'kind', 'signature', 'file', 'line', 'cfg' conditions, the path it
+    'reexports' (None for items declared in place), and its public 'fields', enum
+    'variants', 'methods' and trait 'impls'. When one item is public under several paths,
+    the shortest one is canonical and the others have 'alias_of' set to it. Derived traits
+    count as 'impls'.
+    """
+    base_dir = path if os.path.isdir(path) else os.path.dirname(path)
+    surface = {'root': os.path.basename(os.path.abspath(path)), 'crates': []}
+    sources = {}
+
+    def source(file_path):
+        if file_path not in sources:
+            try:
+                with open(file_path, 'r', encoding='utf-8', errors='ignore') as f:
+                    content = f.read()
+            except (OSError, IOError):
+                content = ''
+            sources[file_path] = (content, mask_rust_source(content), find_cfg_regions(content))
+        return sources[file_path]
+
+    for crate_name, lib_file in find_library_roots(path):
+        modules = build_module_tree(lib_file)
+        externs = _cargo_extern_trees(lib_file, {lib_file: modules})
+        crate_files = sorted({module['file'] for module in modules.values()})
+        public_modules = []
+        entries = {}
+
+        def add_item(public_path, tree, module_path, name, reexported):
+            module = tree[module_path]
+            item = module['items'][name]
+            content, masked, regions = source(module['file'])
+            key = '::'.join(public_path)
+            defined_at = '::'.join((crate_name,) + module_path[1:] + (name,)) if tree is modules else None
+            entries.setdefault(key, {
+                'path': key,
+                'kind': item['kind'],
+                'name': name,
+                'signature': rust_item_signature(content, masked, item['start'], item['kind']),
+                'file': os.path.relpath(module['file'], base_dir),
+                'line': _line_at(content, item['start']),
+                'cfg': module['cfg'] + [condition for condition in cfg_conditions_at(regions, item['start'])
+                                        if condition not in module['cfg']],
+                'reexports': defined_at if reexported and defined_at != key else None,
+                'identity': (module['file'], item['start']),
+            })
+
+        def walk(tree, module_path, public_path, visiting):
+            if (id(tree), module_path) in visiting or len(visiting) > 16:
+                return
+            visiting = visiting | {(id(tree), module_path)}
+            module = tree[module_path]
+            reexported = tree is not modules or public_path[1:] != list(module_path[1:])
+            for name, item in module['items'].items():
+                if item['visibility'] != 'pub':
+                    continue
+                if item['kind'] == 'mod':
+                    if module_path + (name,) in tree:
+                        public_modules.append('::'.join(public_path + [name]))
+                        walk(tree, module_path + (name,), public_path + [name], visiting)
+                    continue
+                add_item(public_path + [name], tree, module_path, name, reexported)
+
+            for use in module['uses']:
+                if use['visibility'] != 'pub':
+                    continue
+                for segments, alias in expand_use_tree(use['tree']):
+                    if not segments:
+                        continue
+                    resolution = resolve_use_path(segments, module_path, tree, externs)
+                    exported_name = alias or (segments[-1] if segments[-1] != 'self' else segments[-2] if len(segments) > 1 else None)
+                    if resolution is None:
+                        # Re-export of an external crate's item
+                        if exported_name and segments[-1] != '*':
+                            key = '::'.join(public_path + [exported_name])
+                            entries.setdefault(key, {
+                                'path': key, 'kind': 'external', 'name': exported_name,
+                                'signature': f"pub use {'::'.join(segments)}" + (f" as {alias}" if alias else ""),
+                                'file': os.path.relpath(module['file'], base_dir),
+                                'line': _line_at(source(module['file'])[0], use['position']),
+                                'cfg': list(module['cfg']), 'reexports': '::'.join(segments),
+                                'identity': ('external', '::'.join(segments)),
+                            })
+                        continue
+                    target_tree, target_module, item_name = resolution
+                    if segments[-1] == '*':
+                        walk(target_tree, target_module, public_path, visiting)
+                    elif item_name is None:
+                        if exported_name:
+                            public_modules.append('::'.join(public_path + [exported_name]))
+                            walk(target_tree, target_module, public_path + [exported_name], visiting)
+                    else:
+                        def_tree, def_module = find_item_definition(target_tree, target_module, item_name, externs)
+                        item = def_tree[def_module]['items'].get(item_name)
+                        if item is None or item['visibility'] != 'pub':
+                            continue
+                        if item['kind'] == 'mod' and def_module + (item_name,) in def_tree:
+                            public_modules.append('::'.join(public_path + [exported_name]))
+                            walk(def_tree, def_module + (item_name,), public_path + [exported_name], visiting)
+                        else:
+                            add_item(public_path + [exported_name], def_tree, def_module, item_name, True)
+
+        walk(modules, ('crate',), [crate_name], frozenset())
+
+        # #[macro_export] macros live at the crate root whatever module defines them
+        for file_path in crate_files:
+            content, _, regions = source(file_path)
+            for macro in find_rust_macros(content):
+                if macro['exported']:
+                    key = f"{crate_name}::{macro['name']}!"
+                    entries.setdefault(key, {
+                        'path': key, 'kind': 'macro', 'name': macro['name'],
+                        'signature': f"macro_rules! {macro['name']}",
+                        'file': os.path.relpath(file_path, base_dir), 'line': macro['line'],
+                        'cfg': cfg_conditions_at(regions, macro['position']), 'reexports': None,
+                        'identity': (file_path, macro['position']),
+                    })
+
+        # Public members of public types, gathered from every impl block in the crate
+        types, functions, impls = {}, [], []
+        for file_path in crate_files:
+            content = source(file_path)[0]
+            for rust_type in find_rust_types(content):
+                types[(file_path, rust_type['name'])] = rust_type
+            functions.extend(find_rust_functions(content))
+            impls.extend(find_rust_impls(content))
+
+        canonical = {}
+        for entry in sorted(entries.values(), key=lambda entry: (entry['path'].count('::'), entry['path'])):
+            canonical.setdefault(entry['identity'], entry['path'])
+
+        items = []
+        for key in sorted(entries):
+            entry = dict(entries[key])
+            identity = entry.pop('identity')
+            entry['alias_of'] = canonical[identity] if canonical[identity] != key else None
+            entry['fields'], entry['variants'], entry['methods'], entry['impls'] = [], [], [], []
+            if entry['alias_of'] is None and entry['kind'] in ['struct', 'enum', 'union']:
+                rust_type = types.get((identity[0], entry['name']))
+                if rust_type:
+                    entry['fields'] = [field for field in rust_type['fields'] if field['visibility'] == 'pub']
+                    entry['variants'] = rust_type['variants']
+                entry['methods'] = [rust_function_signature(func) for func in functions
+                                    if func['container'] == 'impl' and func['trait'] is None
+                                    and func['owner'] == entry['name'] and func['visibility'] == 'pub']
+                derives = rust_type['derives'] if rust_type else []
+                entry['impls'] = list(dict.fromkeys(derives + [impl['trait'] for impl in impls
+                                                               if impl['trait'] and not impl['negative']
+                                                               and rust_base_name(impl['type']) == entry['name']]))
+            elif entry['alias_of'] is None and entry['kind'] == 'trait':
+                entry['methods'] = [rust_function_signature(func) for func in functions
+                                    if func['container'] == 'trait' and func['owner'] == entry['name']]
+            items.append(entry)
+
+        surface['crates'].append({
+            'name': crate_name,
+            'lib': os.path.relpath(lib_file, base_dir),
+            'modules': sorted(set(public_modules)),
+            'items': items,
+        })
+
+    return surface
+
+# Words followed by '(' that are not function calls
+RUST_CALL_KEYWORDS = {
+    'if', 'while', 'for', 'match', 'return', 'loop', 'fn', 'let', 'in', 'as', 'move', 'unsafe',
+    'async', 'await', 'impl', 'where', 'self', 'super', 'crate', 'mut', 'ref', 'dyn', 'box', 'break',
+}
+
+def _innermost_function(functions, position):
+    """Return the function from find_rust_functions whose body most tightly contains position."""
+    enclosing = [func for func in functions if func['body'] and func['body'][0] < position < func['body'][1]]
+    return max(enclosing, key=lambda func: func['body'][0]) if enclosing else None
+
+def rust_function_key(func):
+    """Name a function from find_rust_functions by its owner, `Raw::new`, or bare if free."""
+    return f"{func['owner']}::{func['name']}" if func['owner'] else func['name']
+
+def find_rust_call_sites(content, functions=None):
+    """Return the calls inside Rust function bodies with how each callee is named.
+
+    Each call is {'caller', 'name', 'path', 'receiver', 'position'}. caller is the enclosing
+    function from find_rust_functions. path is the qualifier of a path call as written
+    (`Self`, `String`, `crate::util`), None otherwise. receiver is the expression a method is
+    called on when it is a plain name (`self`, `user`), '' for other expressions, and None
+    for calls that are not method calls.
+    """
+    masked = mask_rust_source(content)
+    functions = functions if functions is not None else find_rust_functions(content)
+    calls = []
+    pattern = r'(?<![\w!:])((?:[A-Za-z_]\w*\s*(?:::\s*<[^(){};]*>\s*)?::\s*)*)([a-z_]\w*)\s*(?:::\s*<[^(){};]*>\s*)?\('
+    for match in re.finditer(pattern, masked):
+        name = match.group(2)
+        if name in RUST_CALL_KEYWORDS or re.search(r'\bfn\s*$', masked[max(0, match.start() - 8):match.start()]):
+            continue
+        caller = _innermost_function(functions, match.start())
+        if not caller:
+            continue
+        path = re.sub(r'\s*::\s*<[^(){};]*>', '', ''.join(match.group(1).split()))[:-2] or None
+        receiver = None
+        if path is None and masked[:match.start()].rstrip().endswith('.'):
+            receiver_match = re.search(r'(?<![\w.)\]])([a-z_]\w*)\s*\.\s*$', masked[:match.start()])
+            receiver = receiver_match.group(1) if receiver_match else ''
+        calls.append({'caller': caller, 'name': name, 'path': path, 'receiver': receiver, 'position': match.start()})
+    return calls
+
+def resolve_rust_calls(rust_sources):
+    """Resolve the calls between the functions of some Rust files.
+
+    rust_sources maps paths to file contents. Returns (functions, calls): functions maps each
+    path to its find_rust_functions result, each function given a 'key' naming it
+    `Owner::name`, or by its bare name when free, prefixed with the file's module path when
+    the name is defined in several files. Calls are {'caller', 'callee', 'caller_file',
+    'callee_file'} with function dicts, recorded only when the callee is one of the functions:
+    - `Self::f()` and `self.f()` call a method of the caller's own type;
+    - `Type::f()` a method of a type, or of a trait the type implements;
+    - `x.f()` a method of x's type, when a parameter or `let` binding gives that type;
+    - `f()` and `module::f()` a free function, in the caller's file first.
+    A type parameter resolves through its bounds. Calls such as `String::new()`, or methods on
+    values of unknown type, are left out rather than matched by name.
+    """
+    functions = {path: find_rust_functions(content) for path, content in rust_sources.items()}
+    defined = {}
+    for path, file_functions in functions.items():
+        for func in file_functions:
+            defined.setdefault(rust_function_key(func), set()).add(path)
+    methods = {}  # (owner, name) -> [(func, path)]
+    free_functions = {}  # name -> [(func, path)]
+    for path, file_functions in functions.items():
+        for func in file_functions:
+            key = rust_function_key(func)
+            if len(defined[key]) > 1:
+                module = [part for part in os.path.splitext(path)[0].replace('\\', '/').split('/')
+                          if part not in ['src', 'lib', 'main', 'mod']]
+                key = '::'.join(module + [key])
+            func['key'] = key
+            if func['owner']:
+                methods.setdefault((func['owner'], func['name']), []).append((func, path))
+            else:
+                free_functions.setdefault(func['name'], []).append((func, path))
+
+    impls = {path: find_rust_impls(content) for path, content in rust_sources.items()}
+    traits_of = {}
+    for file_impls in impls.values():
+        for impl in file_impls:
+            if impl['trait'] and impl['shape'] != 'blanket':
+                traits_of.setdefault(rust_base_name(impl['type']), set()).add(rust_base_name(impl['trait']))
+
+    def find_method(owner, name, caller, path):
+        # The type's own methods, else those of the traits it implements or is bounded by
+        if (owner, name) in methods:
+            return methods[(owner, name)]
+        traits = set(traits_of.get(owner, ()))
+        for impl in impls[path]:
+            if caller['container'] == 'impl' and impl['line'] == caller['block_line']:
+                for param in impl['generics']:
+                    if param['name'] == owner and param['bounds']:
+                        traits.update(rust_base_name(bound) for bound in param['bounds'].split('+') if bound.strip())
+        return [entry for trait in sorted(traits) for entry in methods.get((trait, name), [])]
+
+    def local_types(func, content):
+        # Types of the parameters, and of `let` bindings that are annotated, built by `Type::..`
+        # or returned by a free function
+        types = {}
+        for param in _split_top_level(func['params']):
+            if ':' in param:
+                name, type_text = param.split(':', 1)
+                types[name.replace('mut ', '').strip()] = rust_base_name(type_text)
+        if func['body']:
+            body = content[func['body'][0]:func['body'][1]]
+            pattern = r'\blet\s+(?:mut\s+)?(\w+)\s*(?::\s*([^=;]+?)\s*)?=\s*(?:([A-Z]\w*)|([a-z_]\w*)\s*\()?'
+            for match in re.finditer(pattern, body):
+                returns = [callee['returns'] for callee, _ in free_functions.get(match.group(4), []) if callee['returns']]
+                type_text = match.group(2) or match.group(3) or (returns[0] if len(returns) == 1 else None)
+                if type_text:
+                    types[match.group(1)] = rust_base_name(type_text)
+        return types
+
+    calls = []
+    for path, content in rust_sources.items():
+        for call in find_rust_call_sites(content, functions[path]):
+            caller = call['caller']
+            qualifier = call['path'].split('::')[-1] if call['path'] else None
+            if call['receiver'] is not None:
+                owner = caller['owner'] if call['receiver'] == 'self' else local_types(caller, content).get(call['receiver'])
+                callees = find_method(owner, call['name'], caller, path) if owner else []
+            elif qualifier == 'Self':
+                callees = find_method(caller['owner'], call['name'], caller, path)
+            elif qualifier and qualifier[0].isupper():
+                callees = find_method(qualifier, call['name'], caller, path)
+            else:
+                candidates = free_functions.get(call['name'], [])
+                callees = [entry for entry in candidates if entry[1] == path] or (candidates if len(candidates) == 1 else [])
+            for callee, callee_path in callees:
+                if callee is not caller:
+                    calls.append({'caller': caller, 'callee': callee, 'caller_file': path, 'callee_file': callee_path})
+    return functions, calls
+
+def find_rust_unsafe(content):
+    """Return the unsafe and FFI sites in Rust source.
+
+    Each site is {'kind', 'name', 'function', 'abi', 'items', 'line', 'position'} where kind is
+    'unsafe block' (with its enclosing 'function'), 'unsafe fn', 'unsafe impl', 'unsafe trait',
+    'extern block' (with the foreign 'items' it declares), 'extern fn' (a Rust function with a
+    foreign ABI) or 'no_mangle' (an item exported under its own symbol name).
+    """
+    masked = mask_rust_source(content)
+    functions = find_rust_functions(content)
+    sites = []
+
+    def site(kind, position, name=None, function=None, abi=None, items=None):
+        sites.append({'kind': kind, 'name': name, 'function': function, 'abi': abi, 'items': items or [],
+                      'line': _line_at(content, position), 'position': position})
+
+    for match in re.finditer(r'\bunsafe\s*\{', masked):
+        enclosing = _innermost_function(functions, match.start())
+        site('unsafe block', match.start(), function=enclosing['name'] if enclosing else None)
+
+    extern_blocks = []
+    for match in re.finditer(r'\b(?:unsafe\s+)?extern\s*("[^"]*")?\s*\{', masked):
+        close = find_matching_brace(masked, match.end() - 1)
+        extern_blocks.append((match.end() - 1, close))
+        abi = content[match.start(1):match.end(1)] if match.group(1) else '"C"'
+        items = [func['name'] for func in functions if match.end() <= func['position'] < close]
+        items += re.findall(r'\bstatic\s+(?:mut\s+)?(\w+)', masked[match.end():close])
+        site('extern block', match.start(), abi=abi, items=items)
+
+    for func in functions:
+        qualifiers = func['qualifiers'].split()
+        name = f"{func['owner']}::{func['name']}" if func['owner'] else func['name']
+        if any(start < func['position'] < end for start, end in extern_blocks):
+            continue
+        if 'unsafe' in qualifiers:
+            site('unsafe fn', func['position'], name=name)
+        if 'extern' in qualifiers and func['body']:
+            abi = re.search(r'extern\s*("[^"]*")?', func['qualifiers']).group(1) or '"C"'
+            site('extern fn', func['position'], name=name, abi=abi)
+        if any(re.match(r'(?:unsafe\s*\(\s*)?(?:no_mangle|export_name)\b', attribute) for attribute in func['attributes']):
+            site('no_mangle', func['position'], name=name)
+
+    for match in re.finditer(r'\bstatic\s+(?:mut\s+)?(\w+)', masked):
+        attributes = item_attributes(content, masked, match.start())
+        if any(re.match(r'(?:unsafe\s*\(\s*)?(?:no_mangle|export_name)\b', attribute) for attribute in attributes):
+            site('no_mangle', match.start(), name=match.group(1))
+
+    for impl in find_rust_impls(content):
+        if impl['unsafe']:
+            site('unsafe impl', impl['position'],
+                 name=f"{impl['trait']} for {impl['type']}" if impl['trait'] else impl['type'])
+
+    for match in re.finditer(r'\bunsafe\s+(?:auto\s+)?trait\s+(\w+)', masked):
+        site('unsafe trait', match.start(), name=match.group(1))
+
+    return sorted(sites, key=lambda found: found['position'])
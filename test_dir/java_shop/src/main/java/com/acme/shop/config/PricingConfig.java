@@ -0,0 +1,14 @@
+package com.acme.shop.config;
+
+import com.acme.shop.service.TaxSettings;
+import java.math.BigDecimal;
+import org.springframework.context.annotation.Bean;
+import org.springframework.context.annotation.Configuration;
+
+@Configuration
+public class PricingConfig {
+    @Bean
+    public TaxSettings taxSettings() {
+        return new TaxSettings(new BigDecimal("0.20"));
+    }
+}
@@ -0,0 +1,4 @@
+package com.acme.shop.model;
+
+public record Customer(long id, String name, String email) {
+}
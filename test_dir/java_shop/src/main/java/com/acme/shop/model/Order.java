@@ -0,0 +1,51 @@
+package com.acme.shop.model;
+
+import java.math.BigDecimal;
+import java.util.ArrayList;
+import java.util.List;
+
+public class Order {
+    private final long id;
+    private final Customer customer;
+    private final List<Line> lines = new ArrayList<>();
+    private OrderStatus status = OrderStatus.NEW;
+
+    public Order(long id, Customer customer) {
+        this.id = id;
+        this.customer = customer;
+    }
+
+    public long getId() {
+        return id;
+    }
+
+    public Customer getCustomer() {
+        return customer;
+    }
+
+    public List<Line> getLines() {
+        return lines;
+    }
+
+    public OrderStatus getStatus() {
+        return status;
+    }
+
+    public void setStatus(OrderStatus status) {
+        this.status = status;
+    }
+
+    public static class Line {
+        private final String sku;
+        private final BigDecimal price;
+
+        public Line(String sku, BigDecimal price) {
+            this.sku = sku;
+            this.price = price;
+        }
+
+        public BigDecimal getPrice() {
+            return price;
+        }
+    }
+}
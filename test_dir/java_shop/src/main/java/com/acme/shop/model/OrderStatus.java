@@ -0,0 +1,7 @@
+package com.acme.shop.model;
+
+public enum OrderStatus {
+    NEW,
+    PAID,
+    SHIPPED
+}
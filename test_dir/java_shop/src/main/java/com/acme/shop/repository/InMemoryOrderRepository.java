@@ -0,0 +1,23 @@
+package com.acme.shop.repository;
+
+import com.acme.shop.model.Order;
+import java.util.Map;
+import java.util.Optional;
+import java.util.concurrent.ConcurrentHashMap;
+import org.springframework.stereotype.Repository;
+
+@Repository
+public class InMemoryOrderRepository implements OrderRepository {
+    private final Map<Long, Order> orders = new ConcurrentHashMap<>();
+
+    @Override
+    public Optional<Order> findById(long id) {
+        return Optional.ofNullable(orders.get(id));
+    }
+
+    @Override
+    public Order save(Order order) {
+        orders.put(order.getId(), order);
+        return order;
+    }
+}
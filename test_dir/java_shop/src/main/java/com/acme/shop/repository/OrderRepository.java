@@ -0,0 +1,10 @@
+package com.acme.shop.repository;
+
+import com.acme.shop.model.Order;
+import java.util.Optional;
+
+public interface OrderRepository {
+    Optional<Order> findById(long id);
+
+    Order save(Order order);
+}
@@ -0,0 +1,27 @@
+package com.acme.shop.service;
+
+import java.util.ArrayDeque;
+import java.util.Deque;
+import org.springframework.beans.factory.annotation.Value;
+import org.springframework.stereotype.Component;
+
+@Component
+public class AuditLog {
+    private final Deque<String> entries = new ArrayDeque<>();
+    private final int capacity;
+
+    public AuditLog(@Value("${shop.audit.capacity:100}") int capacity) {
+        this.capacity = capacity;
+    }
+
+    public void record(String entry) {
+        if (entries.size() == capacity) {
+            entries.removeFirst();
+        }
+        entries.addLast(entry);
+    }
+
+    public Deque<String> entries() {
+        return entries;
+    }
+}
@@ -0,0 +1,35 @@
+package com.acme.shop.service;
+
+import static com.acme.shop.util.Money.format;
+
+import com.acme.shop.model.*;
+import com.acme.shop.repository.OrderRepository;
+import org.springframework.beans.factory.annotation.Autowired;
+import org.springframework.stereotype.Service;
+
+@Service
+public class OrderService {
+    private final OrderRepository repository;
+    private final PricingService pricing;
+
+    @Autowired
+    public OrderService(OrderRepository repository, PricingService pricing) {
+        this.repository = repository;
+        this.pricing = pricing;
+    }
+
+    public Order place(Customer customer) {
+        Order order = new Order(System.nanoTime(), customer);
+        return repository.save(order);
+    }
+
+    public Order pay(long id) {
+        Order order = repository.findById(id).orElseThrow();
+        order.setStatus(OrderStatus.PAID);
+        return repository.save(order);
+    }
+
+    public String describe(Order order) {
+        return order.getCustomer().name() + ": " + format(pricing.total(order));
+    }
+}
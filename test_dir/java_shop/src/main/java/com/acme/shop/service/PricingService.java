@@ -0,0 +1,21 @@
+package com.acme.shop.service;
+
+import com.acme.shop.model.Order;
+import java.math.BigDecimal;
+import org.springframework.stereotype.Service;
+
+@Service
+public class PricingService {
+    private final BigDecimal taxRate;
+
+    public PricingService(TaxSettings settings) {
+        this.taxRate = settings.rate();
+    }
+
+    public BigDecimal total(Order order) {
+        BigDecimal net = order.getLines().stream()
+            .map(Order.Line::getPrice)
+            .reduce(BigDecimal.ZERO, BigDecimal::add);
+        return net.add(net.multiply(taxRate));
+    }
+}
@@ -0,0 +1,6 @@
+package com.acme.shop.service;
+
+import java.math.BigDecimal;
+
+public record TaxSettings(BigDecimal rate) {
+}
@@ -0,0 +1,13 @@
+package com.acme.shop.util;
+
+import java.math.BigDecimal;
+import java.math.RoundingMode;
+
+public final class Money {
+    private Money() {
+    }
+
+    public static String format(BigDecimal amount) {
+        return "$" + amount.setScale(2, RoundingMode.HALF_UP);
+    }
+}
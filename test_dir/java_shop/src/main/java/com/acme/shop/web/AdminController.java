@@ -0,0 +1,22 @@
+package com.acme.shop.web;
+
+import com.acme.shop.service.AuditLog;
+import java.util.Deque;
+import org.springframework.beans.factory.annotation.Autowired;
+import org.springframework.stereotype.Controller;
+import org.springframework.web.bind.annotation.RequestMapping;
+import org.springframework.web.bind.annotation.RequestMethod;
+import org.springframework.web.bind.annotation.ResponseBody;
+
+@Controller
+@RequestMapping("/admin")
+public class AdminController {
+    @Autowired
+    private AuditLog audit;
+
+    @RequestMapping(value = "/audit", method = {RequestMethod.GET, RequestMethod.HEAD})
+    @ResponseBody
+    public Deque<String> audit() {
+        return audit.entries();
+    }
+}
@@ -0,0 +1,31 @@
+package com.acme.shop.web;
+
+import com.acme.shop.model.Customer;
+import com.acme.shop.model.Order;
+import com.acme.shop.service.OrderService;
+import org.springframework.web.bind.annotation.*;
+
+@RestController
+@RequestMapping("/api/orders")
+public class OrderController {
+    private final OrderService orders;
+
+    public OrderController(OrderService orders) {
+        this.orders = orders;
+    }
+
+    @PostMapping
+    public Order create(@RequestBody Customer customer) {
+        return orders.place(customer);
+    }
+
+    @PostMapping("/{id}/payment")
+    public Order pay(@PathVariable long id) {
+        return orders.pay(id);
+    }
+
+    @GetMapping(value = "/{id}/summary", produces = "text/plain")
+    public String summary(@PathVariable long id) {
+        return orders.describe(orders.pay(id));
+    }
+}
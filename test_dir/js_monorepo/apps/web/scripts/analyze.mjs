@@ -0,0 +1,3 @@
+export default function analyze(stats) {
+  return stats.modules.length;
+}
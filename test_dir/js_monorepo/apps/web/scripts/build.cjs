@@ -0,0 +1,13 @@
+const { outDir } = require('./paths.cjs');
+
+function build() {
+  const reactPath = require.resolve('react');
+  console.log(`Building into ${outDir} with ${reactPath}`);
+}
+
+async function loadPlugins() {
+  const { default: analyze } = await import('./analyze.mjs');
+  return [analyze];
+}
+
+module.exports = { build, loadPlugins };
@@ -0,0 +1,4 @@
+const path = require('path');
+
+exports.root = path.resolve(__dirname, '..');
+exports.outDir = path.join(exports.root, 'dist');
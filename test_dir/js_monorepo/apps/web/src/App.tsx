@@ -0,0 +1,27 @@
+import React, { Suspense, lazy, useState } from 'react';
+import { BrowserRouter } from 'react-router-dom';
+import { Button, ClickCounter, ErrorBoundary, Header, Spinner } from '@/components';
+import { formatDate } from '@acme/utils';
+import type { ButtonProps } from '@/components/Button';
+import './styles.css';
+
+const Settings = lazy(() => import('./pages/Settings'));
+
+const primary: Partial<ButtonProps> = { label: 'Save' };
+
+export function App() {
+  const [count, setCount] = useState(0);
+  return (
+    <BrowserRouter>
+      <ErrorBoundary fallback={<Spinner />}>
+        <Header title="Acme" />
+        <p>{formatDate(new Date())}</p>
+        <ClickCounter count={count} onIncrement={() => setCount(count + 1)} />
+        <Button label={`Clicked ${count}`} onClick={() => setCount(count + 1)} />
+        <Suspense fallback={<Spinner />}>
+          <Settings {...primary} />
+        </Suspense>
+      </ErrorBoundary>
+    </BrowserRouter>
+  );
+}
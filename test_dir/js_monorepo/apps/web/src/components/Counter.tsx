@@ -0,0 +1,9 @@
+import React, { memo } from 'react';
+import { Button } from './Button';
+
+export const Counter = memo(({ count, onIncrement }: { count: number; onIncrement: () => void }) => (
+  <div className="counter">
+    <span>{count}</span>
+    <Button label="+1" onClick={onIncrement} />
+  </div>
+));
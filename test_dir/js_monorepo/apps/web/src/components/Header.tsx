@@ -0,0 +1,12 @@
+import React from 'react';
+import { shout } from '@acme/utils';
+import { Button } from './Button';
+
+export default function Header({ title }: { title: string }) {
+  return (
+    <header>
+      <h1>{shout(title)}</h1>
+      <Button label="Sign in" />
+    </header>
+  );
+}
@@ -0,0 +1,9 @@
+import React from 'react';
+
+export function Icon({ name }: { name: string }) {
+  return <svg aria-label={name} />;
+}
+
+export function Spinner() {
+  return <Icon name="spinner" />;
+}
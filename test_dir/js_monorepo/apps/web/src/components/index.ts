@@ -0,0 +1,6 @@
+export { Button } from './Button';
+export { default as Header } from './Header';
+export { Counter as ClickCounter } from './Counter';
+export * from './ErrorBoundary';
+export * from './icons';
+export * from './legacy-icons';
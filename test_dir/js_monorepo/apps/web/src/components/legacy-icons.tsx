@@ -0,0 +1,6 @@
+import React from 'react';
+
+// Kept for pages not migrated to the new icon set
+export function Icon({ name }: { name: string }) {
+  return <i className={`icon-${name}`} />;
+}
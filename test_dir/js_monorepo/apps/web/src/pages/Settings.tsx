@@ -0,0 +1,11 @@
+import React from 'react';
+import { Button } from '../components/Button';
+
+export default function Settings({ label }: { label?: string }) {
+  return (
+    <section>
+      <h2>Settings</h2>
+      <Button label={label ?? 'Save'} />
+    </section>
+  );
+}
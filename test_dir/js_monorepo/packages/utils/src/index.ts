@@ -0,0 +1,7 @@
+import { formatDate } from './date';
+
+export function shout(text: string): string {
+  return text.toUpperCase();
+}
+
+export { formatDate };
@@ -0,0 +1,8 @@
+"""
+Blog package: models and services.
+"""
+
+from .models import User, Post
+from .services import PostService
+
+__all__ = ["User", "Post", "PostService"]
@@ -0,0 +1,32 @@
+"""
+Data models for the blog package.
+"""
+
+from dataclasses import dataclass, field
+from datetime import datetime
+
+
+@dataclass
+class User:
+    """A blog author."""
+    user_id: int
+    username: str
+    email: str
+
+    @property
+    def display_name(self) -> str:
+        return f"@{self.username}"
+
+
+@dataclass
+class Post:
+    """A blog post written by a user."""
+    post_id: int
+    title: str
+    body: str
+    author: User
+    tags: list[str] = field(default_factory=list)
+    created_at: datetime = field(default_factory=datetime.now)
+
+    def summary(self, length: int = 40) -> str:
+        return self.body[:length]
@@ -0,0 +1,5 @@
+"""
+Services for the blog package.
+"""
+
+from .posts import PostService
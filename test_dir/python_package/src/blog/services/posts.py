@@ -0,0 +1,26 @@
+"""
+Post publishing service.
+"""
+
+from ..models import Post, User
+from blog_plugins import markdown
+
+
+class PostService:
+    """Creates and renders posts."""
+
+    def __init__(self):
+        self.posts = {}
+
+    def publish(self, title: str, body: str, author: User) -> Post:
+        post = Post(len(self.posts) + 1, title, body, author)
+        self.posts[post.post_id] = post
+        return post
+
+    def render(self, post_id: int) -> str:
+        post = self.posts[post_id]
+        return markdown.render(post.body)
+
+    @staticmethod
+    def slugify(title: str) -> str:
+        return title.lower().replace(" ", "-")
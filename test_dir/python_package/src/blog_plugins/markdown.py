@@ -0,0 +1,10 @@
+"""
+Markdown rendering plugin. blog_plugins is a namespace package (no __init__.py).
+"""
+
+import re
+
+
+def render(text: str) -> str:
+    """Render **bold** markdown as HTML."""
+    return re.sub(r"\*\*(.+?)\*\*", r"<b>\1</b>", text)
@@ -0,0 +1,14 @@
+"""
+Tests for the post service.
+"""
+
+import blog.models
+from blog import PostService, User
+
+
+def test_publish():
+    service = PostService()
+    author = User(1, "ada", "ada@example.com")
+    post = service.publish("Hello", "**hi**", author)
+    assert isinstance(post, blog.models.Post)
+    assert service.render(post.post_id) == "<b>hi</b>"
@@ -0,0 +1,56 @@
+"""
+Typed models for the code diagram fixtures: dataclasses, properties and annotated attributes.
+"""
+
+from dataclasses import dataclass, field
+from typing import ClassVar, Optional
+
+
+@dataclass
+class Author:
+    """An article's author."""
+    
+    name: str
+    email: Optional[str] = None
+
+
+@dataclass(frozen=True)
+class Article:
+    """A published article."""
+    
+    title: str
+    author: Author
+    tags: list[str] = field(default_factory=list)
+    registry: ClassVar[dict[str, "Article"]] = {}
+    
+    @property
+    def slug(self) -> str:
+        """The article's URL slug."""
+        return self.title.lower().replace(" ", "-")
+    
+    @staticmethod
+    def from_title(title: str, author: Author) -> "Article":
+        """Create an untagged article."""
+        return Article(title, author)
+
+
+class Library:
+    """A collection of articles, indexed by slug."""
+    
+    default_size = 10
+    
+    def __init__(self, owner: Author):
+        self.owner = owner
+        self.articles: dict[str, Article] = {}
+        self.count: int = 0
+    
+    def add(self, article: Article) -> Article:
+        """Add an article to the library."""
+        self.articles[article.slug] = article
+        self.count += 1
+        return article
+    
+    @classmethod
+    def empty(cls, owner: Author) -> "Library":
+        """Create a library with no articles."""
+        return cls(owner)
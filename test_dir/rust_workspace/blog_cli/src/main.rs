@@ -0,0 +1,22 @@
+use blog_core::services::PostService;
+use blog_core::User;
+use clap::Parser;
+
+#[derive(Parser)]
+#[command(name = "blog", about = "Preview blog posts")]
+struct Args {
+    /// Author of the first post.
+    #[arg(long, default_value = "alice")]
+    author: String,
+}
+
+fn main() {
+    let args = Args::parse();
+    let mut service = PostService::new();
+    let author: User = blog_core::user!(1, &args.author);
+    service.create_post("Hello, World", "First post on the blog.", author);
+
+    for line in service.previews() {
+        println!("{}", line);
+    }
+}
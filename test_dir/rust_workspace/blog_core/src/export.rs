@@ -0,0 +1,13 @@
+//! Post export in plain text and, with the `json` feature, JSON.
+
+use crate::models::Post;
+
+pub fn to_text(post: &Post) -> String {
+    format!("{}\n\n{}", post.title, post.body)
+}
+
+#[cfg(feature = "json")]
+pub fn to_json(post: &Post) -> String {
+    use serde_json::json;
+    json!({ "title": post.title, "body": post.body }).to_string()
+}
@@ -0,0 +1,29 @@
+//! C bindings used to measure text and to expose the preview length to C callers.
+
+use std::ffi::{c_char, CStr};
+
+extern "C" {
+    fn strlen(s: *const c_char) -> usize;
+}
+
+/// Length of a NUL-terminated C string.
+///
+/// # Safety
+/// `ptr` must point to a valid NUL-terminated string.
+pub unsafe fn c_len(ptr: *const c_char) -> usize {
+    strlen(ptr)
+}
+
+pub fn byte_len(text: &CStr) -> usize {
+    // SAFETY: a CStr is always NUL-terminated
+    unsafe { c_len(text.as_ptr()) }
+}
+
+#[no_mangle]
+pub extern "C" fn blog_preview_len() -> usize {
+    crate::PREVIEW_LEN
+}
+
+pub struct RawBuffer(*mut u8);
+
+unsafe impl Send for RawBuffer {}
@@ -0,0 +1,25 @@
+//! Core types and services for the blog example.
+
+pub mod models;
+pub mod services;
+pub mod ffi;
+
+#[cfg(feature = "export")]
+pub mod export;
+
+#[path = "support/text.rs"]
+mod text;
+
+pub use models::{Post, User};
+pub use services::PostService;
+
+/// Number of characters shown in post previews.
+pub const PREVIEW_LEN: usize = 80;
+
+/// Builds a `User` whose email is derived from the username.
+#[macro_export]
+macro_rules! user {
+    ($id:expr, $name:expr) => {
+        $crate::User::new($id, $name, &format!("{}@example.com", $name))
+    };
+}
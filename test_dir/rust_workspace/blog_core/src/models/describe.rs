@@ -0,0 +1,57 @@
+use std::fmt;
+
+use super::{Post, User};
+
+/// Something that can describe itself in one line.
+pub trait Describe {
+    fn describe(&self) -> String;
+
+    fn headline(&self) -> String {
+        let text = self.describe();
+        match text.find(':') {
+            Some(end) => text[..end].to_string(),
+            None => text,
+        }
+    }
+}
+
+/// A short bracketed label, available for everything that can be described.
+pub trait Label {
+    fn label(&self) -> String;
+}
+
+impl Describe for User {
+    fn describe(&self) -> String {
+        format!("user {}: {}", self.id, self.username)
+    }
+}
+
+impl Describe for Post {
+    fn describe(&self) -> String {
+        format!("post {}: {} by {}", self.id, self.title, self.author.username)
+    }
+}
+
+impl<T: Describe> Describe for Vec<T> {
+    fn describe(&self) -> String {
+        self.iter().map(|item| item.describe()).collect::<Vec<_>>().join("; ")
+    }
+}
+
+impl<T: Describe> Label for T {
+    fn label(&self) -> String {
+        format!("[{}]", self.headline())
+    }
+}
+
+/// Wraps a describable value so it can be used with `{}`.
+pub struct Described<'a, T: ?Sized>(pub &'a T);
+
+impl<T> fmt::Display for Described<'_, T>
+where
+    T: Describe + ?Sized,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.describe())
+    }
+}
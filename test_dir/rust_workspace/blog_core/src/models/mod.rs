@@ -0,0 +1,9 @@
+//! Data model for users and posts.
+
+mod describe;
+mod post;
+mod user;
+
+pub use describe::{Describe, Described, Label};
+pub use post::{Audience, Post, Status};
+pub use user::User;
@@ -0,0 +1,47 @@
+use super::user::User;
+use crate::text::slugify;
+
+#[derive(Debug, Clone)]
+pub struct Post {
+    pub id: u32,
+    pub title: String,
+    pub body: String,
+    pub author: User,
+    pub status: Status,
+}
+
+/// Where a post is in its publishing lifecycle.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+    Draft,
+    Scheduled(u64),
+    Published { at: u64, by: User },
+}
+
+/// Who can read a post, as bit flags.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Audience {
+    Public = 1 << 0,
+    Members = 1 << 1,
+    Staff = 1 << 2,
+}
+
+impl Post {
+    pub fn new(id: u32, title: &str, body: &str, author: User) -> Self {
+        Post {
+            id,
+            title: title.to_string(),
+            body: body.to_string(),
+            author,
+            status: Status::Draft,
+        }
+    }
+
+    pub fn publish(&mut self, at: u64) {
+        self.status = Status::Published { at, by: self.author.clone() };
+    }
+
+    pub fn slug(&self) -> String {
+        slugify(&self.title)
+    }
+}
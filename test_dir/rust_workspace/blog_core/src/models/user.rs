@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct User {
+    pub id: u32,
+    pub username: String,
+    email: String,
+}
+
+impl User {
+    pub fn new(id: u32, username: &str, email: &str) -> Self {
+        User {
+            id,
+            username: username.to_string(),
+            email: email.to_string(),
+        }
+    }
+
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+}
@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+use std::ffi::CString;
+
+use crate::ffi::byte_len;
+
+use crate::models::{Post, User};
+use crate::text::{self, preview};
+
+pub struct PostService {
+    posts: HashMap<u32, Post>,
+    next_id: u32,
+}
+
+impl PostService {
+    pub fn new() -> Self {
+        PostService {
+            posts: HashMap::new(),
+            next_id: 1,
+        }
+    }
+
+    pub fn create_post(&mut self, title: &str, body: &str, author: User) -> &Post {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.posts.insert(id, Post::new(id, title, body, author));
+        &self.posts[&id]
+    }
+
+    pub fn previews(&self) -> Vec<String> {
+        self.posts
+            .values()
+            .map(|post| format!("{}: {}", text::slugify(&post.title), preview(&post.body)))
+            .collect()
+    }
+
+    pub fn total_bytes(&self) -> usize {
+        self.posts
+            .values()
+            .filter_map(|post| CString::new(post.body.as_str()).ok())
+            .map(|body| byte_len(&body))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn numbers_posts_from_one() {
+        let mut service = PostService::new();
+        let author = User::new(1, "alice", "alice@example.com");
+        assert_eq!(service.create_post("Title", "Body", author).id, 1);
+    }
+}
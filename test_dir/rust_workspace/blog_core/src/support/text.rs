@@ -0,0 +1,17 @@
+/// Turn a title into a URL-friendly slug, e.g. "Hello, World" -> "hello-world".
+pub fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.chars() {
+        if c.is_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').to_string()
+}
+
+pub fn preview(body: &str) -> &str {
+    let end = body.len().min(crate::PREVIEW_LEN);
+    &body[..end]
+}